@@ -0,0 +1,51 @@
+use crate::Versions;
+use serde::Deserialize;
+
+/// The response of Artifactory's `/api/search/versions`.
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    version: String,
+}
+
+/// Parses the versions from a version search response.
+pub(crate) fn parse_versions(input: &str) -> Result<Versions, serde_json::Error> {
+    let response = serde_json::from_str::<SearchResponse>(input)?;
+    Ok(response.results.into_iter().map(|r| r.version).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_parse_versions() {
+        let input = r#"{
+          "results": [
+            { "version": "1.1.0", "integrity": false },
+            { "version": "1.0.0", "integrity": true }
+          ]
+        }"#;
+        assert_eq!(
+            parse_versions(input).unwrap(),
+            Versions::from(vec!["1.1.0", "1.0.0"])
+        );
+    }
+
+    #[test_case("{}"; "missing results")]
+    #[test_case(r#"{"results": []}"#; "empty results")]
+    fn test_parse_no_versions(input: &str) {
+        assert_eq!(parse_versions(input).unwrap(), Versions::default());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_versions("<metadata/>").is_err());
+    }
+}
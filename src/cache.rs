@@ -0,0 +1,627 @@
+use crate::{
+    metadata::{self, Metadata},
+    resolvers::{Client, Download, Error, ErrorKind, RequestOptions, Resolver},
+    search::Query,
+    Coordinates, Versions,
+};
+use async_trait::async_trait;
+use console::style;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+use url::Url;
+
+/// Cached responses have this extension, so that nothing else in the directory is removed.
+static EXTENSION: &str = "response";
+
+/// A store for the metadata and the other small responses of the repositories, by their URL.
+///
+/// The URL of a request with credentials or headers has a hash of them as its fragment,
+/// so that their responses are not shared with requests that have other credentials, or none.
+///
+/// Implement it to keep the responses elsewhere, e.g. in Redis, and use it with a [`CachingResolver`].
+#[async_trait]
+pub trait MetadataCache: Send + Sync {
+    /// The response for the URL, unless there is none or it has expired.
+    async fn get(&self, url: &Url) -> Option<String>;
+
+    /// Stores the response, a failure does not fail the request.
+    async fn put(&self, url: &Url, body: &str) -> io::Result<()>;
+}
+
+/// Responses on disk, so that repeated runs do not download the same metadata again.
+///
+/// The CLI uses the cache in `$XDG_CACHE_HOME/latest-maven-version` (or the platform equivalent).
+/// Every response is a file that is named after the hash of its URL,
+/// and it expires when its modification time is older than the TTL.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// Responses in memory, for the lifetime of the cache.
+#[derive(Debug)]
+pub struct MemoryCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<Url, (Instant, String)>>,
+}
+
+/// Caches nothing, e.g. for `--no-cache`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoCache;
+
+/// The entries of the cache, for `cache stats`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Stats {
+    entries: usize,
+    expired: usize,
+    bytes: u64,
+}
+
+impl DiskCache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    /// The cache in the user's cache directory, if there is one.
+    pub fn in_default_dir(ttl: Duration) -> Option<Self> {
+        let mut dir = dirs::cache_dir()?;
+        dir.push(env!("CARGO_PKG_NAME"));
+        Some(Self::new(dir, ttl))
+    }
+
+    fn path(&self, url: &Url) -> PathBuf {
+        let digest = ring::digest::digest(&ring::digest::SHA256, url.as_str().as_bytes());
+        let name = digest
+            .as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        self.dir.join(name).with_extension(EXTENSION)
+    }
+
+    fn is_expired(&self, modified: SystemTime) -> bool {
+        // entries from the future are treated as expired as well
+        modified.elapsed().map_or(true, |age| age >= self.ttl)
+    }
+
+    pub(crate) fn stats(&self) -> io::Result<Stats> {
+        let mut stats = Stats::default();
+        for (_, metadata) in self.entries()? {
+            stats.entries += 1;
+            stats.bytes += metadata.len();
+            if self.is_expired(metadata.modified()?) {
+                stats.expired += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Removes all entries and returns how many there were.
+    pub(crate) fn clear(&self) -> io::Result<usize> {
+        self.remove(|_| true)
+    }
+
+    /// Removes the expired entries and returns how many there were.
+    pub(crate) fn prune(&self) -> io::Result<usize> {
+        self.remove(|modified| self.is_expired(modified))
+    }
+
+    fn remove(&self, predicate: impl Fn(SystemTime) -> bool) -> io::Result<usize> {
+        let mut removed = 0;
+        for (path, metadata) in self.entries()? {
+            if predicate(metadata.modified()?) {
+                std::fs::remove_file(path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn entries(&self) -> io::Result<Vec<(PathBuf, std::fs::Metadata)>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut files = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == EXTENSION) {
+                let metadata = entry.metadata()?;
+                if metadata.is_file() {
+                    files.push((path, metadata));
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[async_trait]
+impl MetadataCache for DiskCache {
+    async fn get(&self, url: &Url) -> Option<String> {
+        let path = self.path(url);
+        let modified = tokio::fs::metadata(&path).await.ok()?.modified().ok()?;
+        if self.is_expired(modified) {
+            return None;
+        }
+        tokio::fs::read_to_string(&path).await.ok()
+    }
+
+    /// The file is replaced at once, so that concurrent runs never read half of it.
+    async fn put(&self, url: &Url, body: &str) -> io::Result<()> {
+        let path = self.path(url);
+        let tmp = path.with_extension(format!("{}.{}", EXTENSION, std::process::id()));
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(&tmp, body).await?;
+        tokio::fs::rename(&tmp, &path).await
+    }
+}
+
+impl MemoryCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl MetadataCache for MemoryCache {
+    async fn get(&self, url: &Url) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(url) {
+            Some((stored, body)) if stored.elapsed() < self.ttl => Some(body.clone()),
+            Some(_) => {
+                entries.remove(url);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn put(&self, url: &Url, body: &str) -> io::Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(url.clone(), (Instant::now(), String::from(body)));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetadataCache for NoCache {
+    async fn get(&self, _url: &Url) -> Option<String> {
+        None
+    }
+
+    async fn put(&self, _url: &Url, _body: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Answers HTTP requests from the cache, and caches the successful responses.
+///
+/// Local files are always read again.
+pub(crate) struct CachingClient<C> {
+    client: C,
+    cache: Arc<dyn MetadataCache>,
+    /// Ignore cached responses, but still cache the new ones.
+    refresh: bool,
+}
+
+impl<C> CachingClient<C> {
+    pub(crate) fn new(client: C, cache: Arc<dyn MetadataCache>, refresh: bool) -> Self {
+        Self {
+            client,
+            cache,
+            refresh,
+        }
+    }
+}
+
+#[async_trait]
+impl<C: Client> Client for CachingClient<C> {
+    async fn request(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return self.client.request(url, options, coordinates).await;
+        }
+        let key = options.response_key(url);
+        if !self.refresh {
+            if let Some(body) = self.cache.get(&key).await {
+                return Ok(body);
+            }
+        }
+        let body = self.client.request(url, options, coordinates).await?;
+        // a failure to cache the response does not fail the request
+        let _ = self.cache.put(&key, &body).await;
+        Ok(body)
+    }
+
+    /// The parsed metadata is cached as a minimal metadata document with all the fields that the parser reads,
+    /// see [`metadata::document`].
+    async fn request_metadata(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Metadata, ErrorKind> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return self
+                .client
+                .request_metadata(url, options, coordinates)
+                .await;
+        }
+        let key = options.response_key(url);
+        if !self.refresh {
+            let cached = self.cache.get(&key).await;
+            if let Some(Ok(metadata)) = cached.as_deref().map(metadata::parse) {
+                return Ok(metadata);
+            }
+        }
+        let metadata = self
+            .client
+            .request_metadata(url, options, coordinates)
+            .await?;
+        let _ = self.cache.put(&key, &metadata::document(&metadata)).await;
+        Ok(metadata)
+    }
+
+    /// Artifacts are large and are saved by the caller anyway, so they are never cached.
+    async fn request_bytes(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        self.client.request_bytes(url, options, coordinates).await
+    }
+
+    /// Files that exist can still be removed, so their existence is never cached.
+    async fn request_exists(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<(), ErrorKind> {
+        self.client.request_exists(url, options, coordinates).await
+    }
+}
+
+/// Caches the responses of any resolver, by sending its requests through a caching client.
+///
+/// Like in the CLI, the metadata and the other small responses are cached, but not the artifacts or local files.
+pub struct CachingResolver<R> {
+    resolver: R,
+    cache: Arc<dyn MetadataCache>,
+    refresh: bool,
+}
+
+impl<R> CachingResolver<R> {
+    pub fn new(resolver: R, cache: Arc<dyn MetadataCache>) -> Self {
+        Self {
+            resolver,
+            cache,
+            refresh: false,
+        }
+    }
+
+    /// Ignores the cached responses, but still caches the new ones.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    fn client<T: Client>(&self, client: T) -> CachingClient<T> {
+        CachingClient::new(client, Arc::clone(&self.cache), self.refresh)
+    }
+}
+
+#[async_trait]
+impl<R: Resolver + Send + Sync> Resolver for CachingResolver<R> {
+    async fn resolve<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        client: &T,
+    ) -> Result<Versions, Error> {
+        let client = self.client(client);
+        self.resolver.resolve(coordinates, &client).await
+    }
+
+    async fn artifacts<T: Client>(&self, group_id: &str, client: &T) -> Result<Vec<String>, Error> {
+        let client = self.client(client);
+        self.resolver.artifacts(group_id, &client).await
+    }
+
+    async fn snapshot_build<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        client: &T,
+    ) -> Result<Option<String>, Error> {
+        let client = self.client(client);
+        self.resolver
+            .snapshot_build(coordinates, version, &client)
+            .await
+    }
+
+    async fn publication_dates<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        client: &T,
+    ) -> Result<HashMap<String, SystemTime>, Error> {
+        let client = self.client(client);
+        self.resolver.publication_dates(coordinates, &client).await
+    }
+
+    async fn version_file<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        extension: &str,
+        client: &T,
+    ) -> Result<Option<String>, Error> {
+        let client = self.client(client);
+        self.resolver
+            .version_file(coordinates, version, extension, &client)
+            .await
+    }
+
+    async fn version_file_url<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        extension: &str,
+        client: &T,
+    ) -> Result<Option<Url>, Error> {
+        let client = self.client(client);
+        self.resolver
+            .version_file_url(coordinates, version, extension, &client)
+            .await
+    }
+
+    async fn download<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        classifier: Option<&str>,
+        extension: &str,
+        client: &T,
+    ) -> Result<Option<Download>, Error> {
+        let client = self.client(client);
+        self.resolver
+            .download(coordinates, version, classifier, extension, &client)
+            .await
+    }
+
+    async fn search<T: Client>(
+        &self,
+        query: &Query,
+        client: &T,
+    ) -> Result<Option<Vec<Coordinates>>, Error> {
+        let client = self.client(client);
+        self.resolver.search(query, &client).await
+    }
+
+    fn hosts(&self) -> Vec<String> {
+        self.resolver.hosts()
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} cached responses with {} bytes, {} of them expired",
+            style(self.entries).cyan().bold(),
+            style(self.bytes).cyan(),
+            style(self.expired).yellow(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{request, CountingClient, VersionsClient},
+        resolvers::Auth,
+        UrlResolver,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use test_case::test_case;
+
+    /// The cache is created in a private directory on its first write.
+    async fn temp_cache(ttl: Duration) -> DiskCache {
+        let dir = crate::temp::private_dir().await.unwrap();
+        DiskCache::new(dir.join("cache"), ttl)
+    }
+
+    #[tokio::test]
+    async fn test_cached_response() {
+        let cache = temp_cache(Duration::from_secs(600)).await;
+        let client = CachingClient::new(CountingClient::default(), Arc::new(cache.clone()), false);
+        assert_eq!(
+            request(&client, "https://repo/a").await.unwrap(),
+            "https://repo/a #1"
+        );
+        assert_eq!(
+            request(&client, "https://repo/a").await.unwrap(),
+            "https://repo/a #1"
+        );
+        assert_eq!(
+            request(&client, "https://repo/b").await.unwrap(),
+            "https://repo/b #2"
+        );
+
+        let refresh = CachingClient::new(CountingClient::default(), Arc::new(cache.clone()), true);
+        assert_eq!(
+            request(&refresh, "https://repo/a").await.unwrap(),
+            "https://repo/a #1"
+        );
+        assert_eq!(
+            request(&client, "https://repo/a").await.unwrap(),
+            "https://repo/a #1"
+        );
+        assert_eq!(
+            cache.stats().unwrap(),
+            Stats {
+                entries: 2,
+                expired: 0,
+                bytes: 34
+            }
+        );
+        assert_eq!(cache.clear().unwrap(), 2);
+        std::fs::remove_dir_all(cache.dir().parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_expired_response() {
+        let cache = temp_cache(Duration::ZERO).await;
+        let client = CachingClient::new(CountingClient::default(), Arc::new(cache.clone()), false);
+        assert_eq!(
+            request(&client, "https://repo/a").await.unwrap(),
+            "https://repo/a #1"
+        );
+        assert_eq!(
+            request(&client, "https://repo/a").await.unwrap(),
+            "https://repo/a #2"
+        );
+        assert_eq!(cache.stats().unwrap().expired, 1);
+        assert_eq!(cache.prune().unwrap(), 1);
+        assert_eq!(cache.stats().unwrap(), Stats::default());
+        std::fs::remove_dir_all(cache.dir().parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_local_files_are_not_cached() {
+        let cache = temp_cache(Duration::from_secs(600)).await;
+        let client = CachingClient::new(CountingClient::default(), Arc::new(cache.clone()), false);
+        assert_eq!(
+            request(&client, "file:///repo/a").await.unwrap(),
+            "file:///repo/a #1"
+        );
+        assert_eq!(
+            request(&client, "file:///repo/a").await.unwrap(),
+            "file:///repo/a #2"
+        );
+        assert!(!cache.dir().exists());
+        std::fs::remove_dir_all(cache.dir().parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_credentials_are_part_of_the_key() {
+        let cache = Arc::new(MemoryCache::new(Duration::from_secs(600)));
+        let client = CachingClient::new(CountingClient::default(), cache, false);
+        let url = Url::parse("https://repo/a").unwrap();
+        let coordinates = Coordinates::new("com.foo", "bar");
+        let alice = RequestOptions {
+            auth: Some(Auth::basic("Alice", "s3cure")),
+            ..RequestOptions::default()
+        };
+        let bob = RequestOptions {
+            auth: Some(Auth::basic("Bob", "s3cure")),
+            ..RequestOptions::default()
+        };
+        let gateway = RequestOptions {
+            headers: vec![(String::from("X-Api-Key"), String::from("secret"))],
+            ..RequestOptions::default()
+        };
+        for (options, response) in [
+            (&alice, "https://repo/a #1"),
+            (&bob, "https://repo/a #2"),
+            (&gateway, "https://repo/a #3"),
+            (&RequestOptions::default(), "https://repo/a #4"),
+            (&alice, "https://repo/a #1"),
+        ] {
+            let body = client.request(&url, options, &coordinates).await.unwrap();
+            assert_eq!(body, response);
+        }
+    }
+
+    #[test_case(Arc::new(MemoryCache::new(Duration::from_secs(600))) => 1; "memory")]
+    #[test_case(Arc::new(MemoryCache::new(Duration::ZERO)) => 2; "memory expired")]
+    #[test_case(Arc::new(NoCache) => 2; "no cache")]
+    #[tokio::test]
+    async fn test_caching_resolver(cache: Arc<dyn MetadataCache>) -> usize {
+        let client = VersionsClient::new(&["1.0.0"]);
+        let resolver = CachingResolver::new(UrlResolver::new("https://repo", None).unwrap(), cache);
+        let coordinates = Coordinates::new("com.foo", "bar");
+        for _ in 0..2 {
+            let versions = resolver.resolve(&coordinates, &client).await.unwrap();
+            assert_eq!(versions, Versions::from(&["1.0.0"][..]));
+        }
+        client.requests()
+    }
+
+    #[tokio::test]
+    async fn test_cached_versions() {
+        /// Answers with a new version for each request of the metadata.
+        #[derive(Default)]
+        struct ReleasingClient {
+            client: CountingClient,
+            releases: AtomicUsize,
+        }
+
+        #[async_trait]
+        impl Client for ReleasingClient {
+            async fn request(
+                &self,
+                url: &Url,
+                options: &RequestOptions,
+                coordinates: &Coordinates,
+            ) -> Result<String, ErrorKind> {
+                self.client.request(url, options, coordinates).await
+            }
+
+            async fn request_metadata(
+                &self,
+                _url: &Url,
+                _options: &RequestOptions,
+                _coordinates: &Coordinates,
+            ) -> Result<Metadata, ErrorKind> {
+                let count = self.releases.fetch_add(1, Ordering::SeqCst) + 1;
+                Ok(Metadata {
+                    group_id: Some(String::from("com.foo")),
+                    versions: vec![String::from("1.0.0"), format!("1.0.{}", count)],
+                    ..Metadata::default()
+                })
+            }
+        }
+
+        let cache = temp_cache(Duration::from_secs(600)).await;
+        let client = CachingClient::new(ReleasingClient::default(), Arc::new(cache.clone()), false);
+        let url = Url::parse("https://repo/com/foo/bar/maven-metadata.xml").unwrap();
+        let coordinates = Coordinates::new("com.foo", "bar");
+        for _ in 0..2 {
+            let metadata = client
+                .request_metadata(&url, &RequestOptions::default(), &coordinates)
+                .await
+                .unwrap();
+            assert_eq!(metadata.group_id.as_deref(), Some("com.foo"));
+            assert_eq!(metadata.versions, vec!["1.0.0", "1.0.1"]);
+        }
+        std::fs::remove_dir_all(cache.dir().parent().unwrap()).unwrap();
+    }
+}
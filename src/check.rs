@@ -0,0 +1,376 @@
+use crate::{
+    opts::{self, parse_min_version, parse_regex},
+    parse::Error as ParseError,
+    resolvers::{Auth, Client, Error, MultiResolver, Resolver, Strategy, MAVEN_CENTRAL},
+    versions::Selection,
+    Coordinates, PublishedVersion, Requirement, UrlResolver, VersionCheck,
+};
+use futures_util::stream::{self, StreamExt};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Checks for the latest versions of coordinates, with the options of the binary that are not about the output.
+///
+/// ```no_run
+/// use latest_maven_version::{client, Auth, CheckRequest};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let request = CheckRequest::builder()
+///     .check("org.neo4j:neo4j:^4".parse()?)
+///     .resolver_with_auth("https://nexus.mycorp.com/repository/maven-public", Auth::basic("user", "pass"))
+///     .resolver("https://repo.maven.apache.org/maven2")
+///     .include_pre_releases(true)
+///     .exclude_qualifier("alpha")
+///     .build()?;
+/// for (coordinates, result) in request.run(&client()?).await {
+///     println!("{}: {:?}", coordinates, result?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CheckRequest {
+    checks: Vec<VersionCheck>,
+    resolver: MultiResolver<UrlResolver>,
+    selection: Selection,
+    jobs: usize,
+}
+
+/// The coordinates of a check with the latest version for each of its requirements.
+pub type CheckResult = (
+    Coordinates,
+    Result<Vec<(Requirement, Option<PublishedVersion>)>, Error>,
+);
+
+/// Collects the options of a [`CheckRequest`], invalid ones are reported by [`CheckRequestBuilder::build`].
+#[derive(Debug)]
+pub struct CheckRequestBuilder {
+    checks: Vec<VersionCheck>,
+    resolvers: Vec<(String, Option<Auth>)>,
+    auth: Option<Auth>,
+    merge_resolvers: bool,
+    include_pre_releases: bool,
+    exclude_qualifiers: Vec<String>,
+    ignore_versions: Vec<String>,
+    min_version: Option<String>,
+    jobs: usize,
+    max_requests_per_host: Option<usize>,
+    timeout: Option<Duration>,
+}
+
+impl CheckRequest {
+    pub fn builder() -> CheckRequestBuilder {
+        CheckRequestBuilder {
+            checks: Vec::new(),
+            resolvers: Vec::new(),
+            auth: None,
+            merge_resolvers: false,
+            include_pre_releases: false,
+            exclude_qualifiers: Vec::new(),
+            ignore_versions: Vec::new(),
+            min_version: None,
+            jobs: opts::DEFAULT_JOBS,
+            max_requests_per_host: None,
+            timeout: None,
+        }
+    }
+
+    pub fn checks(&self) -> &[VersionCheck] {
+        &self.checks
+    }
+
+    /// Runs the checks, at most the number of jobs at the same time, and returns their results in the same order.
+    pub async fn run<C: Client>(&self, client: &C) -> Vec<CheckResult> {
+        self.run_until_cancelled(client, &CancellationToken::new())
+            .await
+    }
+
+    /// Like [`CheckRequest::run`], and stops when the token is cancelled, e.g. because the caller disconnected.
+    ///
+    /// The checks that are still running are dropped with their requests, and only the results of the checks that
+    /// finished before are returned, in the order of the checks. Dropping the future of a run stops it the same way,
+    /// without any results.
+    pub async fn run_until_cancelled<C: Client>(
+        &self,
+        client: &C,
+        token: &CancellationToken,
+    ) -> Vec<CheckResult> {
+        let mut checks = stream::iter(self.checks.iter().enumerate())
+            .map(|(index, check)| async move {
+                let result =
+                    self.resolver
+                        .resolve(&check.coordinates, client)
+                        .await
+                        .map(|versions| {
+                            versions.latest_versions(&self.selection, check.versions.clone())
+                        });
+                (index, (check.coordinates.clone(), result))
+            })
+            .buffer_unordered(self.jobs);
+
+        let mut results = Vec::with_capacity(self.checks.len());
+        while !token.is_cancelled() {
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => break,
+                result = checks.next() => match result {
+                    Some(result) => results.push(result),
+                    None => break,
+                },
+            }
+        }
+        results.sort_unstable_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+impl CheckRequestBuilder {
+    /// Adds coordinates to check, e.g. `"org.neo4j:neo4j:^4".parse()?`.
+    pub fn check(mut self, check: VersionCheck) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    pub fn checks(mut self, checks: impl IntoIterator<Item = VersionCheck>) -> Self {
+        self.checks.extend(checks);
+        self
+    }
+
+    /// Adds a repository, with the credentials of [`CheckRequestBuilder::auth`], if any.
+    ///
+    /// Maven Central is used when no repository is added.
+    pub fn resolver(mut self, url: impl Into<String>) -> Self {
+        self.resolvers.push((url.into(), None));
+        self
+    }
+
+    /// Adds a repository with its own credentials.
+    pub fn resolver_with_auth(mut self, url: impl Into<String>, auth: Auth) -> Self {
+        self.resolvers.push((url.into(), Some(auth)));
+        self
+    }
+
+    /// The credentials for the repositories that were added without their own.
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Merges the versions of all repositories, instead of using the first one that knows the coordinates.
+    pub fn merge_resolvers(mut self, merge: bool) -> Self {
+        self.merge_resolvers = merge;
+        self
+    }
+
+    pub fn include_pre_releases(mut self, include: bool) -> Self {
+        self.include_pre_releases = include;
+        self
+    }
+
+    /// Never considers versions with this qualifier, e.g. `alpha` or `RC`, ignoring case.
+    pub fn exclude_qualifier(mut self, qualifier: impl Into<String>) -> Self {
+        self.exclude_qualifiers.push(qualifier.into());
+        self
+    }
+
+    /// Never considers versions that match this regular expression, e.g. `^2\.17\.0$`.
+    pub fn ignore_versions(mut self, pattern: impl Into<String>) -> Self {
+        self.ignore_versions.push(pattern.into());
+        self
+    }
+
+    /// Never considers versions that are older than this version.
+    pub fn min_version(mut self, version: impl Into<String>) -> Self {
+        self.min_version = Some(version.into());
+        self
+    }
+
+    /// How many coordinates are checked at the same time, 8 by default.
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// How many requests are sent to the same host at the same time, unlimited by default.
+    pub fn max_requests_per_host(mut self, max: usize) -> Self {
+        self.max_requests_per_host = Some(max);
+        self
+    }
+
+    /// The timeout for each request, instead of the one of the client.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn build(self) -> Result<CheckRequest, Error> {
+        if self.jobs == 0 {
+            return Err(ParseError::InvalidLimit(self.jobs.to_string()).into());
+        }
+        let ignore_versions = self
+            .ignore_versions
+            .iter()
+            .map(|pattern| parse_regex(pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+        let min_version = self
+            .min_version
+            .as_deref()
+            .map(parse_min_version)
+            .transpose()?;
+
+        let (default_auth, timeout, max_requests) =
+            (self.auth, self.timeout, self.max_requests_per_host);
+        let mut servers = self.resolvers;
+        if servers.is_empty() {
+            servers.push((String::from(MAVEN_CENTRAL), None));
+        }
+        let resolvers = servers
+            .into_iter()
+            .map(|(url, auth)| {
+                let resolver = UrlResolver::new(url, auth.or_else(|| default_auth.clone()))?;
+                Ok(resolver
+                    .with_timeout(timeout)
+                    .with_max_concurrent_requests(max_requests))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let strategy = if self.merge_resolvers {
+            Strategy::Merge
+        } else {
+            Strategy::Fallback
+        };
+
+        Ok(CheckRequest {
+            checks: self.checks,
+            resolver: MultiResolver::new(resolvers, strategy)?,
+            selection: Selection {
+                include_pre_releases: self.include_pre_releases,
+                exclude_qualifiers: self.exclude_qualifiers,
+                ignore_versions,
+                min_version,
+                ..Selection::default()
+            },
+            jobs: self.jobs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::VersionsClient,
+        resolvers::{ErrorKind, RequestOptions},
+    };
+    use async_trait::async_trait;
+    use test_case::test_case;
+    use url::Url;
+
+    #[test_case(CheckRequest::builder() => Some(String::from("1.2.0")); "defaults")]
+    #[test_case(CheckRequest::builder().include_pre_releases(true) => Some(String::from("2.0.0-beta1")); "pre releases")]
+    #[test_case(CheckRequest::builder().ignore_versions(r"^1\.2\.") => Some(String::from("1.1.0")); "ignored")]
+    #[test_case(CheckRequest::builder().include_pre_releases(true).exclude_qualifier("BETA") => Some(String::from("1.2.0")); "excluded qualifier")]
+    #[test_case(CheckRequest::builder().min_version("3.0.0") => None; "min version")]
+    #[tokio::test]
+    async fn test_run(builder: CheckRequestBuilder) -> Option<String> {
+        let request = builder
+            .check("com.foo:bar".parse().unwrap())
+            .resolver("https://repo.example.com")
+            .build()
+            .unwrap();
+        let client = VersionsClient::new(&["1.0.0", "1.1.0", "1.2.0", "2.0.0-beta1"]);
+        let mut results = request.run(&client).await;
+        assert_eq!(results.len(), 1);
+        let (coordinates, result) = results.remove(0);
+        assert_eq!(coordinates, Coordinates::new("com.foo", "bar"));
+        let (_, latest) = result.unwrap().remove(0);
+        latest.map(|latest| latest.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_run_in_order() {
+        let request = CheckRequest::builder()
+            .checks((0..20).map(|i| {
+                VersionCheck::new(Coordinates::new("com.foo", format!("bar{}", i)), Vec::new())
+            }))
+            .jobs(3)
+            .build()
+            .unwrap();
+        let results = request.run(&VersionsClient::new(&["1.0.0"])).await;
+        let artifacts = results
+            .iter()
+            .map(|(coordinates, _)| coordinates.artifact().to_string())
+            .collect::<Vec<_>>();
+        let expected = (0..20).map(|i| format!("bar{}", i)).collect::<Vec<_>>();
+        assert_eq!(artifacts, expected);
+        assert!(results
+            .into_iter()
+            .all(|(_, result)| result.unwrap()[0].1.is_some()));
+    }
+
+    /// Answers for artifacts that end with `fast` and never for the others.
+    struct StallingClient;
+
+    #[async_trait]
+    impl Client for StallingClient {
+        async fn request(
+            &self,
+            url: &Url,
+            options: &RequestOptions,
+            coordinates: &Coordinates,
+        ) -> Result<String, ErrorKind> {
+            if !coordinates.artifact().ends_with("fast") {
+                futures_util::future::pending::<()>().await;
+            }
+            VersionsClient::new(&["1.0.0"])
+                .request(url, options, coordinates)
+                .await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_until_cancelled() {
+        let request = CheckRequest::builder()
+            .checks(
+                ["a-fast", "b-slow", "c-fast", "d-slow", "e-fast"]
+                    .iter()
+                    .map(|artifact| {
+                        VersionCheck::new(Coordinates::new("com.foo", *artifact), Vec::new())
+                    }),
+            )
+            .jobs(4)
+            .build()
+            .unwrap();
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            cancel.cancel();
+        });
+        let results = request.run_until_cancelled(&StallingClient, &token).await;
+        let artifacts = results
+            .iter()
+            .map(|(coordinates, _)| coordinates.artifact())
+            .collect::<Vec<_>>();
+        assert_eq!(artifacts, ["a-fast", "c-fast", "e-fast"]);
+    }
+
+    #[tokio::test]
+    async fn test_run_already_cancelled() {
+        let request = CheckRequest::builder()
+            .check("com.foo:bar-fast".parse().unwrap())
+            .build()
+            .unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+        let results = request.run_until_cancelled(&StallingClient, &token).await;
+        assert!(results.is_empty());
+    }
+
+    #[test_case(CheckRequest::builder().jobs(0) => "invalid-input"; "no jobs")]
+    #[test_case(CheckRequest::builder().ignore_versions("(") => "invalid-input"; "invalid pattern")]
+    #[test_case(CheckRequest::builder().min_version("latest") => "invalid-input"; "invalid min version")]
+    #[test_case(CheckRequest::builder().resolver("not a url") => "invalid-resolver"; "invalid resolver")]
+    fn test_build_error(builder: CheckRequestBuilder) -> &'static str {
+        builder.build().unwrap_err().code()
+    }
+}
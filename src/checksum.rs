@@ -0,0 +1,92 @@
+use ring::digest;
+use std::fmt::{self, Display};
+
+/// The checksum algorithms of the sidecar files that repositories publish next to their files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha512,
+    Sha256,
+    Sha1,
+}
+
+impl Algorithm {
+    /// The algorithms from the strongest to the weakest, which is the order that sidecar files are tried in.
+    pub(crate) const ALL: [Algorithm; 3] = [Algorithm::Sha512, Algorithm::Sha256, Algorithm::Sha1];
+
+    /// The extension of the sidecar file, e.g. `sha1` for `bar-1.0.0.jar.sha1`.
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            Algorithm::Sha512 => "sha512",
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Sha1 => "sha1",
+        }
+    }
+
+    fn digest(self) -> &'static digest::Algorithm {
+        match self {
+            Algorithm::Sha512 => &digest::SHA512,
+            Algorithm::Sha256 => &digest::SHA256,
+            Algorithm::Sha1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        }
+    }
+}
+
+/// The expected checksum of a file, as read from its sidecar file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Checksum {
+    pub(crate) algorithm: Algorithm,
+    expected: String,
+}
+
+impl Checksum {
+    /// Parses a sidecar file, which has the checksum in hex, sometimes followed by the file name.
+    pub(crate) fn parse(algorithm: Algorithm, sidecar: &str) -> Option<Self> {
+        let expected = sidecar.split_whitespace().next()?.to_ascii_lowercase();
+        let length = algorithm.digest().output_len * 2;
+        let is_hex = expected.bytes().all(|b| b.is_ascii_hexdigit());
+        (expected.len() == length && is_hex).then_some(Self {
+            algorithm,
+            expected,
+        })
+    }
+
+    pub(crate) fn matches(&self, content: &[u8]) -> bool {
+        hex(digest::digest(self.algorithm.digest(), content).as_ref()) == self.expected
+    }
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Algorithm::Sha512 => "SHA-512",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha1 => "SHA-1",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Algorithm::Sha1, "a9993e364706816aba3e25717850c26c9cd0d89d" => true; "sha1")]
+    #[test_case(Algorithm::Sha1, "A9993E364706816ABA3E25717850C26C9CD0D89D  abc.jar\n" => true; "with file name")]
+    #[test_case(Algorithm::Sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad" => true; "sha256")]
+    #[test_case(Algorithm::Sha1, "0000000000000000000000000000000000000000" => false; "mismatch")]
+    fn test_matches(algorithm: Algorithm, sidecar: &str) -> bool {
+        Checksum::parse(algorithm, sidecar).unwrap().matches(b"abc")
+    }
+
+    #[test_case(Algorithm::Sha1, "" ; "empty")]
+    #[test_case(Algorithm::Sha1, "a9993e36" ; "too short")]
+    #[test_case(Algorithm::Sha256, "a9993e364706816aba3e25717850c26c9cd0d89d" ; "wrong algorithm")]
+    #[test_case(Algorithm::Sha1, "<html>not found</html>" ; "not a checksum")]
+    fn test_invalid_sidecar(algorithm: Algorithm, sidecar: &str) {
+        assert_eq!(Checksum::parse(algorithm, sidecar), None);
+    }
+}
@@ -0,0 +1,2078 @@
+use crate::{
+    cache::{CachingClient, DiskCache},
+    compare::Comparison,
+    config::{self, ConfigFile, CoordinatesConfig},
+    deps_dev::Insights,
+    diff::{Changes, Structure},
+    gradle::Module,
+    http::ApiClient,
+    init::Prompt,
+    java, kerberos,
+    license::{Policy, Verdict},
+    mock_repo::{self, MockRepo},
+    opts::{self, CacheCommand, Command, ConfigCommand},
+    osv::Vulnerability,
+    pom::{Details, Pom},
+    presets,
+    progress::Progress,
+    release_notes::ReleaseNotes,
+    resolvers::{
+        self, Client, ClientOptions, Download, MultiResolver, Resolver, RoutingResolver, Strategy,
+        UrlResolver, EXEC_PREFIX, MAVEN_CENTRAL,
+    },
+    search::{close_matches, Query},
+    settings::Settings,
+    signature::{Gpg, Signer, Signers, Trust},
+    sigstore::{self, Cosign, Status},
+    tree::{Poms, Tree},
+    validate,
+    vcr::VcrClient,
+    versions::{Bump, PublishedVersion, Requirement, Selection, TagCheck, Versions},
+    Config, Coordinates, Server, VersionCheck,
+};
+use color_eyre::eyre::{bail, Result};
+use console::{style, Term};
+use futures_util::future::join;
+use std::{
+    net::SocketAddr,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime},
+};
+use tokio::{net::TcpListener, sync::Semaphore};
+use url::Url;
+
+/// Runs the command line interface of the `latest-maven-version` binary.
+pub async fn main() -> Result<()> {
+    if Term::stdout().features().is_attended() {
+        color_eyre::config::HookBuilder::default()
+            .display_env_section(false)
+            .install()?
+    }
+
+    let mut opts = opts::Opts::new();
+    let command = opts.take_command();
+    if let Some(Command::Cache(command)) = command {
+        return run_cache(command, opts.cache_dir());
+    }
+    if let Some(Command::MockRepo { source, listen }) = &command {
+        return run_mock_repo(source, *listen).await;
+    }
+    if let Some(Command::Config(command)) = command {
+        return run_config(command, opts.config_file());
+    }
+    if let Some(Command::Init { force }) = command {
+        return run_init(opts.config_file(), force);
+    }
+
+    let config_file = ConfigFile::load(opts.config_file())?;
+    let profiles = opts
+        .profiles()
+        .iter()
+        .map(|name| config_file.profile(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ConfigFile {
+        routes,
+        resolvers: aliases,
+        pool,
+        versions,
+        run: run_config,
+        coordinates,
+        ..
+    } = config_file;
+    let settings = match opts.maven_settings_file() {
+        Some(path) => Settings::read(&path)?,
+        None => Settings::default(),
+    };
+
+    let client_options = ClientOptions {
+        proxy: opts.proxy(settings.proxy())?,
+        forward_auth_cross_origin: opts.forward_auth_cross_origin(),
+        max_redirects: opts.max_redirects(),
+        verbose: opts.verbose(),
+        timeout: opts.timeout(),
+        connect_timeout: opts.connect_timeout(),
+        user_agent: opts.user_agent().map(String::from),
+        headers: opts.headers().to_vec(),
+        ca_certs: opts.ca_cert_files().to_vec(),
+        ip_family: opts.ip_family(),
+        resolve: opts.resolve().to_vec(),
+        dns_cache: opts.dns_cache(),
+        http2_prior_knowledge: opts.http2_prior_knowledge(),
+        pool_idle_timeout: opts.pool_idle_timeout().or_else(|| pool.idle_timeout()),
+        pool_max_idle_per_host: opts
+            .pool_max_idle_per_host()
+            .or_else(|| pool.max_idle_per_host()),
+        tcp_keepalive: opts.tcp_keepalive().or_else(|| pool.keepalive()),
+        max_response_size: opts.max_response_size(),
+    };
+    let api = ApiClient::new(&client_options)?;
+
+    let mut config = opts.config(&api);
+    config
+        .exclude_qualifiers
+        .extend(versions.exclude_qualifiers);
+    config.ignore_versions.extend(versions.ignore_versions);
+    config.coordinates = coordinates;
+    // the options of a profile come after the ones of the config file, so that they win
+    for profile in &profiles {
+        if let Some(include_pre_releases) = profile.include_pre_releases {
+            config
+                .coordinates
+                .extend(profile.checks.iter().map(|check| {
+                    let options = CoordinatesConfig {
+                        include_pre_releases: Some(include_pre_releases),
+                        ..CoordinatesConfig::default()
+                    };
+                    (check.coordinates.clone(), options)
+                }));
+        }
+    }
+    config.license_policy = opts.license_policy_file().map(Policy::read).transpose()?;
+    let config = Arc::new(config);
+    let apply_settings = |server| settings.apply(server, MAVEN_CENTRAL);
+    let local_repository = settings.local_repository();
+    let retry = opts.retry();
+    let verbose = opts.verbose();
+    let strict = opts.strict();
+    let max_requests_per_host = opts.max_requests_per_host();
+    let progress = (opts.progress() && Term::stderr().is_term())
+        .then(|| Arc::new(ProgressLine::default()) as Arc<dyn Progress>);
+    let url_resolver = |server: Server| -> Result<UrlResolver> {
+        let alias = aliases.get(&server.url);
+        let snapshots = alias
+            .filter(|_| config.matches_snapshots())
+            .and_then(|alias| alias.apply_snapshots(&server));
+        let server = match alias {
+            Some(alias) => alias.apply(server),
+            None => server,
+        };
+        let negotiate = alias.is_some_and(|a| a.negotiate());
+        let resolver = |mut server: Server| -> Result<UrlResolver> {
+            if negotiate && server.auth.is_none() {
+                server.auth = Some(kerberos::negotiate()?);
+            }
+            // only the user's own resolvers get here, repositories of poms are never executables
+            let resolver = match server.url.strip_prefix(EXEC_PREFIX) {
+                Some(program) => UrlResolver::exec(program, server.auth)?,
+                None => {
+                    let server = presets::expand(server, local_repository.as_deref(), &api)?;
+                    let Server { url, auth, .. } = apply_settings(server);
+                    UrlResolver::new(url, auth)?
+                }
+            };
+            let resolver = resolver
+                .with_directory_listing(config.directory_listing)
+                .with_timeout(alias.and_then(|a| a.timeout()))
+                .with_retry(Some(alias.map_or(retry, |a| a.retry(retry))))
+                .with_verbose(verbose)
+                .with_strict(strict)
+                .with_require_checksums(config.require_checksums)
+                .with_headers(alias.map(|a| a.headers()).unwrap_or_default())
+                .with_auth_on_challenge(alias.is_some_and(|a| a.auth_on_challenge()))
+                .with_max_concurrent_requests(
+                    alias
+                        .and_then(|a| a.max_concurrent_requests())
+                        .or(max_requests_per_host),
+                );
+            Ok(match &progress {
+                Some(progress) => resolver.with_progress(Arc::clone(progress)),
+                None => resolver,
+            })
+        };
+        let snapshots = snapshots.map(resolver).transpose()?;
+        Ok(resolver(server)?.with_snapshots(snapshots))
+    };
+
+    let cache = opts.cache();
+    let refresh = opts.refresh();
+    let vcr = opts.vcr();
+
+    if let Some(Command::CompareResolvers { coordinates }) = &command {
+        let resolvers = opts
+            .resolver_servers(&run_config.resolvers)
+            .into_iter()
+            .map(|server| Ok((server.url.clone(), url_resolver(server)?)))
+            .collect::<Result<Vec<_>>>()?;
+        if resolvers.len() < 2 {
+            bail!(
+                "Comparing needs at least two resolvers, please provide them with {}",
+                style("--resolver").cyan()
+            );
+        }
+        let hosts = resolvers.iter().flat_map(|(_, r)| r.hosts()).collect();
+        let client = resolvers::client(client_options.cache_dns(hosts).await)?;
+        let client = VcrClient::new(CachingClient::new(client, cache, refresh), vcr);
+        let mut consistent = true;
+        for coordinates in coordinates {
+            let comparison = Comparison::run(coordinates.clone(), &resolvers, &client).await;
+            consistent &= comparison.is_consistent();
+            println!("{}", comparison);
+        }
+        if !consistent {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let resolvers = opts
+        .resolver_servers(&run_config.resolvers)
+        .into_iter()
+        .map(url_resolver)
+        .collect::<Result<Vec<_>>>()?;
+    let strategy = if config.merge_resolvers {
+        Strategy::Merge
+    } else {
+        Strategy::Fallback
+    };
+    let resolver = MultiResolver::new(resolvers, strategy)?;
+
+    let routes = routes
+        .into_iter()
+        .map(|(prefix, url)| {
+            let resolver = url_resolver(Server {
+                id: None,
+                url,
+                auth: None,
+            })?;
+            Ok((
+                prefix,
+                MultiResolver::new(vec![resolver], Strategy::Fallback)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut resolver = RoutingResolver::new(routes, resolver);
+    for (coordinates, coordinates_config) in &config.coordinates {
+        if let Some(url) = &coordinates_config.resolver {
+            let coordinates_resolver = url_resolver(Server {
+                id: None,
+                url: url.clone(),
+                auth: None,
+            })?;
+            resolver.add_coordinates(
+                coordinates.clone(),
+                MultiResolver::new(vec![coordinates_resolver], Strategy::Fallback)?,
+            );
+        }
+    }
+
+    for profile in &profiles {
+        if profile.resolvers.is_empty() {
+            continue;
+        }
+        let profile_resolvers = profile
+            .resolvers
+            .iter()
+            .map(|url| {
+                url_resolver(Server {
+                    id: None,
+                    url: url.clone(),
+                    auth: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let profile_resolver = MultiResolver::new(profile_resolvers, strategy)?;
+        for check in &profile.checks {
+            resolver.add_coordinates(check.coordinates.clone(), profile_resolver.clone());
+        }
+    }
+
+    let mut checks = Vec::new();
+    for path in opts.pom_files() {
+        let pom = Pom::read(path)?;
+        for repo in pom.repositories.iter().filter(|repo| !repo.is_http()) {
+            eprintln!(
+                "Ignoring the repository {} of {}, only http and https repositories are used from poms",
+                style(&repo.url).yellow(),
+                style(path.display()).cyan()
+            );
+        }
+        let repositories = pom
+            .repositories
+            .iter()
+            .filter(|repo| repo.is_http())
+            .map(|repo| {
+                url_resolver(Server {
+                    id: Some(repo.id.clone()),
+                    url: repo.url.clone(),
+                    auth: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for coordinates in &pom.dependencies {
+            if !repositories.is_empty() {
+                let pom_resolver = resolver.route(coordinates).clone();
+                let pom_resolver = pom_resolver.prepend(repositories.clone());
+                resolver.add_coordinates(coordinates.clone(), pom_resolver);
+            }
+            let current = pom.version(coordinates).and_then(PublishedVersion::parse);
+            checks.push(VersionCheck {
+                coordinates: coordinates.clone(),
+                versions: Vec::new(),
+                current,
+                parent: false,
+            });
+        }
+        if let Some((coordinates, version)) = &pom.parent {
+            // the modules of a project share their parent
+            if checks
+                .iter()
+                .any(|c| c.parent && c.coordinates == *coordinates)
+            {
+                continue;
+            }
+            if !repositories.is_empty() {
+                let pom_resolver = resolver.route(coordinates).clone();
+                let pom_resolver = pom_resolver.prepend(repositories.clone());
+                resolver.add_coordinates(coordinates.clone(), pom_resolver);
+            }
+            checks.push(VersionCheck {
+                coordinates: coordinates.clone(),
+                versions: Vec::new(),
+                current: PublishedVersion::parse(version),
+                parent: true,
+            });
+        }
+    }
+
+    let from_command_line =
+        !checks.is_empty() || !opts.pom_files().is_empty() || !profiles.is_empty();
+    checks.extend(opts.into_version_checks());
+    checks.extend(profiles.into_iter().flat_map(|profile| profile.checks));
+    if !from_command_line && checks.is_empty() {
+        checks = run_config.checks;
+    }
+
+    let client = resolvers::client(client_options.cache_dns(resolver.hosts()).await)?;
+    let client = VcrClient::new(CachingClient::new(client, cache, refresh), vcr);
+
+    if let Some(Command::List {
+        version_checks,
+        limit,
+        reverse,
+    }) = command
+    {
+        return run_list(&resolver, &client, &config, version_checks, limit, reverse).await;
+    }
+    if let Some(Command::Count { version_checks }) = command {
+        return run_count(&resolver, &client, &config, version_checks).await;
+    }
+    if let Some(Command::Compare {
+        coordinates,
+        from,
+        to,
+    }) = command
+    {
+        return run_compare(&resolver, &client, coordinates, &from, &to).await;
+    }
+    if let Some(Command::Search {
+        query,
+        class,
+        limit,
+    }) = command
+    {
+        let query = Query {
+            name: query,
+            class,
+            limit,
+        };
+        return run_search(&resolver, &client, &config, &query).await;
+    }
+    if let Some(Command::Deps {
+        version_checks,
+        depth,
+    }) = command
+    {
+        return run_deps(&resolver, &client, &config, version_checks, depth).await;
+    }
+    if let Some(Command::Download {
+        version_checks,
+        dir,
+        packaging,
+        classifier,
+    }) = command
+    {
+        let file = (packaging.as_str(), classifier.as_deref());
+        return run_download(&resolver, &client, &config, version_checks, &dir, file).await;
+    }
+
+    let results = run(
+        resolver,
+        client,
+        Arc::clone(&config),
+        checks,
+        progress.clone(),
+    )
+    .await;
+    if progress.is_some() {
+        let _ = Term::stderr().clear_line();
+    }
+    let results = results?;
+
+    let mut signers = match &config.gpg {
+        Some(gpg) if gpg.trusts_on_first_use() => Some(Signers::in_default_dir()?),
+        _ => None,
+    };
+    let mut parent_updates = Vec::new();
+    let mut outdated = false;
+    let mut untrusted = false;
+    let mut unverified = false;
+    let mut unlicensed = false;
+    let mut insecure = false;
+    for CheckResult {
+        coordinates,
+        versions,
+        tags,
+        last_updated,
+        age,
+        builds,
+        released,
+        stale,
+        current,
+        parent,
+        updates,
+        skipped,
+        modules,
+        dropped_variants,
+        missing_files,
+        urls,
+        details,
+        release_notes,
+        insights,
+        vulnerabilities,
+        too_new,
+        avoided,
+        denied,
+        disallowed,
+        relicensed,
+        signatures,
+        attestations,
+    } in results
+    {
+        let now = SystemTime::now();
+        if let Some(fail_on_update) = config.fail_on_update {
+            outdated |= updates.iter().any(|(_, bump)| *bump >= fail_on_update);
+        }
+        println!(
+            "Latest version(s) for {}{}:{}:",
+            if parent { "the parent pom " } else { "" },
+            style(&coordinates.group_id).magenta(),
+            style(&coordinates.artifact).blue()
+        );
+        if let (true, Some(current)) = (parent, &current) {
+            for (latest, bump) in &updates {
+                parent_updates.push((coordinates.clone(), current.clone(), latest.clone(), *bump));
+            }
+        }
+
+        for (req, latest) in versions {
+            let skipped_java = too_new
+                .iter()
+                .find(|(r, _)| *r == req)
+                .map(|(_, skipped)| skipped);
+            if let Some(latest) = latest {
+                let build = builds
+                    .iter()
+                    .find(|(version, _)| *version == latest)
+                    .map_or_else(String::new, |(_, build)| {
+                        format!(" ({})", style(build).green())
+                    });
+                let release_age = released
+                    .iter()
+                    .find(|(version, _)| *version == latest)
+                    .map_or_else(String::new, |(_, published)| {
+                        let age = now.duration_since(*published).unwrap_or_default();
+                        format!(", released {}", style(format_age(age)).cyan())
+                    });
+                let update = match (&current, updates.iter().find(|(v, _)| *v == latest)) {
+                    (Some(current), Some((_, bump))) => format!(
+                        ", a {} update from {}",
+                        style(bump).yellow().bold(),
+                        style(current).cyan()
+                    ),
+                    _ => String::new(),
+                };
+                let url = urls
+                    .iter()
+                    .find(|(version, _)| *version == latest)
+                    .map_or_else(String::new, |(_, url)| {
+                        format!(", at {}", style(url).cyan())
+                    });
+                println!(
+                    "Latest version matching {}: {}{}{}{}{}",
+                    style(req).cyan().bold(),
+                    style(&latest).green().bold(),
+                    build,
+                    release_age,
+                    update,
+                    url
+                );
+                print_too_new(skipped_java, config.max_java);
+                if let Some((_, between)) = skipped.iter().find(|(v, _)| *v == latest) {
+                    if !between.is_empty() {
+                        println!(
+                            "The update skips {} version(s): {}",
+                            style(between.len()).yellow().bold(),
+                            between
+                                .iter()
+                                .map(|v| style(v).cyan().to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        );
+                    }
+                }
+                if let Some((_, module)) = modules.iter().find(|(v, _)| *v == latest) {
+                    println!(
+                        "The variants of {} are {}",
+                        style(&latest).green(),
+                        module
+                            .variants
+                            .iter()
+                            .map(|v| style(v.describe(&latest.original)).cyan().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                }
+                if let Some((_, dropped)) = dropped_variants.iter().find(|(v, _)| *v == latest) {
+                    println!(
+                        "The update drops the variant(s) {} of the current version",
+                        style(dropped.join(", ")).red().bold()
+                    );
+                }
+                if let Some((_, missing)) = missing_files.iter().find(|(v, _)| *v == latest) {
+                    println!(
+                        "The files {} of {} are missing, the version might be a broken upload",
+                        style(missing.join(", ")).red().bold(),
+                        style(&latest).yellow()
+                    );
+                }
+                if let Some((_, details)) = details.iter().find(|(v, _)| *v == latest) {
+                    print_details(&latest, details);
+                }
+                if let Some((_, insights)) = insights.iter().find(|(v, _)| *v == latest) {
+                    print_insights(&latest, insights);
+                }
+                if let Some((_, newer)) = avoided.iter().find(|(v, _)| *v == latest) {
+                    let newer = newer.iter().map(ToString::to_string).collect::<Vec<_>>();
+                    match config.deny_vulnerable {
+                        Some(severity) => println!(
+                            "Skipped {} with known vulnerabilities of {} severity or above",
+                            style(newer.join(", ")).red(),
+                            style(severity).red()
+                        ),
+                        None => println!(
+                            "Skipped {} with known vulnerabilities",
+                            style(newer.join(", ")).red()
+                        ),
+                    }
+                }
+                if let Some(severity) = config.deny_vulnerable.filter(|_| denied.contains(&latest))
+                {
+                    insecure = true;
+                    println!(
+                        "Every matching version has known vulnerabilities of {} severity or above",
+                        style(severity).red().bold()
+                    );
+                }
+                if let Some((_, newer)) = relicensed.iter().find(|(v, _)| *v == latest) {
+                    let newer = newer.iter().map(ToString::to_string).collect::<Vec<_>>();
+                    println!(
+                        "Skipped {} with licenses that are not allowed",
+                        style(newer.join(", ")).red()
+                    );
+                }
+                match disallowed.iter().find(|(v, _)| *v == latest) {
+                    Some((_, Verdict::Undeclared)) => {
+                        unlicensed = true;
+                        println!(
+                            "{} declares no license, which the license policy does not allow",
+                            style(&latest).red().bold()
+                        );
+                    }
+                    Some((_, Verdict::Disallowed(licenses))) => {
+                        unlicensed = true;
+                        println!(
+                            "{} is licensed under {}, which the license policy does not allow",
+                            style(&latest).red().bold(),
+                            style(licenses.join(", ")).red()
+                        );
+                    }
+                    _ => {}
+                }
+                match vulnerabilities.iter().find(|(v, _)| *v == latest) {
+                    Some((_, found)) if found.is_empty() => {
+                        println!("{} has no known vulnerabilities", style(&latest).green())
+                    }
+                    Some((_, found)) => {
+                        println!("{} has known vulnerabilities:", style(&latest).red().bold());
+                        for vulnerability in found {
+                            match &vulnerability.summary {
+                                Some(summary) => println!(
+                                    "  {}{}: {}",
+                                    style(vulnerability.name()).red(),
+                                    severity(vulnerability),
+                                    summary
+                                ),
+                                None => println!(
+                                    "  {}{}",
+                                    style(vulnerability.name()).red(),
+                                    severity(vulnerability)
+                                ),
+                            }
+                        }
+                    }
+                    None => {}
+                }
+                if let Some((_, notes)) = release_notes.iter().find(|(v, _)| *v == latest) {
+                    println!("Release notes: {}", style(&notes.release).cyan());
+                    if let Some(compare) = &notes.compare {
+                        println!("Changes: {}", style(compare).cyan());
+                    }
+                }
+                match signatures.iter().find(|(v, _)| *v == latest) {
+                    Some((_, Some(signer))) => {
+                        let trust = signers
+                            .as_mut()
+                            .map_or(Trust::Known, |signers| signers.trust(&coordinates, signer));
+                        let signed_by = format!(
+                            "{} ({})",
+                            style(&signer.user_id).green(),
+                            style(&signer.fingerprint).cyan()
+                        );
+                        match trust {
+                            Trust::Known => {
+                                println!("{} is signed by {}", style(&latest).green(), signed_by)
+                            }
+                            Trust::FirstUse => println!(
+                                "{} is signed by {}, which is trusted on first use",
+                                style(&latest).green(),
+                                signed_by
+                            ),
+                            Trust::Changed(previous) => {
+                                untrusted = true;
+                                println!(
+                                    "{} is signed by {}, but earlier versions were signed by {}",
+                                    style(&latest).red().bold(),
+                                    signed_by,
+                                    style(previous).red().bold()
+                                );
+                            }
+                        }
+                    }
+                    Some((_, None)) => {
+                        untrusted = true;
+                        println!("{} is not signed", style(&latest).red().bold());
+                    }
+                    None => {}
+                }
+                match attestations.iter().find(|(v, _)| *v == latest) {
+                    Some((_, Status::Verified(attested))) => {
+                        let issuer = attested
+                            .issuer
+                            .as_ref()
+                            .map(|issuer| format!(" ({})", style(issuer).cyan()))
+                            .unwrap_or_default();
+                        match &attested.provenance {
+                            Some(provenance) => println!(
+                                "{} has {} provenance signed by {}{}{}",
+                                style(&latest).green(),
+                                style(&provenance.predicate_type).cyan(),
+                                style(&attested.identity).green(),
+                                issuer,
+                                provenance
+                                    .builder
+                                    .as_ref()
+                                    .map(|builder| format!(", built by {}", style(builder).cyan()))
+                                    .unwrap_or_default()
+                            ),
+                            None => println!(
+                                "{} has a Sigstore bundle signed by {}{}",
+                                style(&latest).green(),
+                                style(&attested.identity).green(),
+                                issuer
+                            ),
+                        }
+                        let unchecked = config
+                            .sigstore
+                            .as_ref()
+                            .map(Cosign::unchecked)
+                            .unwrap_or_default();
+                        if !unchecked.is_empty() {
+                            println!(
+                                "The {} of {} {} not checked, use {} to only accept the expected signer",
+                                unchecked
+                                    .iter()
+                                    .map(|(what, _)| *what)
+                                    .collect::<Vec<_>>()
+                                    .join(" and "),
+                                style(&latest).yellow().bold(),
+                                if unchecked.len() == 1 { "was" } else { "were" },
+                                unchecked
+                                    .iter()
+                                    .map(|(_, option)| style(option).cyan().to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(" and ")
+                            );
+                        }
+                    }
+                    Some((_, Status::Unavailable)) => {
+                        println!("{} has no Sigstore bundle", style(&latest).yellow().bold())
+                    }
+                    Some((_, Status::Invalid(e))) => {
+                        unverified = true;
+                        println!(
+                            "The Sigstore bundle of {} cannot be verified: {}",
+                            style(&latest).red().bold(),
+                            e
+                        );
+                    }
+                    None => {}
+                }
+            } else {
+                println!("No version matching {}", style(req).yellow().bold());
+                print_too_new(skipped_java, config.max_java);
+            }
+        }
+
+        if let Some((newest, age)) = stale {
+            println!(
+                "The newest version {} was released {}, the artifact might be abandoned",
+                style(newest).yellow().bold(),
+                style(format_age(age)).yellow().bold()
+            );
+        }
+        if config.use_release_tag {
+            print_tags(&tags);
+        }
+        if config.use_release_tag || config.max_metadata_age.is_some() {
+            if let (Some(last_updated), Some(age)) = (last_updated, age) {
+                println!(
+                    "The metadata was last updated at {}, {}",
+                    style(format_timestamp(&last_updated)).cyan(),
+                    style(format_age(age)).cyan().bold()
+                );
+            }
+        }
+    }
+
+    if !parent_updates.is_empty() {
+        println!("The parent poms can be updated, which also updates the versions they manage:");
+        for (coordinates, current, latest, bump) in parent_updates {
+            println!(
+                "{}:{} from {} to {}, a {} update",
+                style(&coordinates.group_id).magenta(),
+                style(&coordinates.artifact).blue(),
+                style(current).cyan(),
+                style(latest).green().bold(),
+                style(bump).yellow().bold()
+            );
+        }
+    }
+    if let Some(signers) = &signers {
+        if let (Err(e), Some(path)) = (signers.save(), signers.path()) {
+            eprintln!(
+                "Could not remember the signers in {}: {}",
+                style(path.display()).yellow(),
+                e
+            );
+        }
+    }
+    if outdated || untrusted || unverified || unlicensed || insecure {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Prints the versions that were skipped because they need a newer Java than `--max-java`.
+fn print_too_new(skipped: Option<&Vec<(PublishedVersion, u64)>>, max_java: Option<u64>) {
+    if let (Some(skipped), Some(max_java)) = (skipped, max_java) {
+        let skipped = skipped
+            .iter()
+            .map(|(version, java)| format!("{} (Java {})", version, java))
+            .collect::<Vec<_>>();
+        println!(
+            "Skipped {}, which need a newer Java than {}",
+            style(skipped.join(", ")).red(),
+            style(max_java).cyan()
+        );
+    }
+}
+
+/// The severity of the vulnerability in parentheses, if it is known.
+fn severity(vulnerability: &Vulnerability) -> String {
+    vulnerability
+        .severity()
+        .map_or_else(String::new, |severity| format!(" ({})", severity))
+}
+
+/// Prints what the pom of the version tells about the project, leaving out what it does not have.
+fn print_details(version: &PublishedVersion, details: &Details) {
+    let about = match (&details.name, &details.description) {
+        (Some(name), Some(description)) => Some(format!("{}: {}", name, description)),
+        (name, description) => name.clone().or_else(|| description.clone()),
+    };
+    if let Some(about) = about {
+        println!("{} is {}", style(version).green(), style(about).cyan());
+    }
+    if !details.licenses.is_empty() {
+        println!(
+            "{} is licensed under {}",
+            style(version).green(),
+            style(details.licenses.join(", ")).yellow().bold()
+        );
+    }
+    if let Some(url) = &details.url {
+        println!("Project: {}", style(url).cyan());
+    }
+    if let Some(scm) = &details.scm {
+        println!("Source: {}", style(scm).cyan());
+    }
+}
+
+/// Prints what deps.dev knows about the version.
+fn print_insights(version: &PublishedVersion, insights: &Insights) {
+    if let Some(dependents) = insights.dependents {
+        println!(
+            "{} has {} dependents, {} of them direct",
+            style(version).green(),
+            style(dependents.dependent_count).cyan().bold(),
+            style(dependents.direct_dependent_count).cyan()
+        );
+    }
+    if let Some(scorecard) = &insights.scorecard {
+        let score = format!("{:.1}/10", scorecard.score);
+        let score = if scorecard.score < 5.0 {
+            style(score).yellow().bold()
+        } else {
+            style(score).green().bold()
+        };
+        println!(
+            "The OpenSSF Scorecard of {} is {}",
+            style(&scorecard.project).cyan(),
+            score
+        );
+    }
+    for advisory in &insights.advisories {
+        println!(
+            "{} is affected by {}: {}",
+            style(version).red().bold(),
+            style(advisory.name()).red(),
+            advisory.title
+        );
+    }
+}
+
+/// Prints the tags, and the newest version in case they do not match it.
+fn print_tags(tags: &[TagCheck]) {
+    for tag in tags {
+        match &tag.newest {
+            Some(newest) if tag.is_outdated() => println!(
+                "The {} tag is {}, but the newest version is {}",
+                style(tag.tag).cyan().bold(),
+                style(&tag.value).yellow().bold(),
+                style(newest).green().bold()
+            ),
+            _ => println!(
+                "The {} tag is {}",
+                style(tag.tag).cyan().bold(),
+                style(&tag.value).green().bold()
+            ),
+        }
+    }
+}
+
+/// Formats a timestamp like `20200827153717` as `2020-08-27 15:37:17`, other values are kept as they are.
+fn format_timestamp(timestamp: &str) -> String {
+    if timestamp.len() != 14 || !timestamp.bytes().all(|b| b.is_ascii_digit()) {
+        return String::from(timestamp);
+    }
+    format!(
+        "{}-{}-{} {}:{}:{}",
+        &timestamp[0..4],
+        &timestamp[4..6],
+        &timestamp[6..8],
+        &timestamp[8..10],
+        &timestamp[10..12],
+        &timestamp[12..14]
+    )
+}
+
+/// Formats the age like `3 days ago`, in the largest unit that fits.
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (value, unit) = match secs {
+        0..=59 => return String::from("just now"),
+        60..=3599 => (secs / 60, "minute"),
+        3600..=86399 => (secs / 3600, "hour"),
+        // up to two months in days, as months and years have no fixed length
+        86400..=5183999 => (secs / 86400, "day"),
+        5184000..=31535999 => (secs / 2592000, "month"),
+        _ => (secs / 31536000, "year"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    format!("{} {}{} ago", value, unit, plural)
+}
+
+fn run_cache(command: CacheCommand, cache: Option<DiskCache>) -> Result<()> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => bail!("There is no cache directory for the current user"),
+    };
+    match command {
+        CacheCommand::Stats => println!(
+            "{} in {}",
+            cache.stats()?,
+            style(cache.dir().display()).cyan()
+        ),
+        CacheCommand::Clear => println!(
+            "Removed {} cached responses",
+            style(cache.clear()?).cyan().bold()
+        ),
+        CacheCommand::Prune => println!(
+            "Removed {} expired responses",
+            style(cache.prune()?).cyan().bold()
+        ),
+    }
+    Ok(())
+}
+
+fn run_config(command: ConfigCommand, path: Option<&Path>) -> Result<()> {
+    let path = match path.map(Path::to_path_buf).or_else(config::default_path) {
+        Some(path) => path,
+        None => bail!(
+            "There is no config directory for the current user, please provide a file with {}",
+            style("--config").cyan()
+        ),
+    };
+    match command {
+        ConfigCommand::Validate => {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => bail!(
+                    "Could not read the config file {}: {}",
+                    style(path.display()).red().bold(),
+                    e
+                ),
+            };
+            let http = ApiClient::new(&ClientOptions::default())?;
+            let problems = validate::validate(&content, &http);
+            if problems.is_empty() {
+                println!("No problems in {}", style(path.display()).cyan());
+                return Ok(());
+            }
+            for problem in &problems {
+                let location = match problem.line {
+                    Some(line) => format!("{}:{}", path.display(), line),
+                    None => path.display().to_string(),
+                };
+                println!("{}: {}", style(location).red().bold(), problem.message);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_init(path: Option<&Path>, force: bool) -> Result<()> {
+    let path = match path.map(Path::to_path_buf).or_else(config::default_path) {
+        Some(path) => path,
+        None => bail!(
+            "There is no config directory for the current user, please provide a file with {}",
+            style("--config").cyan()
+        ),
+    };
+    if path.exists() && !force {
+        bail!(
+            "The config file {} exists already, overwrite it with {}",
+            style(path.display()).cyan(),
+            style("--force").cyan()
+        );
+    }
+    let pom_path = Path::new("pom.xml");
+    let pom = match pom_path.is_file() {
+        true => Some(Pom::read(pom_path)?),
+        false => None,
+    };
+
+    let stdin = std::io::stdin();
+    let init = Prompt::new(stdin.lock(), std::io::stderr())
+        .ask(pom.as_ref().map(|pom| (pom_path, pom)))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    init.write(&path)?;
+    eprintln!(
+        "Wrote {}, check its coordinates with {}",
+        style(path.display()).cyan(),
+        style(format!("latest-maven-version --config {}", path.display()))
+            .cyan()
+            .bold()
+    );
+    Ok(())
+}
+
+async fn run_mock_repo(source: &Path, listen: SocketAddr) -> Result<()> {
+    let repo = MockRepo::load(source)?;
+    let listener = TcpListener::bind(listen).await?;
+    eprintln!(
+        "Serving {} on {}",
+        style(source.display()).cyan(),
+        style(format!("http://{}/", listener.local_addr()?))
+            .cyan()
+            .bold()
+    );
+    mock_repo::serve(repo, listener).await?;
+    Ok(())
+}
+
+async fn run<R, C>(
+    resolver: R,
+    client: C,
+    config: Arc<Config>,
+    checks: Vec<VersionCheck>,
+    progress: Option<Arc<dyn Progress>>,
+) -> Result<Vec<CheckResult>>
+where
+    R: Resolver + Send + Sync + 'static,
+    C: Client + Send + Sync + 'static,
+{
+    let resolver = Arc::new(resolver);
+    let client = Arc::new(client);
+
+    let checks = discover_artifacts(&*resolver, &*client, checks).await?;
+
+    let jobs = Arc::new(Semaphore::new(config.jobs));
+    let tasks = checks
+        .into_iter()
+        .map(|(check, discovered)| {
+            let resolver = Arc::clone(&resolver);
+            let client = Arc::clone(&client);
+            let config = Arc::clone(&config);
+            let jobs = Arc::clone(&jobs);
+            let progress = progress.clone();
+            tokio::spawn(async move {
+                let _job = jobs
+                    .acquire_owned()
+                    .await
+                    .expect("the semaphore is never closed");
+                let coordinates = check.coordinates.clone();
+                if let Some(progress) = &progress {
+                    progress.on_check_started(&coordinates);
+                }
+                let result = run_check(resolver, client, config, check, discovered).await;
+                if let Some(progress) = &progress {
+                    progress.on_check_finished(&coordinates);
+                }
+                result
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        if let Some(result) = task.await?? {
+            results.push(result);
+        }
+    }
+    Ok(results)
+}
+
+/// Prints all versions of the coordinates that match their requirements, newest first.
+async fn run_list(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    config: &Config,
+    checks: Vec<VersionCheck>,
+    limit: Option<usize>,
+    reverse: bool,
+) -> Result<()> {
+    for VersionCheck {
+        coordinates,
+        versions,
+        ..
+    } in checks
+    {
+        let selection = config.selection(&coordinates);
+        let all_versions = resolve_selected(resolver, client, &coordinates, &selection).await?;
+        let mut matching = all_versions.matching(&versions, &selection);
+        matching.truncate(limit.unwrap_or(usize::MAX));
+        if reverse {
+            matching.reverse();
+        }
+
+        println!(
+            "Versions of {}:{}:",
+            style(&coordinates.group_id).magenta(),
+            style(&coordinates.artifact).blue()
+        );
+        for version in matching {
+            println!("{}", style(version).green());
+        }
+    }
+    Ok(())
+}
+
+/// Prints how many versions of the coordinates match their requirements, stable and pre releases alike.
+async fn run_count(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    config: &Config,
+    checks: Vec<VersionCheck>,
+) -> Result<()> {
+    for VersionCheck {
+        coordinates,
+        versions,
+        ..
+    } in checks
+    {
+        let selection = Selection {
+            include_pre_releases: true,
+            ..config.selection(&coordinates)
+        };
+        let all_versions = resolve_selected(resolver, client, &coordinates, &selection).await?;
+        let matching = all_versions.matching(&versions, &selection);
+        let pre_releases = matching
+            .iter()
+            .filter(|version| version.is_pre_release(selection.order))
+            .count();
+        println!(
+            "{}:{}: {} versions, {} stable, {} pre releases",
+            style(&coordinates.group_id).magenta(),
+            style(&coordinates.artifact).blue(),
+            style(matching.len()).cyan().bold(),
+            style(matching.len() - pre_releases).green(),
+            style(pre_releases).yellow()
+        );
+    }
+    Ok(())
+}
+
+/// Prints the structural changes between the two versions of the coordinates.
+async fn run_compare(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    coordinates: Coordinates,
+    from: &str,
+    to: &str,
+) -> Result<()> {
+    let mut poms = Poms::new(resolver, client);
+    let mut structures = Vec::with_capacity(2);
+    for version in [from, to] {
+        let effective = match poms.effective(&coordinates, version).await? {
+            Some(effective) => effective,
+            None => bail!(
+                "The version {} of {}:{} has no pom, or it cannot be parsed",
+                style(version).red().bold(),
+                style(&coordinates.group_id).cyan(),
+                style(&coordinates.artifact).cyan()
+            ),
+        };
+        let module = resolver
+            .version_file(&coordinates, version, "module", client)
+            .await?
+            .and_then(|file| Module::parse(&file).ok());
+        structures.push(Structure::of(version, &effective, module.as_ref()));
+    }
+    let new = structures.remove(1);
+    let old = structures.remove(0);
+    print!("{}", Changes::new(coordinates, old, new));
+    Ok(())
+}
+
+/// Prints the coordinates that the resolvers find for the query, with their newest version.
+async fn run_search(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    config: &Config,
+    query: &Query,
+) -> Result<()> {
+    let found = match resolver.search(query, client).await? {
+        Some(found) => found,
+        None => bail!(
+            "None of the resolvers can be searched, only Maven Central and Artifactory have a search"
+        ),
+    };
+    if found.is_empty() {
+        println!("Nothing was found for {}", style(&query.name).cyan());
+        return Ok(());
+    }
+    for coordinates in found {
+        let selection = config.selection(&coordinates);
+        // the search index can know artifacts that the repository does not list
+        let latest = resolve_selected(resolver, client, &coordinates, &selection)
+            .await
+            .ok()
+            .and_then(|versions| versions.newest_selected(&selection));
+        let latest = match latest {
+            Some(latest) => style(latest.original).cyan().bold(),
+            None => style(String::from("no version")).dim(),
+        };
+        println!(
+            "{}:{}: {}",
+            style(&coordinates.group_id).magenta(),
+            style(&coordinates.artifact).blue(),
+            latest
+        );
+    }
+    Ok(())
+}
+
+/// Prints the dependency tree of the newest matching version of the coordinates.
+async fn run_deps(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    config: &Config,
+    checks: Vec<VersionCheck>,
+    depth: usize,
+) -> Result<()> {
+    for VersionCheck {
+        coordinates,
+        versions,
+        ..
+    } in checks
+    {
+        let selection = config.selection(&coordinates);
+        let all_versions = resolve_selected(resolver, client, &coordinates, &selection).await?;
+        let version = match all_versions
+            .matching(&versions, &selection)
+            .into_iter()
+            .next()
+        {
+            Some(version) => version,
+            None => bail!(
+                "No version of {}:{} matches, there are no dependencies to list",
+                style(&coordinates.group_id).red().bold(),
+                style(&coordinates.artifact).red().bold()
+            ),
+        };
+        let tree = Tree::resolve(resolver, client, &coordinates, &version.original, depth).await?;
+        print!("{}", tree);
+    }
+    Ok(())
+}
+
+/// Downloads the file with the extension and classifier of the newest matching version of the coordinates.
+async fn run_download(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    config: &Config,
+    checks: Vec<VersionCheck>,
+    dir: &Path,
+    (extension, classifier): (&str, Option<&str>),
+) -> Result<()> {
+    for VersionCheck {
+        coordinates,
+        versions,
+        ..
+    } in checks
+    {
+        let selection = config.selection(&coordinates);
+        let all_versions = resolve_selected(resolver, client, &coordinates, &selection).await?;
+        let version = match all_versions
+            .matching(&versions, &selection)
+            .into_iter()
+            .next()
+        {
+            Some(version) => version,
+            None => bail!(
+                "No version of {}:{} matches, there is nothing to download",
+                style(&coordinates.group_id).red().bold(),
+                style(&coordinates.artifact).red().bold()
+            ),
+        };
+        let download = resolver
+            .download(
+                &coordinates,
+                &version.original,
+                classifier,
+                extension,
+                client,
+            )
+            .await?;
+        let Download {
+            url,
+            content,
+            checksum,
+        } = match download {
+            Some(download) => download,
+            None => bail!(
+                "The version {} of {}:{} has no {} file{}",
+                style(&version).red().bold(),
+                style(&coordinates.group_id).cyan(),
+                style(&coordinates.artifact).cyan(),
+                style(extension).cyan(),
+                classifier.map_or_else(String::new, |classifier| format!(
+                    " with the classifier {}",
+                    style(classifier).cyan()
+                ))
+            ),
+        };
+        let verified = match &checksum {
+            Some(checksum) if checksum.matches(&content) => {
+                format!(
+                    ", the {} checksum matches",
+                    style(checksum.algorithm).green()
+                )
+            }
+            Some(checksum) => bail!(
+                "The {} checksum of {} does not match, the file was not saved",
+                style(checksum.algorithm).red().bold(),
+                style(&url).red().bold()
+            ),
+            None if config.require_checksums => bail!(
+                "The repository has no checksum for {}, which {} does not allow, the file was not saved",
+                style(&url).red().bold(),
+                style("--require-checksums").cyan()
+            ),
+            None => format!(
+                ", {}",
+                style("the repository has no checksum for it").yellow()
+            ),
+        };
+        let file_name = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default();
+        tokio::fs::create_dir_all(dir).await?;
+        let path = dir.join(file_name);
+        tokio::fs::write(&path, &content).await?;
+        println!(
+            "Downloaded {} of {}:{} to {}{}",
+            style(&version).green().bold(),
+            style(&coordinates.group_id).magenta(),
+            style(&coordinates.artifact).blue(),
+            style(path.display()).cyan(),
+            verified
+        );
+    }
+    Ok(())
+}
+
+/// Resolves the versions of the coordinates, with their publication dates if the selection needs them.
+async fn resolve_selected(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    coordinates: &Coordinates,
+    selection: &Selection,
+) -> Result<Versions> {
+    let versions = match resolver.resolve(coordinates, client).await {
+        Ok(versions) => versions,
+        Err(e) => return Err(suggest(resolver, client, coordinates, e).await.into()),
+    };
+    if !selection.needs_publication_dates() {
+        return Ok(versions);
+    }
+    let dates = resolver.publication_dates(coordinates, client).await?;
+    Ok(versions.with_publication_dates(dates))
+}
+
+/// Adds the coordinates that might have been meant to the error if the coordinates could not be found.
+///
+/// The candidates are the other artifacts of the group, and what the search finds for the artifact.
+/// Suggestions are a best effort, any error while looking for them is ignored.
+async fn suggest(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    coordinates: &Coordinates,
+    error: resolvers::Error,
+) -> resolvers::Error {
+    if !error.is_not_found() {
+        return error;
+    }
+    let query = Query {
+        name: coordinates.artifact.clone(),
+        class: false,
+        limit: 20,
+    };
+    let (artifacts, found) = join(
+        resolver.artifacts(&coordinates.group_id, client),
+        resolver.search(&query, client),
+    )
+    .await;
+    let artifacts = artifacts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|artifact| Coordinates {
+            group_id: coordinates.group_id.clone(),
+            artifact,
+        });
+    let found = found.ok().flatten().unwrap_or_default();
+    error.with_suggestions(close_matches(coordinates, artifacts.chain(found)))
+}
+
+/// Replaces checks for all artifacts of a group, e.g. `org.neo4j.gds:*`, with a check for each artifact.
+///
+/// The returned flag marks the discovered artifacts.
+async fn discover_artifacts(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    checks: Vec<VersionCheck>,
+) -> Result<Vec<(VersionCheck, bool)>> {
+    let mut expanded = Vec::with_capacity(checks.len());
+    for check in checks {
+        if !check.coordinates.is_any_artifact() {
+            expanded.push((check, false));
+            continue;
+        }
+        let group_id = &check.coordinates.group_id;
+        let artifacts = resolver.artifacts(group_id, client).await?;
+        if artifacts.is_empty() {
+            bail!(
+                "Could not find any artifacts in the group {}, the resolvers might not support listing their directories",
+                style(group_id).red().bold()
+            );
+        }
+        for artifact in artifacts {
+            let coordinates = Coordinates {
+                group_id: group_id.clone(),
+                artifact,
+            };
+            let versions = check.versions.clone();
+            expanded.push((
+                VersionCheck {
+                    coordinates,
+                    versions,
+                    current: check.current.clone(),
+                    parent: check.parent,
+                },
+                true,
+            ));
+        }
+    }
+    Ok(expanded)
+}
+
+/// Discovered artifacts that cannot be found are skipped, as they are most likely subgroups.
+///
+/// Metadata that was last updated longer ago than `--max-metadata-age` fails the check.
+async fn run_check(
+    resolver: Arc<impl Resolver>,
+    client: Arc<impl Client>,
+    config: Arc<Config>,
+    check: VersionCheck,
+    discovered: bool,
+) -> Result<Option<CheckResult>> {
+    let VersionCheck {
+        coordinates,
+        versions,
+        current,
+        parent,
+    } = check;
+
+    let mut all_versions = match resolver.resolve(&coordinates, &*client).await {
+        Ok(versions) => versions,
+        // the parent of a module is often the unpublished root of its project
+        Err(e) if (discovered || parent) && e.is_not_found() => return Ok(None),
+        Err(e) => return Err(suggest(&*resolver, &*client, &coordinates, e).await.into()),
+    };
+    let age = all_versions.tags().age(SystemTime::now());
+    if let (Some(age), Some(max_age)) = (age, config.max_metadata_age) {
+        if age > max_age {
+            bail!(
+                "The metadata of {}:{} was last updated {}, which is longer ago than {} allows.\nThe resolver might be a mirror that stopped syncing.",
+                style(&coordinates.group_id).red().bold(),
+                style(&coordinates.artifact).red().bold(),
+                format_age(age),
+                style("--max-metadata-age").cyan(),
+            );
+        }
+    }
+    let selection = config.selection(&coordinates);
+    if selection.needs_publication_dates() || config.release_age || config.flag_stale.is_some() {
+        let dates = resolver.publication_dates(&coordinates, &*client).await?;
+        all_versions = all_versions.with_publication_dates(dates);
+    }
+    if config.strict_versions {
+        let unparseable = all_versions.unparseable(&selection);
+        if !unparseable.is_empty() {
+            bail!(
+                "The versions {} of {}:{} cannot be parsed and are never matched.\nUse {} to skip them, or run without {} to ignore them silently.",
+                style(unparseable.join(", ")).red().bold(),
+                style(&coordinates.group_id).cyan(),
+                style(&coordinates.artifact).cyan(),
+                style("--ignore-version").cyan(),
+                style("--strict-versions").cyan(),
+            );
+        }
+    }
+    let mut versions = all_versions.latest_versions(&selection, versions);
+    let mut poms = Poms::new(&*resolver, &*client);
+    let mut too_new = Vec::new();
+    if let Some(max_java) = config.max_java {
+        for (requirement, latest) in versions.iter_mut() {
+            let newest = match latest.take() {
+                Some(newest) => newest,
+                None => continue,
+            };
+            let matching = all_versions.matching(std::slice::from_ref(requirement), &selection);
+            let mut skipped = Vec::new();
+            for version in matching.into_iter().skip_while(|v| *v != newest) {
+                match java_release(&mut poms, &*resolver, &*client, &coordinates, &version).await? {
+                    Some(java) if java > max_java => skipped.push((version, java)),
+                    _ => {
+                        *latest = Some(version);
+                        break;
+                    }
+                }
+            }
+            if !skipped.is_empty() {
+                too_new.push((requirement.clone(), skipped));
+            }
+        }
+    }
+    let mut avoided = Vec::new();
+    let mut denied = Vec::new();
+    let avoids = config.avoid_vulnerable || config.deny_vulnerable.is_some();
+    if let Some(osv) = config.osv.as_ref().filter(|_| avoids) {
+        for (requirement, latest) in versions.iter_mut() {
+            if latest.is_none() {
+                continue;
+            }
+            // the latest version might already have skipped newer versions that need a newer Java
+            let matching = all_versions
+                .matching(std::slice::from_ref(requirement), &selection)
+                .into_iter()
+                .skip_while(|v| Some(v) != latest.as_ref())
+                .collect::<Vec<_>>();
+            let originals = matching
+                .iter()
+                .map(|version| version.original.as_str())
+                .collect::<Vec<_>>();
+            let vulnerable = match config.deny_vulnerable {
+                Some(severity) => osv
+                    .affecting(&coordinates, &originals)
+                    .await
+                    .map(|affecting| {
+                        affecting
+                            .iter()
+                            .map(|found| found.iter().any(|v| v.is_at_least(severity)))
+                            .collect()
+                    }),
+                None => osv.are_vulnerable(&coordinates, &originals).await,
+            };
+            let vulnerable = match vulnerable {
+                Ok(vulnerable) => vulnerable,
+                Err(e) => bail!("Could not look up the known vulnerabilities: {}", e),
+            };
+            // the latest version is kept if every version is vulnerable, so that it is reported
+            match vulnerable.iter().position(|vulnerable| !vulnerable) {
+                Some(safe) if safe > 0 => {
+                    avoided.push((matching[safe].clone(), matching[..safe].to_vec()));
+                    *latest = Some(matching[safe].clone());
+                }
+                Some(_) => {}
+                None if config.deny_vulnerable.is_some() => denied.extend(latest.clone()),
+                None => {}
+            }
+        }
+    }
+    let mut disallowed = Vec::new();
+    let mut relicensed = Vec::new();
+    if let Some(policy) = &config.license_policy {
+        for (requirement, latest) in versions.iter_mut() {
+            let newest = match latest {
+                Some(newest) => newest.clone(),
+                None => continue,
+            };
+            let verdict = license_verdict(&mut poms, policy, &coordinates, &newest).await?;
+            if verdict == Verdict::Allowed {
+                continue;
+            }
+            let mut replaced = false;
+            if config.avoid_disallowed_licenses {
+                let matching = all_versions.matching(std::slice::from_ref(requirement), &selection);
+                let mut skipped = vec![newest.clone()];
+                // the latest version might already have skipped newer versions with known vulnerabilities
+                for older in matching.into_iter().skip_while(|v| *v != newest).skip(1) {
+                    if license_verdict(&mut poms, policy, &coordinates, &older).await?
+                        == Verdict::Allowed
+                    {
+                        relicensed.push((older.clone(), skipped));
+                        *latest = Some(older);
+                        replaced = true;
+                        break;
+                    }
+                    skipped.push(older);
+                }
+            }
+            if !replaced {
+                disallowed.push((newest, verdict));
+            }
+        }
+    }
+    let mut builds = Vec::new();
+    if config.resolve_snapshots {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            if latest.is_snapshot() {
+                if let Some(build) = resolver
+                    .snapshot_build(&coordinates, &latest.original, &*client)
+                    .await?
+                {
+                    builds.push((latest.clone(), build));
+                }
+            }
+        }
+    }
+    let mut released = Vec::new();
+    if config.release_age {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            if let Some(published) = all_versions.published(&latest.original) {
+                released.push((latest.clone(), published));
+            }
+        }
+    }
+    let stale = config.flag_stale.and_then(|max_age| {
+        let newest = all_versions.newest_selected(&selection)?;
+        let published = all_versions.published(&newest.original)?;
+        let age = SystemTime::now().duration_since(published).ok()?;
+        Some((newest, age)).filter(|_| age > max_age)
+    });
+    let updates = current.as_ref().map_or_else(Vec::new, |current| {
+        versions
+            .iter()
+            .filter_map(|(_, latest)| {
+                let latest = latest.as_ref()?;
+                Some((latest.clone(), current.bump_to(latest, selection.order)?))
+            })
+            .collect()
+    });
+    let skipped = match &current {
+        Some(current) if config.show_skipped => updates
+            .iter()
+            .map(|(latest, _)| {
+                let between = all_versions.between(current, latest, &selection);
+                (latest.clone(), between)
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let mut modules = Vec::new();
+    let mut dropped_variants = Vec::new();
+    if config.variants {
+        let current_module = match &current {
+            Some(current) => module(&*resolver, &*client, &coordinates, current).await?,
+            None => None,
+        };
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            if let Some(module) = module(&*resolver, &*client, &coordinates, latest).await? {
+                if let Some(current_module) = &current_module {
+                    let dropped = current_module.dropped_variants(&module);
+                    if !dropped.is_empty() {
+                        let dropped = dropped.into_iter().map(String::from).collect();
+                        dropped_variants.push((latest.clone(), dropped));
+                    }
+                }
+                modules.push((latest.clone(), module));
+            }
+        }
+    }
+    let mut missing_files = Vec::new();
+    if config.verify_artifact {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            let missing = self::missing_files(&*resolver, &*client, &coordinates, latest).await?;
+            if !missing.is_empty() {
+                missing_files.push((latest.clone(), missing));
+            }
+        }
+    }
+    let mut urls = Vec::new();
+    if config.with_url {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            if let Some(url) = artifact_url(&*resolver, &*client, &coordinates, latest).await? {
+                urls.push((latest.clone(), url));
+            }
+        }
+    }
+    let mut details = Vec::new();
+    let mut release_notes = Vec::new();
+    if config.details || config.release_notes {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            if let Some(pom) = pom(&*resolver, &*client, &coordinates, latest).await? {
+                if config.release_notes {
+                    // only an update has changes to compare
+                    let current = current
+                        .as_ref()
+                        .filter(|_| updates.iter().any(|(v, _)| v == latest));
+                    let current = current.map(|current| current.original.as_str());
+                    if let Some(notes) =
+                        ReleaseNotes::guess(&pom.details, &latest.original, current)
+                    {
+                        release_notes.push((latest.clone(), notes));
+                    }
+                }
+                if config.details {
+                    details.push((latest.clone(), pom.details));
+                }
+            }
+        }
+    }
+    let mut insights = Vec::new();
+    if let Some(deps_dev) = &config.deps_dev {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            match deps_dev.insights(&coordinates, &latest.original).await {
+                Ok(Some(found)) => insights.push((latest.clone(), found)),
+                Ok(None) => {}
+                Err(e) => bail!("Could not look up the version on deps.dev: {}", e),
+            }
+        }
+    }
+    let mut vulnerabilities = Vec::new();
+    if let Some(osv) = &config.osv {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            let found = match osv.vulnerabilities(&coordinates, &latest.original).await {
+                Ok(found) => found,
+                Err(e) => bail!("Could not look up the known vulnerabilities: {}", e),
+            };
+            vulnerabilities.push((latest.clone(), found));
+        }
+    }
+    let mut signatures = Vec::new();
+    if let Some(gpg) = &config.gpg {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            let signer = signature(&*resolver, &*client, gpg, &coordinates, latest).await?;
+            signatures.push((latest.clone(), signer));
+        }
+    }
+    let mut attestations = Vec::new();
+    if let Some(cosign) = &config.sigstore {
+        for latest in versions.iter().filter_map(|(_, latest)| latest.as_ref()) {
+            let status = attestation(&*resolver, &*client, cosign, &coordinates, latest).await?;
+            attestations.push((latest.clone(), status));
+        }
+    }
+    Ok(Some(CheckResult {
+        coordinates,
+        versions,
+        tags: all_versions.check_tags(),
+        last_updated: all_versions.tags().last_updated.clone(),
+        age,
+        builds,
+        released,
+        stale,
+        current,
+        parent,
+        updates,
+        skipped,
+        modules,
+        dropped_variants,
+        missing_files,
+        urls,
+        details,
+        release_notes,
+        insights,
+        vulnerabilities,
+        too_new,
+        avoided,
+        denied,
+        disallowed,
+        relicensed,
+        signatures,
+        attestations,
+    }))
+}
+
+/// The Java release that the version targets, as its pom, its parents, or its Gradle module tell.
+async fn java_release(
+    poms: &mut Poms<'_, impl Resolver, impl Client>,
+    resolver: &impl Resolver,
+    client: &impl Client,
+    coordinates: &Coordinates,
+    version: &PublishedVersion,
+) -> Result<Option<u64>> {
+    let effective = match poms.inherited(coordinates, &version.original).await? {
+        Some(effective) => effective,
+        None => return Ok(None),
+    };
+    let module = resolver
+        .version_file(coordinates, &version.original, "module", client)
+        .await?
+        .and_then(|file| Module::parse(&file).ok());
+    Ok(java::target(&effective, module.as_ref()).and_then(|java| java::release(&java)))
+}
+
+/// Checks the licenses that the pom of the version declares or inherits.
+///
+/// A version without a pom declares no license.
+async fn license_verdict(
+    poms: &mut Poms<'_, impl Resolver, impl Client>,
+    policy: &Policy,
+    coordinates: &Coordinates,
+    version: &PublishedVersion,
+) -> Result<Verdict> {
+    let licenses = poms
+        .inherited(coordinates, &version.original)
+        .await?
+        .map(|effective| effective.licenses)
+        .unwrap_or_default();
+    Ok(policy.check(&licenses))
+}
+
+/// Verifies the signature of the jar of the version, or of its pom if it has no jar.
+async fn signature(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    gpg: &Gpg,
+    coordinates: &Coordinates,
+    version: &PublishedVersion,
+) -> Result<Option<Signer>> {
+    for extension in ["jar", "pom"] {
+        let download = resolver
+            .download(coordinates, &version.original, None, extension, client)
+            .await?;
+        let content = match download {
+            Some(download) => download.content,
+            None => continue,
+        };
+        let signature = resolver
+            .version_file(
+                coordinates,
+                &version.original,
+                &format!("{}.asc", extension),
+                client,
+            )
+            .await?;
+        return match signature {
+            Some(signature) => match gpg.verify(&content, &signature).await {
+                Ok(signer) => Ok(Some(signer)),
+                Err(e) => bail!(
+                    "The signature of {} of {}:{} cannot be trusted.\n{}",
+                    style(version).red().bold(),
+                    style(&coordinates.group_id).cyan(),
+                    style(&coordinates.artifact).cyan(),
+                    e
+                ),
+            },
+            None => Ok(None),
+        };
+    }
+    Ok(None)
+}
+
+/// Verifies the Sigstore bundle of the jar of the version, or of its pom if it has no jar.
+async fn attestation(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    cosign: &Cosign,
+    coordinates: &Coordinates,
+    version: &PublishedVersion,
+) -> Result<Status> {
+    for extension in ["jar", "pom"] {
+        let download = resolver
+            .download(coordinates, &version.original, None, extension, client)
+            .await?;
+        let content = match download {
+            Some(download) => download.content,
+            None => continue,
+        };
+        for bundle_extension in sigstore::BUNDLE_EXTENSIONS {
+            let bundle = resolver
+                .version_file(
+                    coordinates,
+                    &version.original,
+                    &format!("{}.{}", extension, bundle_extension),
+                    client,
+                )
+                .await?;
+            if let Some(bundle) = bundle {
+                return match cosign.verify(&content, &bundle).await {
+                    Ok(attested) => Ok(Status::Verified(attested)),
+                    Err(e @ sigstore::Error::NotStarted(_)) => {
+                        bail!("Could not verify the Sigstore bundle: {}", e)
+                    }
+                    Err(e) => Ok(Status::Invalid(e)),
+                };
+            }
+        }
+        return Ok(Status::Unavailable);
+    }
+    Ok(Status::Unavailable)
+}
+
+/// The URL of the jar of the version, or of its pom if it has no jar.
+async fn artifact_url(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    coordinates: &Coordinates,
+    version: &PublishedVersion,
+) -> Result<Option<Url>> {
+    for extension in ["jar", "pom"] {
+        let url = resolver
+            .version_file_url(coordinates, &version.original, extension, client)
+            .await?;
+        if url.is_some() {
+            return Ok(url);
+        }
+    }
+    Ok(None)
+}
+
+/// Reads the pom of the version, if the repository has it.
+async fn pom(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    coordinates: &Coordinates,
+    version: &PublishedVersion,
+) -> Result<Option<Pom>> {
+    let file = resolver
+        .version_file(coordinates, &version.original, "pom", client)
+        .await?;
+    match file.as_deref().map(Pom::parse).transpose() {
+        Ok(pom) => Ok(pom),
+        Err(e) => bail!(
+            "The pom of {} of {}:{} cannot be parsed: {}",
+            style(version).red().bold(),
+            style(&coordinates.group_id).cyan(),
+            style(&coordinates.artifact).cyan(),
+            e
+        ),
+    }
+}
+
+/// The names of the pom and the artifact of the version that the repository does not have.
+async fn missing_files(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    coordinates: &Coordinates,
+    version: &PublishedVersion,
+) -> Result<Vec<String>> {
+    let file_name = |extension: &str| {
+        format!(
+            "{}-{}.{}",
+            coordinates.artifact, version.original, extension
+        )
+    };
+    let pom = match pom(resolver, client, coordinates, version).await? {
+        Some(pom) => pom,
+        None => return Ok(vec![file_name("pom")]),
+    };
+    let extension = match pom.artifact_extension() {
+        Some(extension) => extension,
+        None => return Ok(Vec::new()),
+    };
+    let url = resolver
+        .version_file_url(coordinates, &version.original, extension, client)
+        .await?;
+    Ok(match url {
+        Some(_) => Vec::new(),
+        None => vec![file_name(extension)],
+    })
+}
+
+/// Reads the Gradle Module Metadata of the version, if it was published with one.
+async fn module(
+    resolver: &impl Resolver,
+    client: &impl Client,
+    coordinates: &Coordinates,
+    version: &PublishedVersion,
+) -> Result<Option<Module>> {
+    let file = resolver
+        .version_file(coordinates, &version.original, "module", client)
+        .await?;
+    Ok(file.map(|file| Module::parse(&file)).transpose()?)
+}
+
+/// Shows the running and finished checks and the sent requests on stderr, with `--progress`.
+#[derive(Default)]
+struct ProgressLine {
+    running: AtomicUsize,
+    finished: AtomicUsize,
+    requests: AtomicUsize,
+    retries: AtomicUsize,
+    /// Only one thread draws the line at a time.
+    term: Mutex<()>,
+}
+
+impl ProgressLine {
+    fn draw(&self) {
+        let _term = self.term.lock().unwrap();
+        let term = Term::stderr();
+        let _ = term.clear_line();
+        let _ = term.write_str(&format!(
+            "{} {} running, {} done, {} requests, {} retries",
+            style("Checking:").cyan().bold(),
+            self.running.load(Ordering::Relaxed),
+            self.finished.load(Ordering::Relaxed),
+            self.requests.load(Ordering::Relaxed),
+            self.retries.load(Ordering::Relaxed),
+        ));
+    }
+}
+
+impl Progress for ProgressLine {
+    fn on_check_started(&self, _coordinates: &Coordinates) {
+        self.running.fetch_add(1, Ordering::Relaxed);
+        self.draw();
+    }
+
+    fn on_request(&self, _url: &Url) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.draw();
+    }
+
+    fn on_retry(&self, _url: &Url, _attempt: u32, _delay: Duration) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+        self.draw();
+    }
+
+    fn on_check_finished(&self, _coordinates: &Coordinates) {
+        self.running.fetch_sub(1, Ordering::Relaxed);
+        self.finished.fetch_add(1, Ordering::Relaxed);
+        self.draw();
+    }
+}
+
+#[derive(Debug)]
+struct CheckResult {
+    coordinates: Coordinates,
+    versions: Vec<(Requirement, Option<PublishedVersion>)>,
+    tags: Vec<TagCheck>,
+    last_updated: Option<String>,
+    age: Option<Duration>,
+    /// The timestamped builds of the matching snapshots, with `--resolve-snapshots`.
+    builds: Vec<(PublishedVersion, String)>,
+    /// When the latest versions were published, with `--release-age`.
+    released: Vec<(PublishedVersion, SystemTime)>,
+    /// The newest version and how long ago it was published, if that is longer than `--flag-stale` allows.
+    stale: Option<(PublishedVersion, Duration)>,
+    current: Option<PublishedVersion>,
+    /// Whether the coordinates are the parent of a pom from `--pom`, whose updates are reported separately.
+    parent: bool,
+    /// The latest versions that are newer than the current version, and how big the update is.
+    updates: Vec<(PublishedVersion, Bump)>,
+    /// The versions between the current and the latest versions, with `--show-skipped`.
+    skipped: Vec<(PublishedVersion, Vec<PublishedVersion>)>,
+    /// The Gradle Module Metadata of the latest versions, with `--variants`.
+    modules: Vec<(PublishedVersion, Module)>,
+    /// The variants of the current version that the latest versions do not have, with `--variants`.
+    dropped_variants: Vec<(PublishedVersion, Vec<String>)>,
+    /// The files of the latest versions that are missing in the repository, with `--verify-artifact`.
+    missing_files: Vec<(PublishedVersion, Vec<String>)>,
+    /// Where the artifacts of the latest versions can be downloaded, with `--with-url`.
+    urls: Vec<(PublishedVersion, Url)>,
+    /// What the poms of the latest versions tell about the project, with `--details`.
+    details: Vec<(PublishedVersion, Details)>,
+    /// The links to the releases of the latest versions, with `--release-notes`.
+    release_notes: Vec<(PublishedVersion, ReleaseNotes)>,
+    /// What deps.dev knows about the latest versions, with `--deps-dev`.
+    insights: Vec<(PublishedVersion, Insights)>,
+    /// The known vulnerabilities of the latest versions, with `--audit`.
+    vulnerabilities: Vec<(PublishedVersion, Vec<Vulnerability>)>,
+    /// The newer versions that need a newer Java and which Java they need, by requirement, with `--max-java`.
+    too_new: Vec<(Requirement, Vec<(PublishedVersion, u64)>)>,
+    /// The newer versions with known vulnerabilities that the latest versions replaced,
+    /// with `--avoid-vulnerable` or `--deny-vulnerable`.
+    avoided: Vec<(PublishedVersion, Vec<PublishedVersion>)>,
+    /// The latest versions that are kept although they have vulnerabilities that are denied, with `--deny-vulnerable`.
+    denied: Vec<PublishedVersion>,
+    /// The latest versions whose licenses the policy does not allow, with `--license-check`.
+    disallowed: Vec<(PublishedVersion, Verdict)>,
+    /// The newer versions with licenses that are not allowed that the latest versions replaced,
+    /// with `--avoid-disallowed-licenses`.
+    relicensed: Vec<(PublishedVersion, Vec<PublishedVersion>)>,
+    /// Who signed the artifacts of the latest versions, `None` if they are not signed, with `--verify-signature`.
+    signatures: Vec<(PublishedVersion, Option<Signer>)>,
+    /// Whether the Sigstore bundles of the artifacts of the latest versions verify, with `--verify-sigstore`.
+    attestations: Vec<(PublishedVersion, Status)>,
+}
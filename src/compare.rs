@@ -0,0 +1,156 @@
+use crate::{
+    resolvers::{Client, Error, Resolver},
+    Coordinates, Versions,
+};
+use console::style;
+use futures_util::future::join_all;
+use std::fmt::Display;
+
+/// The versions of the same coordinates from different resolvers.
+#[derive(Debug)]
+pub(crate) struct Comparison {
+    coordinates: Coordinates,
+    resolvers: Vec<(String, Result<Versions, Error>)>,
+}
+
+impl Comparison {
+    /// Resolves the coordinates against all resolvers concurrently.
+    ///
+    /// A resolver that does not know about the coordinates is treated as having no versions.
+    pub(crate) async fn run<R, C>(
+        coordinates: Coordinates,
+        resolvers: &[(String, R)],
+        client: &C,
+    ) -> Self
+    where
+        R: Resolver + Sync,
+        C: Client,
+    {
+        let results = join_all(
+            resolvers
+                .iter()
+                .map(|(_, resolver)| resolver.resolve(&coordinates, client)),
+        )
+        .await;
+        let resolvers = resolvers
+            .iter()
+            .zip(results)
+            .map(|((name, _), result)| match result {
+                Err(e) if e.is_not_found() => (name.clone(), Ok(Versions::default())),
+                result => (name.clone(), result),
+            })
+            .collect();
+        Self {
+            coordinates,
+            resolvers,
+        }
+    }
+
+    /// The versions that are known to any resolver.
+    fn all_versions(&self) -> Versions {
+        self.resolvers
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok())
+            .fold(Versions::default(), |all, versions| {
+                all.union(versions.clone())
+            })
+    }
+
+    /// Whether all resolvers could be queried and know about the same versions.
+    pub(crate) fn is_consistent(&self) -> bool {
+        let all = self.all_versions();
+        self.resolvers.iter().all(|(_, result)| match result {
+            Ok(versions) => all.iter().all(|v| versions.contains(v)),
+            Err(_) => false,
+        })
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Comparing resolvers for {}:{}:",
+            style(&self.coordinates.group_id).magenta(),
+            style(&self.coordinates.artifact).blue()
+        )?;
+        let all = self.all_versions();
+        for (name, result) in &self.resolvers {
+            match result {
+                Ok(versions) => {
+                    let missing = all
+                        .iter()
+                        .filter(|v| !versions.contains(v))
+                        .collect::<Vec<_>>();
+                    if missing.is_empty() {
+                        writeln!(
+                            f,
+                            "{} has all {} version(s)",
+                            style(name).cyan(),
+                            style(all.len()).green().bold()
+                        )?;
+                    } else {
+                        writeln!(
+                            f,
+                            "{} is missing {}",
+                            style(name).cyan(),
+                            style(missing.join(", ")).yellow().bold()
+                        )?;
+                    }
+                }
+                Err(e) => writeln!(f, "{} failed: {}", style(name).cyan(), e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::HostClient, resolvers::UrlResolver};
+
+    fn resolvers(hosts: &[&str]) -> Vec<(String, UrlResolver)> {
+        hosts
+            .iter()
+            .map(|host| {
+                let url = format!("http://{}", host);
+                (url.clone(), UrlResolver::new(url, None).unwrap())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_missing_versions() {
+        console::set_colors_enabled(false);
+        let client = HostClient::new([("central", &["1.0.0", "1.1.0"]), ("mirror", &["1.0.0"])]);
+        let comparison = Comparison::run(
+            Coordinates::new("com.foo", "bar"),
+            &resolvers(&["central", "mirror", "missing"]),
+            &client,
+        )
+        .await;
+
+        assert!(!comparison.is_consistent());
+        assert_eq!(
+            comparison.to_string(),
+            "Comparing resolvers for com.foo:bar:\n\
+             http://central has all 2 version(s)\n\
+             http://mirror is missing 1.1.0\n\
+             http://missing is missing 1.0.0, 1.1.0\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_consistent() {
+        let client = HostClient::new([("central", &["1.0.0"]), ("mirror", &["1.0.0"])]);
+        let comparison = Comparison::run(
+            Coordinates::new("com.foo", "bar"),
+            &resolvers(&["central", "mirror"]),
+            &client,
+        )
+        .await;
+
+        assert!(comparison.is_consistent());
+    }
+}
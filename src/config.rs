@@ -0,0 +1,837 @@
+use crate::{
+    regex::Regex,
+    resolvers::{Auth, Retry},
+    versions::VersionOrder,
+    Coordinates, Server, VersionCheck,
+};
+use console::style;
+use serde::{de::Error as _, Deserialize, Deserializer};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+static CONFIG_FILE: &str = "config.toml";
+
+/// The contents of the configuration file.
+///
+/// The file is read from `$XDG_CONFIG_HOME/latest-maven-version/config.toml`
+/// (or the platform equivalent), unless a different file is given with `--config`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ConfigFile {
+    /// Maps a groupId prefix to the resolver that should be used for it.
+    pub(crate) routes: BTreeMap<String, String>,
+    /// Named resolvers that can be used instead of their URL.
+    pub(crate) resolvers: BTreeMap<String, ResolverAlias>,
+    /// Tuning of the connection pool.
+    pub(crate) pool: PoolConfig,
+    /// Which versions are considered, in addition to the command line options.
+    pub(crate) versions: VersionsConfig,
+    /// What is checked when nothing is given on the command line.
+    pub(crate) run: RunConfig,
+    /// Named sets of resolvers and coordinates, which are checked with `--profile`.
+    pub(crate) profiles: BTreeMap<String, ProfileConfig>,
+    /// Options for certain coordinates, keyed by `groupId:artifactId`, where the artifactId can be `*`.
+    #[serde(deserialize_with = "coordinates_table")]
+    pub(crate) coordinates: Vec<(Coordinates, CoordinatesConfig)>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct VersionsConfig {
+    /// Versions with any of these qualifiers are never considered, e.g. `alpha` or `RC`.
+    pub(crate) exclude_qualifiers: Vec<String>,
+    /// Versions that match any of these regular expressions are never considered.
+    #[serde(deserialize_with = "patterns")]
+    pub(crate) ignore_versions: Vec<Regex>,
+}
+
+/// The resolvers and coordinates of a run without them on the command line.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct RunConfig {
+    /// The resolvers that are used when none are given with `--resolver`, as URLs or names of resolvers.
+    pub(crate) resolvers: Vec<String>,
+    /// The coordinates that are checked when none are given on the command line.
+    #[serde(deserialize_with = "checks")]
+    pub(crate) checks: Vec<VersionCheck>,
+}
+
+/// A profile as it is written, the values that it does not set are taken from the profile it extends.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ProfileConfig {
+    /// The name of the profile to take the values from that this profile does not set.
+    pub(crate) extends: Option<String>,
+    /// The resolvers for the coordinates of this profile, as URLs or names of resolvers.
+    pub(crate) resolvers: Option<Vec<String>>,
+    #[serde(deserialize_with = "optional_checks")]
+    pub(crate) checks: Option<Vec<VersionCheck>>,
+    /// Whether pre releases of the coordinates of this profile are considered, instead of `--include-pre-releases`.
+    pub(crate) include_pre_releases: Option<bool>,
+}
+
+/// A profile with the values it inherits.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Profile {
+    /// The resolvers for the coordinates of this profile, the ones of the run are used if there are none.
+    pub(crate) resolvers: Vec<String>,
+    pub(crate) checks: Vec<VersionCheck>,
+    pub(crate) include_pre_releases: Option<bool>,
+}
+
+/// The options for certain coordinates, which override the global ones.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct CoordinatesConfig {
+    /// Whether pre releases of these coordinates are considered, instead of `--include-pre-releases`.
+    pub(crate) include_pre_releases: Option<bool>,
+    /// The resolver for these coordinates, as a URL or the name of a resolver.
+    pub(crate) resolver: Option<String>,
+    /// How versions of these coordinates are ordered, instead of `--ordering`.
+    pub(crate) ordering: Option<VersionOrder>,
+    /// Versions of these coordinates with any of these qualifiers are never considered,
+    /// instead of the globally excluded qualifiers.
+    pub(crate) exclude_qualifiers: Option<Vec<String>>,
+    /// Versions of these coordinates that match any of these regular expressions are never considered.
+    #[serde(deserialize_with = "patterns")]
+    pub(crate) ignore_versions: Vec<Regex>,
+    /// Versions of these coordinates that are older than this version are never considered.
+    #[serde(deserialize_with = "version")]
+    pub(crate) min_version: Option<String>,
+}
+
+/// How connections are kept for later requests, the command line options take precedence.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct PoolConfig {
+    /// Idle connections are closed after this many seconds.
+    idle_timeout: Option<u64>,
+    /// The number of idle connections that are kept per host.
+    max_idle_per_host: Option<usize>,
+    /// The TCP keepalive interval in seconds, `0` disables it.
+    keepalive: Option<u64>,
+}
+
+/// A resolver that is referenced by its name, e.g. `--resolver internal`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ResolverAlias {
+    url: String,
+    /// A separate repository for snapshots, only used when pre-releases are included.
+    snapshots: Option<String>,
+    #[serde(default, rename = "type")]
+    kind: ResolverKind,
+    username: Option<String>,
+    password: Option<String>,
+    /// The environment variable that contains the password, instead of the `password`.
+    password_env: Option<String>,
+    /// The request timeout in seconds.
+    timeout: Option<u64>,
+    /// How often failed requests are retried.
+    retries: Option<u32>,
+    /// The delay before the first retry in milliseconds, it doubles with every further retry.
+    backoff: Option<u64>,
+    /// Randomize the delays between retries.
+    jitter: Option<bool>,
+    /// Headers that are added to every request.
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    /// Authenticate with the Kerberos ticket of the current user.
+    #[serde(default)]
+    negotiate: bool,
+    /// Whether credentials are sent with the first request, or only after a 401 challenge.
+    preemptive_auth: Option<bool>,
+    /// The most requests that are sent to the host of the resolver at the same time.
+    max_concurrent_requests: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ResolverKind {
+    /// Reads `maven-metadata.xml`.
+    #[default]
+    Maven,
+    /// Uses the version search API of Artifactory.
+    Artifactory,
+}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    UnknownProfile(String),
+    /// The names of the profiles that extend each other, starting and ending with the same one.
+    CyclicProfiles(Vec<String>),
+}
+
+impl ConfigFile {
+    /// Loads the given file or the default file, if it exists.
+    ///
+    /// An explicitly given file must exist, a missing default file is treated as an empty config.
+    pub(crate) fn load(path: Option<&Path>) -> Result<Self, Error> {
+        match path {
+            Some(path) => Self::read(path),
+            None => match default_path() {
+                Some(path) if path.is_file() => Self::read(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    fn read(path: &Path) -> Result<Self, Error> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| Error::Read(path.to_path_buf(), e))?;
+        Self::parse(&content).map_err(|e| Error::Parse(path.to_path_buf(), e))
+    }
+
+    pub(crate) fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// The profile with the values of the profiles it extends, where the values of the profile itself win.
+    pub(crate) fn profile(&self, name: &str) -> Result<Profile, Error> {
+        let mut chain = Vec::new();
+        let mut next = Some(name);
+        while let Some(name) = next {
+            if chain.iter().any(|(n, _)| *n == name) {
+                let mut names = chain
+                    .iter()
+                    .map(|(n, _)| String::from(*n))
+                    .collect::<Vec<_>>();
+                names.push(String::from(name));
+                return Err(Error::CyclicProfiles(names));
+            }
+            let profile = self
+                .profiles
+                .get(name)
+                .ok_or_else(|| Error::UnknownProfile(String::from(name)))?;
+            chain.push((name, profile));
+            next = profile.extends.as_deref();
+        }
+
+        let mut profile = Profile::default();
+        for (_, config) in chain.into_iter().rev() {
+            if let Some(resolvers) = &config.resolvers {
+                profile.resolvers = resolvers.clone();
+            }
+            if let Some(checks) = &config.checks {
+                profile.checks = checks.clone();
+            }
+            if config.include_pre_releases.is_some() {
+                profile.include_pre_releases = config.include_pre_releases;
+            }
+        }
+        Ok(profile)
+    }
+}
+
+impl ResolverAlias {
+    /// Replaces the name of the server with the configured resolver.
+    ///
+    /// Credentials that were given on the command line take precedence.
+    pub(crate) fn apply(&self, server: Server) -> Server {
+        self.server(&self.url, server)
+    }
+
+    /// The server for the snapshot repository, if there is a separate one.
+    pub(crate) fn apply_snapshots(&self, server: &Server) -> Option<Server> {
+        let snapshots = self.snapshots.as_deref()?;
+        Some(self.server(snapshots, server.clone()))
+    }
+
+    fn server(&self, url: &str, server: Server) -> Server {
+        let url = match self.kind {
+            ResolverKind::Maven => String::from(url),
+            ResolverKind::Artifactory => format!("artifactory:{}", url),
+        };
+        let auth = server.auth.or_else(|| {
+            let user = self.username.clone()?;
+            let password = self.password.clone().or_else(|| {
+                let var = self.password_env.as_deref()?;
+                std::env::var(var).ok()
+            });
+            Some(Auth::Basic(user, password.unwrap_or_default()))
+        });
+        Server {
+            id: server.id,
+            url,
+            auth,
+        }
+    }
+
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+
+    pub(crate) fn password_env(&self) -> Option<&str> {
+        self.password_env.as_deref()
+    }
+
+    pub(crate) fn negotiate(&self) -> bool {
+        self.negotiate
+    }
+
+    /// Credentials are sent preemptively, unless disabled.
+    pub(crate) fn auth_on_challenge(&self) -> bool {
+        self.preemptive_auth == Some(false)
+    }
+
+    pub(crate) fn max_concurrent_requests(&self) -> Option<usize> {
+        self.max_concurrent_requests
+    }
+
+    pub(crate) fn headers(&self) -> Vec<(String, String)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+
+    /// The retry settings of this resolver, falling back to the given ones.
+    pub(crate) fn retry(&self, default: Retry) -> Retry {
+        Retry {
+            retries: self.retries.unwrap_or(default.retries),
+            backoff: self.backoff.map_or(default.backoff, Duration::from_millis),
+            jitter: self.jitter.unwrap_or(default.jitter),
+        }
+    }
+}
+
+impl PoolConfig {
+    pub(crate) fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout.map(Duration::from_secs)
+    }
+
+    pub(crate) fn max_idle_per_host(&self) -> Option<usize> {
+        self.max_idle_per_host
+    }
+
+    /// A zero interval disables the keepalive.
+    pub(crate) fn keepalive(&self) -> Option<Duration> {
+        self.keepalive.map(Duration::from_secs)
+    }
+}
+
+fn patterns<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Regex>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(D::Error::custom))
+        .collect()
+}
+
+fn checks<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<VersionCheck>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|check| check.parse().map_err(D::Error::custom))
+        .collect()
+}
+
+fn optional_checks<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<VersionCheck>>, D::Error> {
+    checks(deserializer).map(Some)
+}
+
+fn version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let version = String::deserialize(deserializer)?;
+    match lenient_semver::parse(&version) {
+        Ok(_) => Ok(Some(version)),
+        Err(_) => Err(D::Error::custom(format!("invalid version `{}`", version))),
+    }
+}
+
+/// A table keyed by `groupId:artifactId`, in the order of the keys.
+pub(crate) fn coordinates_table<'de, D, T>(
+    deserializer: D,
+) -> Result<Vec<(Coordinates, T)>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    BTreeMap::<String, T>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, config)| match key.split_once(':') {
+            Some((group_id, artifact)) if !group_id.is_empty() && !artifact.is_empty() => {
+                let coordinates = Coordinates {
+                    group_id: String::from(group_id),
+                    artifact: String::from(artifact),
+                };
+                Ok((coordinates, config))
+            }
+            _ => Err(D::Error::custom(format!(
+                "invalid coordinates `{}`, expected `groupId:artifactId`",
+                key
+            ))),
+        })
+        .collect()
+}
+
+pub(crate) fn default_path() -> Option<PathBuf> {
+    let mut path = dirs::config_dir()?;
+    path.push(env!("CARGO_PKG_NAME"));
+    path.push(CONFIG_FILE);
+    Some(path)
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Read(path, e) => write!(
+                f,
+                "Could not read the config file {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+            Error::Parse(path, e) => write!(
+                f,
+                "Could not parse the config file {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+            Error::UnknownProfile(name) => write!(
+                f,
+                "There is no profile {} in the config file",
+                style(name).red().bold()
+            ),
+            Error::CyclicProfiles(names) => write!(
+                f,
+                "The profiles extend each other: {}",
+                style(names.join(" -> ")).red().bold()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Read(_, src) => Some(src),
+            Error::Parse(_, src) => Some(src),
+            Error::UnknownProfile(_) | Error::CyclicProfiles(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_empty_config() {
+        let config = ConfigFile::parse("").unwrap();
+        assert!(config.routes.is_empty());
+    }
+
+    #[test]
+    fn test_routes() {
+        let config = ConfigFile::parse(
+            r#"
+            [routes]
+            "com.mycorp" = "https://nexus.mycorp.com/repository/maven"
+            "org.example.*" = "https://example.org/maven2"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.routes.into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    String::from("com.mycorp"),
+                    String::from("https://nexus.mycorp.com/repository/maven")
+                ),
+                (
+                    String::from("org.example.*"),
+                    String::from("https://example.org/maven2")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pool() {
+        let config = ConfigFile::parse(
+            r#"
+            [pool]
+            idle_timeout = 300
+            max_idle_per_host = 4
+            keepalive = 0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.pool.idle_timeout(), Some(Duration::from_secs(300)));
+        assert_eq!(config.pool.max_idle_per_host(), Some(4));
+        assert_eq!(config.pool.keepalive(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_versions() {
+        let config = ConfigFile::parse(
+            r#"
+            [versions]
+            exclude_qualifiers = ["alpha", "beta"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.versions.exclude_qualifiers, vec!["alpha", "beta"]);
+        assert_eq!(
+            ConfigFile::parse("").unwrap().versions,
+            VersionsConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_ignore_versions() {
+        let config = ConfigFile::parse(
+            r#"
+            [versions]
+            ignore_versions = ['-rc\d+$']
+
+            [coordinates."org.apache.logging.log4j:log4j-core"]
+            ignore_versions = ['^2\.17\.0$']
+
+            [coordinates."org.neo4j.gds:*"]
+            ignore_versions = ['^1\.']
+            min_version = "2.0"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.versions.ignore_versions,
+            vec![Regex::new(r"-rc\d+$").unwrap()]
+        );
+        assert_eq!(
+            config.coordinates,
+            vec![
+                (
+                    Coordinates::new("org.apache.logging.log4j", "log4j-core"),
+                    CoordinatesConfig {
+                        ignore_versions: vec![Regex::new(r"^2\.17\.0$").unwrap()],
+                        ..CoordinatesConfig::default()
+                    }
+                ),
+                (
+                    Coordinates::new("org.neo4j.gds", "*"),
+                    CoordinatesConfig {
+                        ignore_versions: vec![Regex::new(r"^1\.").unwrap()],
+                        min_version: Some(String::from("2.0")),
+                        ..CoordinatesConfig::default()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coordinates_overrides() {
+        let config = ConfigFile::parse(
+            r#"
+            [coordinates."org.springframework:spring-core"]
+            include_pre_releases = true
+            resolver = "spring"
+            ordering = "maven"
+            exclude_qualifiers = ["M"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.coordinates,
+            vec![(
+                Coordinates::new("org.springframework", "spring-core"),
+                CoordinatesConfig {
+                    include_pre_releases: Some(true),
+                    resolver: Some(String::from("spring")),
+                    ordering: Some(VersionOrder::Maven),
+                    exclude_qualifiers: Some(vec![String::from("M")]),
+                    ..CoordinatesConfig::default()
+                }
+            )]
+        );
+    }
+
+    #[test_case("[versions]\nignore_versions = ['(']" => "unclosed group"; "invalid pattern")]
+    #[test_case("[coordinates.\"foo:bar\"]\nmin_version = 'latest'" => "invalid version `latest`"; "invalid min version")]
+    #[test_case("[coordinates.\"foo:bar\"]\nordering = 'npm'" => "unknown variant `npm`"; "unknown ordering")]
+    #[test_case("[coordinates.foo]\nignore_versions = []" => "invalid coordinates `foo`"; "invalid coordinates")]
+    #[test_case("[coordinates.\"foo:bar\"]\nfoo = 42" => "unknown field `foo`"; "unknown coordinates option")]
+    fn test_invalid_coordinates_config(content: &str) -> &'static str {
+        let err = ConfigFile::parse(content).unwrap_err().to_string();
+        [
+            "unclosed group",
+            "invalid coordinates `foo`",
+            "unknown field `foo`",
+            "invalid version `latest`",
+            "unknown variant `npm`",
+        ]
+        .iter()
+        .copied()
+        .find(|expected| err.contains(expected))
+        .unwrap_or_else(|| panic!("unexpected error {}", err))
+    }
+
+    #[test]
+    fn test_resolvers() {
+        let config = ConfigFile::parse(
+            r#"
+            [resolvers.internal]
+            url = "https://repo.mycorp.com/artifactory/libs-release"
+            type = "artifactory"
+            username = "alice"
+            password = "s3cure"
+            timeout = 60
+            retries = 3
+            backoff = 250
+            jitter = true
+            negotiate = true
+            preemptive_auth = false
+            max_concurrent_requests = 4
+            headers = { X-JFrog-Art-Api = "api-key" }
+
+            [resolvers.mirror]
+            url = "https://nexus.mycorp.com/repository/maven"
+            "#,
+        )
+        .unwrap();
+
+        let server = |auth| Server {
+            id: None,
+            url: String::from("internal"),
+            auth,
+        };
+        let internal = &config.resolvers["internal"];
+        let applied = internal.apply(server(None));
+        assert_eq!(
+            applied.url,
+            "artifactory:https://repo.mycorp.com/artifactory/libs-release"
+        );
+        assert_eq!(applied.auth, Some(Auth::basic("alice", "s3cure")));
+        assert_eq!(internal.timeout(), Some(Duration::from_secs(60)));
+        assert!(internal.negotiate());
+        assert!(internal.auth_on_challenge());
+        assert_eq!(internal.max_concurrent_requests(), Some(4));
+        assert_eq!(
+            internal.retry(Retry::default()),
+            Retry {
+                retries: 3,
+                backoff: Duration::from_millis(250),
+                jitter: true,
+            }
+        );
+
+        assert_eq!(
+            internal.headers(),
+            vec![(String::from("X-JFrog-Art-Api"), String::from("api-key"))]
+        );
+
+        let applied = internal.apply(server(Some(Auth::basic("bob", "pass"))));
+        assert_eq!(applied.auth, Some(Auth::basic("bob", "pass")));
+
+        let mirror = &config.resolvers["mirror"];
+        let applied = mirror.apply(server(None));
+        assert_eq!(applied.url, "https://nexus.mycorp.com/repository/maven");
+        assert_eq!(applied.auth, None);
+        assert_eq!(mirror.timeout(), None);
+        let retry = Retry {
+            retries: 5,
+            ..Retry::default()
+        };
+        assert_eq!(mirror.retry(retry), retry);
+        assert!(!mirror.negotiate());
+        assert!(!mirror.auth_on_challenge());
+        assert_eq!(mirror.max_concurrent_requests(), None);
+        assert!(mirror.headers().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_repository() {
+        let config = ConfigFile::parse(
+            r#"
+            [resolvers.ossrh]
+            url = "https://oss.sonatype.org/content/repositories/releases"
+            snapshots = "https://oss.sonatype.org/content/repositories/snapshots"
+            username = "alice"
+            password = "s3cure"
+
+            [resolvers.central]
+            url = "https://repo.maven.apache.org/maven2"
+            "#,
+        )
+        .unwrap();
+
+        let server = |url: &str| Server {
+            id: None,
+            url: String::from(url),
+            auth: None,
+        };
+        let ossrh = &config.resolvers["ossrh"];
+        let snapshots = ossrh.apply_snapshots(&server("ossrh")).unwrap();
+        assert_eq!(
+            snapshots.url,
+            "https://oss.sonatype.org/content/repositories/snapshots"
+        );
+        assert_eq!(snapshots.auth, Some(Auth::basic("alice", "s3cure")));
+        assert_eq!(
+            ossrh.apply(server("ossrh")).url,
+            "https://oss.sonatype.org/content/repositories/releases"
+        );
+
+        assert!(config.resolvers["central"]
+            .apply_snapshots(&server("central"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_password_env() {
+        let config = ConfigFile::parse(
+            r#"
+            [resolvers.internal]
+            url = "https://repo.mycorp.com/maven"
+            username = "alice"
+            password_env = "LMV_TEST_CONFIG_PASSWORD"
+            "#,
+        )
+        .unwrap();
+        std::env::set_var("LMV_TEST_CONFIG_PASSWORD", "s3cure");
+        let server = Server {
+            id: None,
+            url: String::from("internal"),
+            auth: None,
+        };
+        let applied = config.resolvers["internal"].apply(server);
+        assert_eq!(applied.auth, Some(Auth::basic("alice", "s3cure")));
+    }
+
+    #[test]
+    fn test_run() {
+        let config = ConfigFile::parse(
+            r#"
+            [run]
+            resolvers = ["internal", "https://repo.maven.apache.org/maven2"]
+            checks = ["org.neo4j:neo4j:^4", "com.mycorp:app"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.run.resolvers,
+            vec!["internal", "https://repo.maven.apache.org/maven2"]
+        );
+        assert_eq!(
+            config.run.checks,
+            vec![
+                "org.neo4j:neo4j:^4".parse().unwrap(),
+                "com.mycorp:app".parse().unwrap()
+            ]
+        );
+        assert_eq!(ConfigFile::parse("").unwrap().run, RunConfig::default());
+    }
+
+    #[test]
+    fn test_invalid_run_check() {
+        let err = ConfigFile::parse("[run]\nchecks = ['org.neo4j:']").unwrap_err();
+        assert!(err.to_string().contains("run.checks"), "{}", err);
+    }
+
+    #[test]
+    fn test_profiles() {
+        let config = ConfigFile::parse(
+            r#"
+            [profiles.base]
+            resolvers = ["internal"]
+            checks = ["org.neo4j:neo4j"]
+
+            [profiles.releases]
+            extends = "base"
+
+            [profiles.snapshots]
+            extends = "base"
+            checks = ["com.mycorp:app"]
+            include_pre_releases = true
+
+            [profiles.team]
+            extends = "snapshots"
+            resolvers = []
+            include_pre_releases = false
+            "#,
+        )
+        .unwrap();
+        let check = |c: &str| c.parse::<VersionCheck>().unwrap();
+        assert_eq!(
+            config.profile("releases").unwrap(),
+            Profile {
+                resolvers: vec![String::from("internal")],
+                checks: vec![check("org.neo4j:neo4j")],
+                include_pre_releases: None,
+            }
+        );
+        assert_eq!(
+            config.profile("snapshots").unwrap(),
+            Profile {
+                resolvers: vec![String::from("internal")],
+                checks: vec![check("com.mycorp:app")],
+                include_pre_releases: Some(true),
+            }
+        );
+        assert_eq!(
+            config.profile("team").unwrap(),
+            Profile {
+                resolvers: Vec::new(),
+                checks: vec![check("com.mycorp:app")],
+                include_pre_releases: Some(false),
+            }
+        );
+    }
+
+    #[test_case("nightly" => "unknown nightly"; "unknown")]
+    #[test_case("broken" => "unknown missing"; "unknown base")]
+    #[test_case("a" => "cycle a b a"; "cycle")]
+    #[test_case("self" => "cycle self self"; "self extends")]
+    fn test_invalid_profiles(name: &str) -> String {
+        let config = ConfigFile::parse(
+            r#"
+            [profiles.broken]
+            extends = "missing"
+            [profiles.a]
+            extends = "b"
+            [profiles.b]
+            extends = "a"
+            [profiles.self]
+            extends = "self"
+            "#,
+        )
+        .unwrap();
+        match config.profile(name).unwrap_err() {
+            Error::UnknownProfile(name) => format!("unknown {}", name),
+            Error::CyclicProfiles(names) => format!("cycle {}", names.join(" ")),
+            e => panic!("unexpected error {}", e),
+        }
+    }
+
+    #[test]
+    fn test_unknown_resolver_type() {
+        let err = ConfigFile::parse(
+            r#"
+            [resolvers.foo]
+            url = "https://example.org"
+            type = "ivy"
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown variant `ivy`"));
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let err = ConfigFile::parse("foo = 42").unwrap_err();
+        assert!(err.to_string().contains("unknown field `foo`"));
+    }
+
+    #[test]
+    fn test_missing_explicit_file() {
+        let err = ConfigFile::load(Some(Path::new("/does/not/exist.toml"))).unwrap_err();
+        assert!(matches!(err, Error::Read(_, _)));
+    }
+}
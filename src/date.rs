@@ -0,0 +1,70 @@
+//! Conversions between civil dates in UTC and the time since the epoch, see
+//! http://howardhinnant.github.io/date_algorithms.html
+
+use std::{
+    convert::TryFrom,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The abbreviated English month names, as in HTTP dates and directory listings.
+pub(crate) static MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The time of the date and the time of day in UTC, `None` before the epoch.
+pub(crate) fn utc_time(
+    (year, month, day): (i64, i64, i64),
+    (hour, minute, second): (i64, i64, i64),
+) -> Option<SystemTime> {
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    let secs = days * 86400 + u64::try_from(hour * 3600 + minute * 60 + second).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// The days since the epoch of the date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// The year, month, and day of the days since the epoch.
+#[cfg(feature = "runtime")]
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(1970, 1, 1 => 0; "epoch")]
+    #[test_case(2000, 2, 29 => 11016; "leap day")]
+    #[test_case(2020, 8, 27 => 18501; "date")]
+    #[test_case(1969, 12, 31 => -1; "before the epoch")]
+    fn test_days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+        days_from_civil(year, month, day)
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_civil_from_days() {
+        for days in -1000..30000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+}
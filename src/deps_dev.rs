@@ -0,0 +1,237 @@
+use crate::{http::ApiClient, Coordinates};
+use serde::{de::DeserializeOwned, Deserialize};
+use url::form_urlencoded::byte_serialize;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+static DEPS_DEV_API: &str = "https://api.deps.dev";
+
+/// Looks up what https://deps.dev knows about versions on Maven Central.
+#[derive(Debug, Clone)]
+pub(crate) struct DepsDev {
+    http: ApiClient,
+}
+
+/// What deps.dev knows about a version.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Insights {
+    /// How many packages depend on the version.
+    pub(crate) dependents: Option<Dependents>,
+    /// The OpenSSF Scorecard of the source repository.
+    pub(crate) scorecard: Option<Scorecard>,
+    /// The security advisories that affect the version.
+    pub(crate) advisories: Vec<Advisory>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Dependents {
+    pub(crate) dependent_count: u64,
+    pub(crate) direct_dependent_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Scorecard {
+    /// The source repository, e.g. `github.com/neo4j/graph-data-science`.
+    pub(crate) project: String,
+    /// The overall score, from 0 to 10.
+    pub(crate) score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct Advisory {
+    #[serde(rename = "advisoryKey")]
+    key: Key,
+    #[serde(default)]
+    pub(crate) title: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct Key {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Version {
+    #[serde(default)]
+    advisory_keys: Vec<Key>,
+    #[serde(default)]
+    related_projects: Vec<RelatedProject>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RelatedProject {
+    project_key: Key,
+    relation_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Project {
+    scorecard: Option<ProjectScorecard>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectScorecard {
+    overall_score: f64,
+}
+
+impl DepsDev {
+    pub(crate) fn new(http: ApiClient) -> Self {
+        Self { http }
+    }
+
+    /// What deps.dev knows about the version, `None` if it does not know the version at all.
+    pub(crate) async fn insights(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+    ) -> Result<Option<Insights>, BoxError> {
+        let path = format!(
+            "systems/maven/packages/{}/versions/{}",
+            encode(&format!(
+                "{}:{}",
+                coordinates.group_id, coordinates.artifact
+            )),
+            encode(version)
+        );
+        let found = match self.get::<Version>(&format!("v3/{}", path)).await? {
+            Some(version) => version,
+            None => return Ok(None),
+        };
+        // the dependents are only in the alpha API, which might change, so they are optional
+        let dependents = self
+            .get::<Dependents>(&format!("v3alpha/{}:dependents", path))
+            .await
+            .ok()
+            .flatten();
+        let scorecard = match source_repository(&found) {
+            Some(project) => self
+                .get::<Project>(&format!("v3/projects/{}", encode(project)))
+                .await?
+                .and_then(|p| p.scorecard)
+                .map(|scorecard| Scorecard {
+                    project: String::from(project),
+                    score: scorecard.overall_score,
+                }),
+            None => None,
+        };
+        let mut advisories = Vec::with_capacity(found.advisory_keys.len());
+        for key in found.advisory_keys {
+            let advisory = self
+                .get::<Advisory>(&format!("v3/advisories/{}", encode(&key.id)))
+                .await?;
+            advisories.push(advisory.unwrap_or(Advisory {
+                key,
+                title: String::new(),
+                aliases: Vec::new(),
+            }));
+        }
+        Ok(Some(Insights {
+            dependents,
+            scorecard,
+            advisories,
+        }))
+    }
+
+    /// Gets the endpoint of the API, `None` if it is not found.
+    async fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<Option<T>, BoxError> {
+        let response = self
+            .http
+            .get(format!("{}/{}", DEPS_DEV_API, endpoint))
+            .send()
+            .await?;
+        match response.status {
+            404 => Ok(None),
+            _ if response.is_success() => Ok(Some(serde_json::from_str(&response.body)?)),
+            status => Err(format!(
+                "The deps.dev request failed with {}: {}",
+                status, response.body
+            )
+            .into()),
+        }
+    }
+}
+
+/// The source repository that deps.dev linked the version to, e.g. `github.com/neo4j/graph-data-science`.
+fn source_repository(version: &Version) -> Option<&str> {
+    version
+        .related_projects
+        .iter()
+        .find(|project| project.relation_type == "SOURCE_REPO")
+        .map(|project| project.project_key.id.as_str())
+}
+
+/// Encodes a path segment, including the `:` of the coordinates and the `/` of the projects.
+fn encode(segment: &str) -> String {
+    byte_serialize(segment.as_bytes()).collect()
+}
+
+impl Advisory {
+    /// The CVE of the advisory if it has one, otherwise its id.
+    pub(crate) fn name(&self) -> &str {
+        self.aliases
+            .iter()
+            .find(|alias| alias.starts_with("CVE-"))
+            .unwrap_or(&self.key.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        let version = serde_json::from_str::<Version>(
+            r#"{
+                "versionKey": { "system": "MAVEN", "name": "org.neo4j.gds:proc", "version": "1.3.1" },
+                "advisoryKeys": [ { "id": "GHSA-jfh8-c2jp-5v3q" } ],
+                "relatedProjects": [
+                    { "projectKey": { "id": "github.com/neo4j/docs" }, "relationType": "ISSUE_TRACKER" },
+                    { "projectKey": { "id": "github.com/neo4j/graph-data-science" }, "relationType": "SOURCE_REPO" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            source_repository(&version),
+            Some("github.com/neo4j/graph-data-science")
+        );
+        assert_eq!(
+            version.advisory_keys,
+            vec![Key {
+                id: String::from("GHSA-jfh8-c2jp-5v3q")
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_advisory() {
+        let advisory = serde_json::from_str::<Advisory>(
+            r#"{
+                "advisoryKey": { "id": "GHSA-jfh8-c2jp-5v3q" },
+                "url": "https://osv.dev/vulnerability/GHSA-jfh8-c2jp-5v3q",
+                "title": "Remote code injection in Log4j",
+                "aliases": [ "CVE-2021-44228" ],
+                "cvss3Score": 10
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(advisory.name(), "CVE-2021-44228");
+        assert_eq!(advisory.title, "Remote code injection in Log4j");
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("org.neo4j.gds:proc"), "org.neo4j.gds%3Aproc");
+        assert_eq!(
+            encode("github.com/neo4j/graph-data-science"),
+            "github.com%2Fneo4j%2Fgraph-data-science"
+        );
+    }
+}
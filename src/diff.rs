@@ -0,0 +1,262 @@
+use crate::{gradle::Module, java, pom::Dependency, tree::Effective, Coordinates};
+use console::style;
+use std::fmt::{self, Display};
+
+/// What a version is made of, as far as its pom and its Gradle module tell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Structure {
+    version: String,
+    /// The dependencies that end up on the classpath.
+    dependencies: Vec<Dependency>,
+    licenses: Vec<String>,
+    /// The Java version that the version targets, e.g. `11`.
+    java: Option<String>,
+}
+
+/// The structural changes between two versions of the same coordinates.
+#[derive(Debug)]
+pub(crate) struct Changes {
+    coordinates: Coordinates,
+    old: Structure,
+    new: Structure,
+}
+
+impl Structure {
+    pub(crate) fn of(version: &str, effective: &Effective, module: Option<&Module>) -> Self {
+        Self {
+            version: String::from(version),
+            dependencies: effective.dependencies(),
+            licenses: effective.licenses.clone(),
+            java: java::target(effective, module),
+        }
+    }
+
+    /// The Java version without the `1.` of old versions, so that `1.8` and `8` are the same.
+    fn java(&self) -> Option<&str> {
+        self.java
+            .as_deref()
+            .map(|java| java.strip_prefix("1.").unwrap_or(java))
+    }
+
+    fn dependency(&self, coordinates: &Coordinates) -> Option<&Dependency> {
+        self.dependencies
+            .iter()
+            .find(|dependency| dependency.coordinates == *coordinates)
+    }
+}
+
+impl Changes {
+    pub(crate) fn new(coordinates: Coordinates, old: Structure, new: Structure) -> Self {
+        Self {
+            coordinates,
+            old,
+            new,
+        }
+    }
+
+    fn added(&self) -> impl Iterator<Item = &Dependency> {
+        self.new
+            .dependencies
+            .iter()
+            .filter(move |dependency| self.old.dependency(&dependency.coordinates).is_none())
+    }
+
+    fn removed(&self) -> impl Iterator<Item = &Dependency> {
+        self.old
+            .dependencies
+            .iter()
+            .filter(move |dependency| self.new.dependency(&dependency.coordinates).is_none())
+    }
+
+    /// The dependencies whose version or scope changed, as they were and as they are.
+    fn changed(&self) -> impl Iterator<Item = (&Dependency, &Dependency)> {
+        self.old.dependencies.iter().filter_map(move |old| {
+            let new = self.new.dependency(&old.coordinates)?;
+            let changed = old.version != new.version || scope(old) != scope(new);
+            changed.then_some((old, new))
+        })
+    }
+
+    /// Whether the versions have the same dependencies, licenses, and Java target.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added().next().is_none()
+            && self.removed().next().is_none()
+            && self.changed().next().is_none()
+            && self.old.licenses == self.new.licenses
+            && self.old.java() == self.new.java()
+    }
+}
+
+fn scope(dependency: &Dependency) -> &str {
+    dependency.scope.as_deref().unwrap_or("compile")
+}
+
+fn version(dependency: &Dependency) -> &str {
+    dependency.version.as_deref().unwrap_or("?")
+}
+
+fn licenses(licenses: &[String]) -> String {
+    if licenses.is_empty() {
+        String::from("none")
+    } else {
+        licenses.join(", ")
+    }
+}
+
+impl Display for Changes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let coordinates = format!(
+            "{}:{}",
+            style(&self.coordinates.group_id).magenta(),
+            style(&self.coordinates.artifact).blue()
+        );
+        if self.is_empty() {
+            return writeln!(
+                f,
+                "There are no structural changes of {} from {} to {}",
+                coordinates,
+                style(&self.old.version).cyan(),
+                style(&self.new.version).cyan()
+            );
+        }
+        writeln!(
+            f,
+            "Changes of {} from {} to {}:",
+            coordinates,
+            style(&self.old.version).cyan(),
+            style(&self.new.version).cyan()
+        )?;
+        for dependency in self.added() {
+            writeln!(
+                f,
+                "{} {}:{}:{} ({})",
+                style("+").green().bold(),
+                dependency.coordinates.group_id,
+                dependency.coordinates.artifact,
+                style(version(dependency)).green(),
+                scope(dependency)
+            )?;
+        }
+        for dependency in self.removed() {
+            writeln!(
+                f,
+                "{} {}:{}:{} ({})",
+                style("-").red().bold(),
+                dependency.coordinates.group_id,
+                dependency.coordinates.artifact,
+                style(version(dependency)).red(),
+                scope(dependency)
+            )?;
+        }
+        for (old, new) in self.changed() {
+            write!(
+                f,
+                "{} {}:{}",
+                style("~").yellow().bold(),
+                old.coordinates.group_id,
+                old.coordinates.artifact,
+            )?;
+            if old.version != new.version {
+                write!(
+                    f,
+                    " {} -> {}",
+                    style(version(old)).yellow(),
+                    style(version(new)).green()
+                )?;
+            }
+            if scope(old) != scope(new) {
+                write!(f, " ({} -> {})", scope(old), scope(new))?;
+            }
+            writeln!(f)?;
+        }
+        if self.old.licenses != self.new.licenses {
+            writeln!(
+                f,
+                "The licenses changed from {} to {}",
+                style(licenses(&self.old.licenses)).yellow(),
+                style(licenses(&self.new.licenses)).yellow().bold()
+            )?;
+        }
+        if self.old.java() != self.new.java() {
+            writeln!(
+                f,
+                "The Java target changed from {} to {}",
+                style(self.old.java.as_deref().unwrap_or("unknown")).yellow(),
+                style(self.new.java.as_deref().unwrap_or("unknown"))
+                    .yellow()
+                    .bold()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency(artifact: &str, version: &str, scope: Option<&str>) -> Dependency {
+        Dependency {
+            coordinates: Coordinates::new("com.foo", artifact),
+            version: Some(String::from(version)),
+            scope: scope.map(String::from),
+            optional: false,
+        }
+    }
+
+    fn structure(version: &str, dependencies: Vec<Dependency>, java: &str) -> Structure {
+        Structure {
+            version: String::from(version),
+            dependencies,
+            licenses: vec![String::from("Apache-2.0")],
+            java: Some(String::from(java)),
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let old = structure(
+            "1.2.3",
+            vec![
+                dependency("kept", "1.0", None),
+                dependency("removed", "1.0", None),
+                dependency("upgraded", "1.0", None),
+                dependency("rescoped", "1.0", None),
+            ],
+            "1.8",
+        );
+        let mut new = structure(
+            "1.3.0",
+            vec![
+                dependency("kept", "1.0", None),
+                dependency("upgraded", "2.0", None),
+                dependency("rescoped", "1.0", Some("runtime")),
+                dependency("added", "3.0", None),
+            ],
+            "11",
+        );
+        new.licenses = vec![String::from("BUSL-1.1")];
+        let changes = Changes::new(Coordinates::new("com.foo", "bar"), old, new);
+        console::set_colors_enabled(false);
+        assert_eq!(
+            changes.to_string(),
+            "\
+Changes of com.foo:bar from 1.2.3 to 1.3.0:
++ com.foo:added:3.0 (compile)
+- com.foo:removed:1.0 (compile)
+~ com.foo:upgraded 1.0 -> 2.0
+~ com.foo:rescoped (compile -> runtime)
+The licenses changed from Apache-2.0 to BUSL-1.1
+The Java target changed from 1.8 to 11
+"
+        );
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let old = structure("1.2.3", vec![dependency("kept", "1.0", None)], "1.8");
+        let new = structure("1.2.4", vec![dependency("kept", "1.0", None)], "8");
+        let changes = Changes::new(Coordinates::new("com.foo", "bar"), old, new);
+        assert!(changes.is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+use super::{Client, Download, Error, ErrorKind, MaybeSendSync, RequestOptions, Resolver};
+use crate::{metadata::Metadata, search::Query, Coordinates, Versions};
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc, time::SystemTime};
+use url::Url;
+
+/// The object safe version of [`Resolver`], to combine resolvers at runtime as `Arc<dyn DynResolver>`.
+///
+/// Every resolver implements it, and `Arc<dyn DynResolver>` implements [`Resolver`] again,
+/// so that it can be used wherever a resolver is expected, e.g. in a fallback chain of resolvers of different types.
+#[cfg_attr(not(all(target_arch = "wasm32", target_os = "unknown")), async_trait)]
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), async_trait(?Send))]
+pub trait DynResolver: MaybeSendSync {
+    async fn dyn_resolve(
+        &self,
+        coordinates: &Coordinates,
+        client: &dyn Client,
+    ) -> Result<Versions, Error>;
+
+    async fn dyn_artifacts(
+        &self,
+        group_id: &str,
+        client: &dyn Client,
+    ) -> Result<Vec<String>, Error>;
+
+    async fn dyn_snapshot_build(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        client: &dyn Client,
+    ) -> Result<Option<String>, Error>;
+
+    async fn dyn_publication_dates(
+        &self,
+        coordinates: &Coordinates,
+        client: &dyn Client,
+    ) -> Result<HashMap<String, SystemTime>, Error>;
+
+    async fn dyn_version_file(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        extension: &str,
+        client: &dyn Client,
+    ) -> Result<Option<String>, Error>;
+
+    async fn dyn_version_file_url(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        extension: &str,
+        client: &dyn Client,
+    ) -> Result<Option<Url>, Error>;
+
+    async fn dyn_download(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        classifier: Option<&str>,
+        extension: &str,
+        client: &dyn Client,
+    ) -> Result<Option<Download>, Error>;
+
+    async fn dyn_search(
+        &self,
+        query: &Query,
+        client: &dyn Client,
+    ) -> Result<Option<Vec<Coordinates>>, Error>;
+
+    fn dyn_hosts(&self) -> Vec<String>;
+}
+
+#[cfg_attr(not(all(target_arch = "wasm32", target_os = "unknown")), async_trait)]
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), async_trait(?Send))]
+impl<R: Resolver + MaybeSendSync> DynResolver for R {
+    async fn dyn_resolve(
+        &self,
+        coordinates: &Coordinates,
+        client: &dyn Client,
+    ) -> Result<Versions, Error> {
+        self.resolve(coordinates, &client).await
+    }
+
+    async fn dyn_artifacts(
+        &self,
+        group_id: &str,
+        client: &dyn Client,
+    ) -> Result<Vec<String>, Error> {
+        self.artifacts(group_id, &client).await
+    }
+
+    async fn dyn_snapshot_build(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        client: &dyn Client,
+    ) -> Result<Option<String>, Error> {
+        self.snapshot_build(coordinates, version, &client).await
+    }
+
+    async fn dyn_publication_dates(
+        &self,
+        coordinates: &Coordinates,
+        client: &dyn Client,
+    ) -> Result<HashMap<String, SystemTime>, Error> {
+        self.publication_dates(coordinates, &client).await
+    }
+
+    async fn dyn_version_file(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        extension: &str,
+        client: &dyn Client,
+    ) -> Result<Option<String>, Error> {
+        self.version_file(coordinates, version, extension, &client)
+            .await
+    }
+
+    async fn dyn_version_file_url(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        extension: &str,
+        client: &dyn Client,
+    ) -> Result<Option<Url>, Error> {
+        self.version_file_url(coordinates, version, extension, &client)
+            .await
+    }
+
+    async fn dyn_download(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        classifier: Option<&str>,
+        extension: &str,
+        client: &dyn Client,
+    ) -> Result<Option<Download>, Error> {
+        self.download(coordinates, version, classifier, extension, &client)
+            .await
+    }
+
+    async fn dyn_search(
+        &self,
+        query: &Query,
+        client: &dyn Client,
+    ) -> Result<Option<Vec<Coordinates>>, Error> {
+        self.search(query, &client).await
+    }
+
+    fn dyn_hosts(&self) -> Vec<String> {
+        self.hosts()
+    }
+}
+
+/// Calls the resolver behind the `Arc`, and not the blanket [`DynResolver`] impl of the `Arc` itself.
+#[cfg_attr(not(all(target_arch = "wasm32", target_os = "unknown")), async_trait)]
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), async_trait(?Send))]
+impl Resolver for Arc<dyn DynResolver> {
+    async fn resolve<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        client: &T,
+    ) -> Result<Versions, Error> {
+        (**self).dyn_resolve(coordinates, client).await
+    }
+
+    async fn artifacts<T: Client>(&self, group_id: &str, client: &T) -> Result<Vec<String>, Error> {
+        (**self).dyn_artifacts(group_id, client).await
+    }
+
+    async fn snapshot_build<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        client: &T,
+    ) -> Result<Option<String>, Error> {
+        (**self)
+            .dyn_snapshot_build(coordinates, version, client)
+            .await
+    }
+
+    async fn publication_dates<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        client: &T,
+    ) -> Result<HashMap<String, SystemTime>, Error> {
+        (**self).dyn_publication_dates(coordinates, client).await
+    }
+
+    async fn version_file<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        extension: &str,
+        client: &T,
+    ) -> Result<Option<String>, Error> {
+        (**self)
+            .dyn_version_file(coordinates, version, extension, client)
+            .await
+    }
+
+    async fn version_file_url<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        extension: &str,
+        client: &T,
+    ) -> Result<Option<Url>, Error> {
+        (**self)
+            .dyn_version_file_url(coordinates, version, extension, client)
+            .await
+    }
+
+    async fn download<T: Client>(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+        classifier: Option<&str>,
+        extension: &str,
+        client: &T,
+    ) -> Result<Option<Download>, Error> {
+        (**self)
+            .dyn_download(coordinates, version, classifier, extension, client)
+            .await
+    }
+
+    async fn search<T: Client>(
+        &self,
+        query: &Query,
+        client: &T,
+    ) -> Result<Option<Vec<Coordinates>>, Error> {
+        (**self).dyn_search(query, client).await
+    }
+
+    fn hosts(&self) -> Vec<String> {
+        (**self).dyn_hosts()
+    }
+}
+
+/// Forwards all methods, so that the ones the client overrides are kept, also for `&dyn Client`.
+#[allow(clippy::needless_lifetimes)] // the lifetime that async-trait names
+#[cfg_attr(not(all(target_arch = "wasm32", target_os = "unknown")), async_trait)]
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), async_trait(?Send))]
+impl<C: Client + ?Sized> Client for &C {
+    async fn request(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        (**self).request(url, options, coordinates).await
+    }
+
+    async fn request_metadata(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Metadata, ErrorKind> {
+        (**self).request_metadata(url, options, coordinates).await
+    }
+
+    async fn request_bytes(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        (**self).request_bytes(url, options, coordinates).await
+    }
+
+    async fn request_exists(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<(), ErrorKind> {
+        (**self).request_exists(url, options, coordinates).await
+    }
+}
+
+/// Shares a client, e.g. an `Arc<dyn Client>` that is chosen at runtime.
+#[cfg_attr(not(all(target_arch = "wasm32", target_os = "unknown")), async_trait)]
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), async_trait(?Send))]
+impl<C: Client + ?Sized> Client for Arc<C> {
+    async fn request(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        (**self).request(url, options, coordinates).await
+    }
+
+    async fn request_metadata(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Metadata, ErrorKind> {
+        (**self).request_metadata(url, options, coordinates).await
+    }
+
+    async fn request_bytes(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        (**self).request_bytes(url, options, coordinates).await
+    }
+
+    async fn request_exists(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<(), ErrorKind> {
+        (**self).request_exists(url, options, coordinates).await
+    }
+}
@@ -0,0 +1,130 @@
+use super::ErrorKind;
+use crate::{Coordinates, Versions};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, process::Stdio, time::Duration};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// How long a plugin may take, unless the resolver has a different timeout.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Written as a single line of JSON to the stdin of the plugin.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Request<'a> {
+    group_id: &'a str,
+    artifact_id: &'a str,
+}
+
+/// Read as JSON from the stdout of the plugin.
+#[derive(Debug, Deserialize)]
+struct Response {
+    versions: Vec<String>,
+}
+
+/// Runs an external executable as resolver.
+///
+/// The plugin reads `{"groupId": "…", "artifactId": "…"}` from stdin and writes
+/// `{"versions": ["…"]}` to stdout. An empty list means that the coordinates are unknown,
+/// and a non-zero exit code is reported as an error together with stderr.
+pub(super) async fn resolve(
+    program: &Path,
+    coordinates: &Coordinates,
+    timeout: Option<Duration>,
+) -> Result<Versions, ErrorKind> {
+    let request = serde_json::to_string(&Request {
+        group_id: &coordinates.group_id,
+        artifact_id: &coordinates.artifact,
+    })
+    .map_err(|e| ErrorKind::InvalidRequest(Box::new(e)))?;
+
+    let run = async {
+        let mut child = Command::new(program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| ErrorKind::PluginError(format!("could not be started: {}", e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // a plugin that does not read its input is not an error
+            let _ = stdin.write_all(request.as_bytes()).await;
+            let _ = stdin.write_all(b"\n").await;
+        }
+        child
+            .wait_with_output()
+            .await
+            .map_err(|e| ErrorKind::TransportError(Box::new(e)))
+    };
+
+    let output = tokio::time::timeout(timeout.unwrap_or(DEFAULT_TIMEOUT), run)
+        .await
+        .map_err(|_| ErrorKind::ServerNotAvailable)??;
+
+    if !output.status.success() {
+        return Err(ErrorKind::PluginError(format!(
+            "exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let response = serde_json::from_slice::<Response>(&output.stdout)
+        .map_err(|e| ErrorKind::PluginError(format!("returned an invalid response: {}", e)))?;
+    if response.versions.is_empty() {
+        return Err(ErrorKind::CoordinatesNotFound(coordinates.clone()));
+    }
+    Ok(response.versions.into_iter().collect())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::{os::unix::fs::PermissionsExt, path::PathBuf};
+
+    async fn plugin(name: &str, script: &str) -> PathBuf {
+        let path = crate::temp::private_dir().await.unwrap().join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_plugin_versions() {
+        let path = plugin(
+            "plugin-versions",
+            r#"read input; case "$input" in *'"artifactId":"bar"'*) echo '{"versions":["1.0.0","1.1.0"]}';; *) echo '{"versions":[]}';; esac"#,
+        )
+        .await;
+        let found = resolve(&path, &Coordinates::new("com.foo", "bar"), None).await;
+        let missing = resolve(&path, &Coordinates::new("com.foo", "baz"), None).await;
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(found.unwrap(), Versions::from(vec!["1.0.0", "1.1.0"]));
+        assert!(matches!(missing, Err(ErrorKind::CoordinatesNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_failure() {
+        let path = plugin("plugin-failure", "echo 'no access' >&2; exit 3").await;
+        let result = resolve(&path, &Coordinates::new("com.foo", "bar"), None).await;
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        match result {
+            Err(ErrorKind::PluginError(message)) => {
+                assert!(message.contains("no access"), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_plugin() {
+        let result = resolve(
+            Path::new("/does/not/exist"),
+            &Coordinates::new("com.foo", "bar"),
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(ErrorKind::PluginError(_))));
+    }
+}
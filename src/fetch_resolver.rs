@@ -0,0 +1,387 @@
+use super::{send_with_challenge, Auth, Client, ErrorKind, MaybeSendSync, RequestOptions};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use crate::http::retry_after;
+use crate::{
+    http::{content_type_charset, DEFAULT_MAX_RESPONSE_SIZE},
+    metadata::{self, Metadata},
+    Coordinates,
+};
+use async_trait::async_trait;
+use encoding_rs::{Encoding, UTF_8};
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::SystemTime;
+use url::Url;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// The fetch API of the host, e.g. `WebFetch` with the `web-fetch` feature, or a binding of another host.
+///
+/// Redirects are followed by the host, and error statuses are responses, not errors.
+#[cfg_attr(not(all(target_arch = "wasm32", target_os = "unknown")), async_trait)]
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), async_trait(?Send))]
+pub trait Fetch: MaybeSendSync {
+    async fn fetch(&self, request: FetchRequest) -> Result<FetchResponse, BoxError>;
+}
+
+/// A request for [`Fetch::fetch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchRequest {
+    /// `GET`, or `HEAD` for checks that do not need the body.
+    pub method: &'static str,
+    pub url: Url,
+    pub headers: Vec<(String, String)>,
+    /// The timeout of the resolver, e.g. for an `AbortSignal.timeout`.
+    pub timeout: Option<Duration>,
+    /// The limit of the body in bytes, so that the host can skip reading a larger body, `u64::MAX` for downloads.
+    ///
+    /// The client checks the body and the `Content-Length` against the limit either way.
+    pub max_response_size: u64,
+}
+
+/// The response of [`Fetch::fetch`], with the complete body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Sends the requests with the fetch API of the host, for targets without sockets, e.g. `wasm32-unknown-unknown`.
+pub struct FetchClient<F> {
+    fetch: F,
+    max_response_size: u64,
+}
+
+impl<F: Fetch> FetchClient<F> {
+    /// A client that rejects responses over 64MiB, like the other clients do by default.
+    pub fn new(fetch: F) -> Self {
+        Self {
+            fetch,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+        }
+    }
+
+    /// Rejects responses with a larger body in bytes.
+    pub fn with_max_response_size(mut self, max_response_size: u64) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+}
+
+#[cfg_attr(not(all(target_arch = "wasm32", target_os = "unknown")), async_trait)]
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), async_trait(?Send))]
+impl<F: Fetch> Client for FetchClient<F> {
+    async fn request(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        let response = self.response(url, options, "GET", coordinates).await?;
+        Ok(response.text())
+    }
+
+    /// Metadata is parsed from the bytes, as it does not have to be UTF-8.
+    async fn request_metadata(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Metadata, ErrorKind> {
+        let response = self.response(url, options, "GET", coordinates).await?;
+        metadata::parse_bytes(&response.body).map_err(ErrorKind::parse_body)
+    }
+
+    async fn request_bytes(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        let response = self.response(url, options, "GET", coordinates).await?;
+        Ok(response.body)
+    }
+
+    /// Sends a `HEAD` request, and falls back to `GET` for servers that do not allow it.
+    async fn request_exists(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<(), ErrorKind> {
+        match self.response(url, options, "HEAD", coordinates).await {
+            Err(ErrorKind::ClientError(405, _)) => self
+                .response(url, options, "GET", coordinates)
+                .await
+                .map(|_| ()),
+            result => result.map(|_| ()),
+        }
+    }
+}
+
+impl<F: Fetch> FetchClient<F> {
+    /// The successful response, after the anonymous request was challenged if credentials are not preemptive.
+    async fn response(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        method: &'static str,
+        coordinates: &Coordinates,
+    ) -> Result<FetchResponse, ErrorKind> {
+        send_with_challenge(options, |credentials| {
+            self.send(url, options, method, credentials, coordinates)
+        })
+        .await
+    }
+
+    /// Sends the request, with the credentials and custom headers only if `credentials` is set.
+    async fn send(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        method: &'static str,
+        credentials: bool,
+        coordinates: &Coordinates,
+    ) -> Result<FetchResponse, ErrorKind> {
+        let mut headers = Vec::new();
+        if credentials {
+            headers.extend(options.headers.iter().cloned());
+            match &options.auth {
+                Some(Auth::Basic(user, pass)) => {
+                    let credentials = base64::encode(format!("{}:{}", user, pass));
+                    headers.push((
+                        String::from("Authorization"),
+                        format!("Basic {}", credentials),
+                    ));
+                }
+                Some(Auth::Bearer(source)) => {
+                    let token = source
+                        .token()
+                        .await
+                        .map_err(ErrorKind::AuthenticationFailed)?;
+                    headers.push((String::from("Authorization"), format!("Bearer {}", token)));
+                }
+                Some(Auth::Signed(signer)) => {
+                    let signed = signer
+                        .sign(url)
+                        .await
+                        .map_err(ErrorKind::AuthenticationFailed)?;
+                    headers.extend(signed);
+                }
+                None => {}
+            }
+        }
+        let limit = options.body_limit(self.max_response_size);
+        let request = FetchRequest {
+            method,
+            url: url.clone(),
+            headers,
+            timeout: options.timeout,
+            max_response_size: limit,
+        };
+        let response = self
+            .fetch
+            .fetch(request)
+            .await
+            .map_err(ErrorKind::TransportError)?;
+        let response = check_status(response, coordinates)?;
+        check_size(response, limit)
+    }
+}
+
+/// Turns error responses into errors, like the other clients do.
+fn check_status(
+    response: FetchResponse,
+    coordinates: &Coordinates,
+) -> Result<FetchResponse, ErrorKind> {
+    match response.status {
+        404 => Err(ErrorKind::CoordinatesNotFound(coordinates.clone())),
+        429 => Err(ErrorKind::TooManyRequests(
+            response.header("retry-after").and_then(retry_delay),
+        )),
+        status @ 400..=499 => Err(ErrorKind::ClientError(status, response.text())),
+        status @ 500..=599 => Err(ErrorKind::ServerError(status, response.text())),
+        _ => Ok(response),
+    }
+}
+
+/// Rejects bodies over the limit, and responses that announce one, since the host might not have read the body then.
+fn check_size(response: FetchResponse, limit: u64) -> Result<FetchResponse, ErrorKind> {
+    let announced = response
+        .header("content-length")
+        .and_then(|length| length.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+    if announced > limit || response.body.len() as u64 > limit {
+        return Err(ErrorKind::ResponseTooLarge(limit));
+    }
+    Ok(response)
+}
+
+/// The delay of a `Retry-After` header, only in seconds on `wasm32-unknown-unknown`, which has no clock for dates.
+fn retry_delay(value: &str) -> Option<Duration> {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    return retry_after(value, SystemTime::now());
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    return value.trim().parse().ok().map(Duration::from_secs);
+}
+
+impl FetchResponse {
+    /// The value of the header, whose name is case insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Decodes the body with the charset of the `Content-Type`, or as UTF-8.
+    fn text(&self) -> String {
+        let encoding = self
+            .header("content-type")
+            .and_then(content_type_charset)
+            .and_then(|charset| Encoding::for_label(charset.as_bytes()))
+            .unwrap_or(UTF_8);
+        encoding.decode(&self.body).0.into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use test_case::test_case;
+
+    /// Answers with the canned responses and records the requests.
+    struct Canned {
+        responses: Mutex<Vec<FetchResponse>>,
+        requests: Mutex<Vec<FetchRequest>>,
+    }
+
+    #[async_trait]
+    impl Fetch for Canned {
+        async fn fetch(&self, request: FetchRequest) -> Result<FetchResponse, BoxError> {
+            self.requests.lock().unwrap().push(request);
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+    }
+
+    fn client(responses: Vec<FetchResponse>) -> FetchClient<Canned> {
+        FetchClient::new(Canned {
+            responses: Mutex::new(responses),
+            requests: Mutex::default(),
+        })
+    }
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &[u8]) -> FetchResponse {
+        FetchResponse {
+            status,
+            headers: headers
+                .iter()
+                .map(|(n, v)| (String::from(*n), String::from(*v)))
+                .collect(),
+            body: body.to_vec(),
+        }
+    }
+
+    fn url() -> Url {
+        Url::parse("https://repo.example.com/foo/bar/maven-metadata.xml").unwrap()
+    }
+
+    fn coordinates() -> Coordinates {
+        Coordinates::new("foo", "bar")
+    }
+
+    #[test_case(response(200, &[], b"ok") => Ok(String::from("ok")); "ok")]
+    #[test_case(response(200, &[("Content-Type", "text/plain; charset=ISO-8859-1")], b"caf\xe9") => Ok(String::from("café")); "charset")]
+    #[test_case(response(404, &[], b"") => Err(String::from("not found")); "not found")]
+    #[test_case(response(429, &[("Retry-After", "3")], b"") => Err(String::from("too many requests: Some(3s)")); "too many requests")]
+    #[test_case(response(403, &[], b"denied") => Err(String::from("403: denied")); "client error")]
+    #[test_case(response(502, &[], b"gateway") => Err(String::from("502: gateway")); "server error")]
+    #[tokio::test]
+    async fn test_request(response: FetchResponse) -> Result<String, String> {
+        let client = client(vec![response]);
+        let options = RequestOptions::default();
+        client
+            .request(&url(), &options, &coordinates())
+            .await
+            .map_err(|e| match e {
+                ErrorKind::CoordinatesNotFound(_) => String::from("not found"),
+                ErrorKind::TooManyRequests(delay) => format!("too many requests: {:?}", delay),
+                ErrorKind::ClientError(status, body) | ErrorKind::ServerError(status, body) => {
+                    format!("{}: {}", status, body)
+                }
+                e => panic!("unexpected error {:?}", e),
+            })
+    }
+
+    #[tokio::test]
+    async fn test_request_metadata() {
+        let client = client(vec![response(
+            200,
+            &[],
+            b"<metadata><versioning><versions><version>1.0.0</version></versions></versioning></metadata>",
+        )]);
+        let metadata = client
+            .request_metadata(&url(), &RequestOptions::default(), &coordinates())
+            .await
+            .unwrap();
+        assert_eq!(metadata.versions, vec![String::from("1.0.0")]);
+    }
+
+    #[test_case(response(200, &[], b"0123456789") => true; "body")]
+    #[test_case(response(200, &[("Content-Length", "100")], b"") => true; "announced")]
+    #[test_case(response(200, &[("Content-Length", "4")], b"0123") => false; "within")]
+    #[tokio::test]
+    async fn test_max_response_size(response: FetchResponse) -> bool {
+        let client = client(vec![response]).with_max_response_size(5);
+        let result = client
+            .request(&url(), &RequestOptions::default(), &coordinates())
+            .await;
+        let max_response_size = client.fetch.requests.lock().unwrap()[0].max_response_size;
+        assert_eq!(max_response_size, 5);
+        matches!(result, Err(ErrorKind::ResponseTooLarge(5)))
+    }
+
+    #[tokio::test]
+    async fn test_request_exists_falls_back_to_get() {
+        let client = client(vec![response(405, &[], b""), response(200, &[], b"")]);
+        client
+            .request_exists(&url(), &RequestOptions::default(), &coordinates())
+            .await
+            .unwrap();
+        let methods = client.fetch.requests.lock().unwrap();
+        let methods = methods.iter().map(|r| r.method).collect::<Vec<_>>();
+        assert_eq!(methods, vec!["HEAD", "GET"]);
+    }
+
+    #[test_case(false => vec![vec![String::from("Authorization: Basic dXNlcjpwYXNz")]]; "preemptive")]
+    #[test_case(true => vec![vec![], vec![String::from("Authorization: Basic dXNlcjpwYXNz")]]; "on challenge")]
+    #[tokio::test]
+    async fn test_auth(auth_on_challenge: bool) -> Vec<Vec<String>> {
+        let client = if auth_on_challenge {
+            client(vec![response(401, &[], b""), response(200, &[], b"")])
+        } else {
+            client(vec![response(200, &[], b"")])
+        };
+        let options = RequestOptions {
+            auth: Some(Auth::basic("user", "pass")),
+            auth_on_challenge,
+            ..RequestOptions::default()
+        };
+        client
+            .request(&url(), &options, &coordinates())
+            .await
+            .unwrap();
+        let requests = client.fetch.requests.lock().unwrap();
+        requests
+            .iter()
+            .map(|r| {
+                r.headers
+                    .iter()
+                    .map(|(n, v)| format!("{}: {}", n, v))
+                    .collect()
+            })
+            .collect()
+    }
+}
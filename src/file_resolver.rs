@@ -0,0 +1,173 @@
+use super::{Client, ErrorKind, RequestOptions};
+use crate::{
+    metadata::{self, Metadata},
+    runtime, Coordinates,
+};
+use async_trait::async_trait;
+use std::{io::ErrorKind as IoErrorKind, path::PathBuf};
+use url::Url;
+
+/// Reads `file://` URLs from disk, e.g. for repositories that are mirrored to a network share.
+pub(super) struct FileClient;
+
+#[async_trait]
+impl Client for FileClient {
+    async fn request(
+        &self,
+        url: &Url,
+        _options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        let path = url.to_file_path().map_err(|_| {
+            ErrorKind::InvalidRequest(format!("{} is not a valid file path", url).into())
+        })?;
+
+        if path.is_dir() {
+            return directory_index(path).await.map_err(|e| match e.kind() {
+                IoErrorKind::NotFound => ErrorKind::CoordinatesNotFound(coordinates.clone()),
+                _ => ErrorKind::TransportError(Box::new(e)),
+            });
+        }
+
+        match runtime::read_to_string(path).await {
+            Ok(body) => Ok(body),
+            Err(e) if e.kind() == IoErrorKind::NotFound => {
+                Err(ErrorKind::CoordinatesNotFound(coordinates.clone()))
+            }
+            Err(e) => Err(ErrorKind::TransportError(Box::new(e))),
+        }
+    }
+
+    /// Metadata is read as bytes, as it does not have to be UTF-8.
+    async fn request_metadata(
+        &self,
+        url: &Url,
+        _options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Metadata, ErrorKind> {
+        let path = url.to_file_path().map_err(|_| {
+            ErrorKind::InvalidRequest(format!("{} is not a valid file path", url).into())
+        })?;
+
+        match runtime::read(path).await {
+            Ok(body) => metadata::parse_bytes(&body).map_err(ErrorKind::parse_body),
+            Err(e) if e.kind() == IoErrorKind::NotFound => {
+                Err(ErrorKind::CoordinatesNotFound(coordinates.clone()))
+            }
+            Err(e) => Err(ErrorKind::TransportError(Box::new(e))),
+        }
+    }
+
+    async fn request_bytes(
+        &self,
+        url: &Url,
+        _options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        let path = url.to_file_path().map_err(|_| {
+            ErrorKind::InvalidRequest(format!("{} is not a valid file path", url).into())
+        })?;
+
+        match runtime::read(path).await {
+            Ok(body) => Ok(body),
+            Err(e) if e.kind() == IoErrorKind::NotFound => {
+                Err(ErrorKind::CoordinatesNotFound(coordinates.clone()))
+            }
+            Err(e) => Err(ErrorKind::TransportError(Box::new(e))),
+        }
+    }
+
+    async fn request_exists(
+        &self,
+        url: &Url,
+        _options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<(), ErrorKind> {
+        let path = url.to_file_path().map_err(|_| {
+            ErrorKind::InvalidRequest(format!("{} is not a valid file path", url).into())
+        })?;
+
+        match runtime::metadata(path).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == IoErrorKind::NotFound => {
+                Err(ErrorKind::CoordinatesNotFound(coordinates.clone()))
+            }
+            Err(e) => Err(ErrorKind::TransportError(Box::new(e))),
+        }
+    }
+}
+
+/// Lists the subdirectories like an HTML directory index would.
+async fn directory_index(path: PathBuf) -> std::io::Result<String> {
+    let mut index = String::new();
+    for name in runtime::subdirectories(path).await? {
+        index.push_str(&format!("<a href=\"{}/\">{}/</a>\n", name, name));
+    }
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_file() {
+        let dir = crate::temp::private_dir().await.unwrap();
+        let file = dir.join("maven-metadata.xml");
+        std::fs::write(&file, "<metadata/>").unwrap();
+        std::fs::create_dir_all(dir.join("1.0.0")).unwrap();
+
+        let coordinates = Coordinates::new("com.foo", "bar");
+        let found = FileClient
+            .request(
+                &Url::from_file_path(&file).unwrap(),
+                &RequestOptions::default(),
+                &coordinates,
+            )
+            .await;
+        let missing = FileClient
+            .request(
+                &Url::from_file_path(dir.join("missing.xml")).unwrap(),
+                &RequestOptions::default(),
+                &coordinates,
+            )
+            .await;
+        let index = FileClient
+            .request(
+                &Url::from_directory_path(&dir).unwrap(),
+                &RequestOptions::default(),
+                &coordinates,
+            )
+            .await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(found.unwrap(), "<metadata/>");
+        assert_eq!(index.unwrap(), "<a href=\"1.0.0/\">1.0.0/</a>\n");
+        assert!(matches!(
+            missing,
+            Err(ErrorKind::CoordinatesNotFound(c)) if c == coordinates
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_latin1_metadata() {
+        let dir = crate::temp::private_dir().await.unwrap();
+        let file = dir.join("maven-metadata.xml");
+        std::fs::write(
+            &file,
+            b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n<metadata><versioning><versions><version>1.0-f\xfcr</version></versions></versioning></metadata>",
+        )
+        .unwrap();
+
+        let versions = FileClient
+            .request_metadata(
+                &Url::from_file_path(&file).unwrap(),
+                &RequestOptions::default(),
+                &Coordinates::new("com.foo", "bar"),
+            )
+            .await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(versions.unwrap().versions, vec!["1.0-f\u{fc}r"]);
+    }
+}
@@ -0,0 +1,154 @@
+//! Fake clients and metadata for the tests of the resolvers and the checks.
+
+use crate::{
+    resolvers::{Client, ErrorKind, RequestOptions},
+    Coordinates,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+#[cfg(feature = "runtime")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use url::Url;
+
+/// The `maven-metadata.xml` with the versions.
+pub(crate) fn metadata(versions: &[&str]) -> String {
+    let versions = versions
+        .iter()
+        .map(|v| format!("<version>{}</version>", v))
+        .collect::<String>();
+    format!(
+        "<metadata><versioning><versions>{}</versions></versioning></metadata>",
+        versions
+    )
+}
+
+/// Answers with the same versions for every host, and counts the requests.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Default)]
+pub(crate) struct VersionsClient {
+    versions: &'static [&'static str],
+    requests: AtomicUsize,
+}
+
+#[cfg(feature = "runtime")]
+impl VersionsClient {
+    pub(crate) fn new(versions: &'static [&'static str]) -> Self {
+        Self {
+            versions,
+            requests: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(feature = "runtime")]
+#[async_trait]
+impl Client for VersionsClient {
+    async fn request(
+        &self,
+        _url: &Url,
+        _options: &RequestOptions,
+        _coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        Ok(metadata(self.versions))
+    }
+}
+
+/// Answers with the URL and the number of the request, or with a 404 for the paths that end with the `missing` suffix.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Default)]
+pub(crate) struct CountingClient {
+    requests: AtomicUsize,
+    missing: Option<&'static str>,
+}
+
+#[cfg(feature = "runtime")]
+impl CountingClient {
+    pub(crate) fn missing(suffix: &'static str) -> Self {
+        Self {
+            requests: AtomicUsize::new(0),
+            missing: Some(suffix),
+        }
+    }
+
+    pub(crate) fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(feature = "runtime")]
+#[async_trait]
+impl Client for CountingClient {
+    async fn request(
+        &self,
+        url: &Url,
+        _options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        let count = self.requests.fetch_add(1, Ordering::SeqCst) + 1;
+        if self
+            .missing
+            .is_some_and(|suffix| url.path().ends_with(suffix))
+        {
+            return Err(ErrorKind::CoordinatesNotFound(coordinates.clone()));
+        }
+        Ok(format!("{} #{}", url, count))
+    }
+}
+
+/// Requests the URL for `com.foo:bar` with the default options, with a 404 as `not found`.
+#[cfg(feature = "runtime")]
+pub(crate) async fn request(client: &impl Client, url: &str) -> Result<String, String> {
+    client
+        .request(
+            &Url::parse(url).unwrap(),
+            &RequestOptions::default(),
+            &Coordinates::new("com.foo", "bar"),
+        )
+        .await
+        .map_err(|e| match e {
+            ErrorKind::CoordinatesNotFound(_) => String::from("not found"),
+            e => format!("{:?}", e),
+        })
+}
+
+/// Answers with different versions per host, or a 404 for unknown hosts.
+#[derive(Debug, Default)]
+pub(crate) struct HostClient {
+    pub(crate) hosts: HashMap<&'static str, &'static [&'static str]>,
+    /// The host that answers with a 502.
+    pub(crate) failing: Option<&'static str>,
+}
+
+impl HostClient {
+    #[cfg(feature = "runtime")]
+    pub(crate) fn new<const N: usize>(hosts: [(&'static str, &'static [&'static str]); N]) -> Self {
+        Self {
+            hosts: HashMap::from(hosts),
+            failing: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Client for HostClient {
+    async fn request(
+        &self,
+        url: &Url,
+        _options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        let host = url.host_str().unwrap();
+        if self.failing == Some(host) {
+            return Err(ErrorKind::ServerError(502, String::from("Bad Gateway")));
+        }
+        match self.hosts.get(host) {
+            Some(versions) => Ok(metadata(versions)),
+            None => Err(ErrorKind::CoordinatesNotFound(coordinates.clone())),
+        }
+    }
+}
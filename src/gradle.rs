@@ -0,0 +1,162 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The parts of a Gradle Module Metadata file (`.module`) that are relevant for comparing versions.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct Module {
+    #[serde(default)]
+    pub(crate) variants: Vec<Variant>,
+}
+
+/// A variant of the published component, e.g. the `apiElements` of a JVM library or a Kotlin native target.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct Variant {
+    pub(crate) name: String,
+    #[serde(default)]
+    attributes: BTreeMap<String, Value>,
+    #[serde(default)]
+    files: Vec<File>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct File {
+    name: String,
+}
+
+const JVM_VERSION: &str = "org.gradle.jvm.version";
+
+/// The attributes that tell the variants apart, in the order they are shown.
+static ATTRIBUTES: [&str; 4] = [
+    "org.gradle.category",
+    "org.jetbrains.kotlin.platform.type",
+    "org.jetbrains.kotlin.native.target",
+    JVM_VERSION,
+];
+
+impl Module {
+    pub(crate) fn parse(input: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(input)
+    }
+
+    /// The highest Java version that any variant targets, e.g. `11`.
+    pub(crate) fn jvm_version(&self) -> Option<u64> {
+        self.variants
+            .iter()
+            .filter_map(|variant| variant.attributes.get(JVM_VERSION)?.as_u64())
+            .max()
+    }
+
+    /// The names of the variants that this module has, but the other one does not.
+    pub(crate) fn dropped_variants<'a>(&'a self, other: &Module) -> Vec<&'a str> {
+        self.variants
+            .iter()
+            .filter(|variant| !other.variants.iter().any(|v| v.name == variant.name))
+            .map(|variant| variant.name.as_str())
+            .collect()
+    }
+}
+
+impl Variant {
+    /// The classifier of the variant's files, e.g. `linux-x86_64` for `netty-transport-4.1.0-linux-x86_64.jar`.
+    fn classifier(&self, artifact_version: &str) -> Option<&str> {
+        self.files.iter().find_map(|file| {
+            let rest = &file.name[file.name.find(artifact_version)? + artifact_version.len()..];
+            let classifier = rest.strip_prefix('-')?;
+            Some(&classifier[..classifier.rfind('.').unwrap_or(classifier.len())])
+        })
+    }
+
+    /// Describes the variant by its name, its distinguishing attributes, and its classifier.
+    pub(crate) fn describe(&self, version: &str) -> String {
+        let mut details = ATTRIBUTES
+            .iter()
+            .filter_map(|attribute| match self.attributes.get(*attribute)? {
+                Value::String(value) => Some(value.clone()),
+                Value::Null => None,
+                value => Some(value.to_string()),
+            })
+            .filter(|value| value != "library")
+            .collect::<Vec<_>>();
+        if let Some(classifier) = self.classifier(version) {
+            details.push(format!("classifier {}", classifier));
+        }
+        if details.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.name, details.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static MODULE: &str = r#"{
+      "formatVersion": "1.1",
+      "component": { "group": "com.foo", "module": "bar", "version": "1.2.0" },
+      "variants": [
+        {
+          "name": "jvmApiElements-published",
+          "attributes": {
+            "org.gradle.category": "library",
+            "org.gradle.jvm.version": 8,
+            "org.jetbrains.kotlin.platform.type": "jvm"
+          },
+          "files": [ { "name": "bar-jvm-1.2.0.jar", "url": "bar-jvm-1.2.0.jar" } ]
+        },
+        {
+          "name": "linuxX64ApiElements-published",
+          "attributes": {
+            "org.jetbrains.kotlin.native.target": "linux_x64",
+            "org.jetbrains.kotlin.platform.type": "native"
+          },
+          "files": [ { "name": "bar-1.2.0-linux-x64.klib" } ]
+        },
+        { "name": "enforcedApiElements", "attributes": { "org.gradle.category": "enforced-platform" } }
+      ]
+    }"#;
+
+    #[test]
+    fn test_parse() {
+        let module = Module::parse(MODULE).unwrap();
+        let variants = module
+            .variants
+            .iter()
+            .map(|variant| variant.describe("1.2.0"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            variants,
+            vec![
+                "jvmApiElements-published (jvm, 8)",
+                "linuxX64ApiElements-published (native, linux_x64, classifier linux-x64)",
+                "enforcedApiElements (enforced-platform)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jvm_version() {
+        assert_eq!(Module::parse(MODULE).unwrap().jvm_version(), Some(8));
+        let module = Module::parse(r#"{ "variants": [ { "name": "apiElements" } ] }"#).unwrap();
+        assert_eq!(module.jvm_version(), None);
+    }
+
+    #[test]
+    fn test_dropped_variants() {
+        let current = Module::parse(MODULE).unwrap();
+        let latest = Module::parse(
+            r#"{ "variants": [ { "name": "jvmApiElements-published" }, { "name": "jsApiElements-published" } ] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            current.dropped_variants(&latest),
+            vec!["linuxX64ApiElements-published", "enforcedApiElements"]
+        );
+        assert_eq!(
+            latest.dropped_variants(&current),
+            vec!["jsApiElements-published"]
+        );
+    }
+}
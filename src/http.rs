@@ -0,0 +1,323 @@
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use crate::date::{utc_time, MONTHS};
+#[cfg(all(feature = "runtime", feature = "backend-reqwest"))]
+use crate::resolvers::{reqwest_resolver, ErrorKind};
+#[cfg(feature = "runtime")]
+use crate::resolvers::{ClientOptions, Error};
+#[cfg(all(feature = "runtime", not(feature = "backend-reqwest")))]
+use crate::{resolvers::ureq_resolver::Agents, runtime::unblock};
+#[cfg(all(feature = "runtime", not(feature = "backend-reqwest")))]
+use std::convert::TryFrom;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::{Duration, SystemTime};
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+use url::Url;
+
+#[cfg(all(
+    feature = "runtime",
+    not(any(feature = "backend-reqwest", feature = "backend-ureq"))
+))]
+compile_error!(
+    "The runtime needs an HTTP backend, enable the `backend-reqwest` or the `backend-ureq` feature."
+);
+
+#[cfg(feature = "runtime")]
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+/// The time for a whole request, unless overridden by `--timeout` or the resolver.
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+/// How many redirects a request follows, unless overridden by `--max-redirects`.
+pub(crate) const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// The limit for response bodies, unless overridden by `--max-response-size`.
+pub(crate) const DEFAULT_MAX_RESPONSE_SIZE: u64 = 64 * 1024 * 1024;
+
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
+
+/// A client for the JSON APIs next to the repositories, e.g. OSV, deps.dev, or Google's token endpoint.
+///
+/// It sends the requests with the same backend and options as the resolvers, reqwest if it is enabled,
+/// but follows redirects itself, as the requests have no credentials to protect.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Clone)]
+pub(crate) struct ApiClient {
+    #[cfg(feature = "backend-reqwest")]
+    http: reqwest::Client,
+    #[cfg(not(feature = "backend-reqwest"))]
+    agents: Agents,
+}
+
+/// A request of the [`ApiClient`], sent by [`ApiRequest::send`].
+#[cfg(feature = "runtime")]
+#[derive(Debug)]
+pub(crate) struct ApiRequest<'a> {
+    client: &'a ApiClient,
+    method: &'static str,
+    url: String,
+    headers: Vec<(&'static str, String)>,
+    body: Option<String>,
+    timeout: Option<Duration>,
+}
+
+/// The status and the whole body of a response, error statuses included.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ApiResponse {
+    pub(crate) status: u16,
+    pub(crate) body: String,
+}
+
+#[cfg(feature = "runtime")]
+impl ApiClient {
+    #[cfg(feature = "backend-reqwest")]
+    pub(crate) fn new(options: &ClientOptions) -> Result<Self, Error> {
+        let redirects = reqwest::redirect::Policy::limited(options.max_redirects);
+        let http = reqwest_resolver::builder(options)?
+            .redirect(redirects)
+            .build()
+            .map_err(|e| ErrorKind::BuildClient(Box::new(e)))?;
+        Ok(Self { http })
+    }
+
+    #[cfg(not(feature = "backend-reqwest"))]
+    pub(crate) fn new(options: &ClientOptions) -> Result<Self, Error> {
+        let redirects = u32::try_from(options.max_redirects).unwrap_or(u32::MAX);
+        Ok(Self {
+            agents: Agents::new(options, redirects)?,
+        })
+    }
+
+    pub(crate) fn get(&self, url: impl Into<String>) -> ApiRequest<'_> {
+        self.request("GET", url.into())
+    }
+
+    pub(crate) fn post(&self, url: impl Into<String>) -> ApiRequest<'_> {
+        self.request("POST", url.into())
+    }
+
+    fn request(&self, method: &'static str, url: String) -> ApiRequest<'_> {
+        ApiRequest {
+            client: self,
+            method,
+            url,
+            headers: Vec::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl ApiRequest<'_> {
+    pub(crate) fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub(crate) fn json(self, body: &serde_json::Value) -> Self {
+        let mut request = self.header("Content-Type", "application/json");
+        request.body = Some(body.to_string());
+        request
+    }
+
+    pub(crate) fn form(self, fields: &[(&str, &str)]) -> Self {
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(fields)
+            .finish();
+        let mut request = self.header("Content-Type", "application/x-www-form-urlencoded");
+        request.body = Some(body);
+        request
+    }
+
+    pub(crate) fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    #[cfg(feature = "backend-reqwest")]
+    pub(crate) async fn send(self) -> Result<ApiResponse, BoxError> {
+        let method = reqwest::Method::from_bytes(self.method.as_bytes())?;
+        let mut request = self.client.http.request(method, &self.url);
+        for (name, value) in self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(body) = self.body {
+            request = request.body(body);
+        }
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        let body = response.text().await?;
+        Ok(ApiResponse { status, body })
+    }
+
+    #[cfg(not(feature = "backend-reqwest"))]
+    pub(crate) async fn send(self) -> Result<ApiResponse, BoxError> {
+        let url = Url::parse(&self.url)?;
+        let mut request = self.client.agents.request(self.method, &url);
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        let body = self.body;
+        unblock(move || {
+            let response = match body {
+                Some(body) => request.send_string(&body),
+                None => request.call(),
+            };
+            let response = match response {
+                Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+                Err(e) => return Err(BoxError::from(e)),
+            };
+            let status = response.status();
+            let body = response.into_string()?;
+            Ok(ApiResponse { status, body })
+        })
+        .await?
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl ApiResponse {
+    pub(crate) fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+pub(crate) fn content_type_charset(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+/// Parses the delay in seconds or the HTTP date of a `Retry-After` header.
+pub(crate) fn retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = parse_http_date(value)?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+/// Parses the IMF-fixdate format, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_, date) = value.split_once(", ")?;
+    let mut parts = date.split(' ');
+    let day = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as i64 + 1;
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let mut time = parts.next()?.split(':').map(|t| t.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    if parts.next() != Some("GMT")
+        || !(0..=23).contains(&hour)
+        || !(0..=59).contains(&minute)
+        || !(0..=60).contains(&second)
+    {
+        return None;
+    }
+    utc_time((year, month, day), (hour, minute, second))
+}
+
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+/// Replaces the default User-Agent, or appends to it if the given one starts with `+`.
+pub(crate) fn user_agent(user_agent: Option<&str>) -> String {
+    match user_agent {
+        None => String::from(APP_USER_AGENT),
+        Some(user_agent) => match user_agent.strip_prefix('+') {
+            Some(suffix) => format!("{} {}", APP_USER_AGENT, suffix.trim()),
+            None => String::from(user_agent),
+        },
+    }
+}
+
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+/// Credentials are only sent to the origin of the resolver, unless cross-origin forwarding is allowed.
+///
+/// Credentials are never sent when a redirect downgrades from https to http.
+pub(crate) fn forward_auth(resolver: &Url, target: &Url, cross_origin: bool) -> bool {
+    if resolver.scheme() == "https" && target.scheme() != "https" {
+        return false;
+    }
+    cross_origin || resolver.origin() == target.origin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+    use test_case::test_case;
+
+    #[test_case("text/xml" => None; "no charset")]
+    #[test_case("text/xml; charset=ISO-8859-1" => Some("ISO-8859-1"); "charset")]
+    #[test_case("text/xml;Charset=\"utf-8\"" => Some("utf-8"); "quoted charset")]
+    #[test_case("application/xml; q=1; charset=windows-1252" => Some("windows-1252"); "other parameters")]
+    fn test_content_type_charset(content_type: &str) -> Option<&str> {
+        content_type_charset(content_type)
+    }
+
+    #[test_case("120" => Some(Duration::from_secs(120)); "seconds")]
+    #[test_case("Sun, 06 Nov 1994 08:50:07 GMT" => Some(Duration::from_secs(30)); "date")]
+    #[test_case("Sun, 06 Nov 1994 08:49:07 GMT" => Some(Duration::ZERO); "date in the past")]
+    #[test_case("Sunday, 06-Nov-94 08:49:37 GMT" => None; "obsolete format")]
+    #[test_case("soon" => None; "invalid")]
+    fn test_retry_after(value: &str) -> Option<Duration> {
+        // Sun, 06 Nov 1994 08:49:37 GMT
+        let now = UNIX_EPOCH + Duration::from_secs(784111777);
+        retry_after(value, now)
+    }
+
+    #[cfg(any(feature = "runtime", feature = "blocking"))]
+    #[test_case(None => String::from(APP_USER_AGENT); "default")]
+    #[test_case(Some("curl/7.85.0") => String::from("curl/7.85.0"); "replace")]
+    #[test_case(Some("+mycorp-ci/1.0") => format!("{} mycorp-ci/1.0", APP_USER_AGENT); "extend")]
+    fn test_user_agent(input: Option<&str>) -> String {
+        user_agent(input)
+    }
+
+    #[cfg(any(feature = "runtime", feature = "blocking"))]
+    #[test_case("https://repo.example.com/maven/a", false => true; "same origin")]
+    #[test_case("https://cdn.example.com/maven/a", false => false; "other host")]
+    #[test_case("https://repo.example.com:8443/maven/a", false => false; "other port")]
+    #[test_case("https://cdn.example.com/maven/a", true => true; "other host allowed")]
+    #[test_case("http://repo.example.com/maven/a", true => false; "downgrade to http")]
+    fn test_forward_auth(target: &str, cross_origin: bool) -> bool {
+        let resolver = Url::parse("https://repo.example.com/maven/").unwrap();
+        forward_auth(&resolver, &Url::parse(target).unwrap(), cross_origin)
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_api_client_options() {
+        let options = ClientOptions {
+            ca_certs: vec!["missing.pem".into()],
+            ..ClientOptions::default()
+        };
+        assert!(ApiClient::new(&options).is_err());
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case(&[("grant_type", "refresh_token"), ("client_id", "a b&c")] => "grant_type=refresh_token&client_id=a+b%26c"; "encoded")]
+    fn test_form(fields: &[(&str, &str)]) -> String {
+        let client = ApiClient::new(&ClientOptions::default()).unwrap();
+        client
+            .post("https://example.com")
+            .form(fields)
+            .body
+            .unwrap()
+    }
+}
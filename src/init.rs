@@ -0,0 +1,415 @@
+use crate::{pom::Pom, resolvers::MAVEN_CENTRAL, VersionCheck};
+use console::style;
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+/// The answers to the questions of the `init` subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Init {
+    /// The name and URL of the resolver, Maven Central is used without one.
+    pub(crate) resolver: Option<(String, String)>,
+    pub(crate) credentials: Credentials,
+    /// The coordinates to check, as they were entered.
+    pub(crate) checks: Vec<String>,
+}
+
+/// Where the credentials of the resolver are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Credentials {
+    None,
+    /// Both the username and the password are written to the config file.
+    Config {
+        username: String,
+        password: String,
+    },
+    /// The password is read from the environment variable.
+    Env {
+        username: String,
+        var: String,
+    },
+    /// The Kerberos ticket of the current user.
+    Negotiate,
+}
+
+/// Asks questions on the output and reads the answers from the input, an empty answer takes the default.
+pub(crate) struct Prompt<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompt<R, W> {
+    pub(crate) fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    /// Asks all questions, and offers the dependencies of the `pom` to be checked.
+    pub(crate) fn ask(&mut self, pom: Option<(&Path, &Pom)>) -> io::Result<Init> {
+        let url = self.line(&format!(
+            "The URL of the repository to resolve versions from [{}]: ",
+            MAVEN_CENTRAL
+        ))?;
+        let resolver = if url.is_empty() || url.trim_end_matches('/') == MAVEN_CENTRAL {
+            None
+        } else {
+            let name = self
+                .line("A name for the repository, to use it as `--resolver NAME` [internal]: ")?;
+            let name = if name.is_empty() {
+                String::from("internal")
+            } else {
+                name
+            };
+            Some((name, url))
+        };
+
+        let credentials = match &resolver {
+            Some((name, _)) => self.credentials(name)?,
+            None => Credentials::None,
+        };
+
+        let mut checks = Vec::new();
+        if let Some((path, pom)) = pom.filter(|(_, pom)| !pom.dependencies.is_empty()) {
+            let question = format!(
+                "Check the {} dependencies of {}? [Y/n]: ",
+                pom.dependencies.len(),
+                path.display()
+            );
+            if self.confirm(&question, true)? {
+                checks.extend(
+                    pom.dependencies
+                        .iter()
+                        .map(|c| format!("{}:{}", c.group_id, c.artifact)),
+                );
+            }
+        }
+        writeln!(
+            self.output,
+            "The coordinates to check, in the form of `{{groupId}}:{{artifactId}}[:{{version}}]*`, one per line, and an empty line to finish:"
+        )?;
+        loop {
+            let check = self.line("> ")?;
+            if check.is_empty() {
+                break;
+            }
+            match check.parse::<VersionCheck>() {
+                Ok(_) => checks.push(check),
+                Err(e) => writeln!(self.output, "{}", e)?,
+            }
+        }
+
+        Ok(Init {
+            resolver,
+            credentials,
+            checks,
+        })
+    }
+
+    fn credentials(&mut self, name: &str) -> io::Result<Credentials> {
+        writeln!(self.output, "How does {} authenticate?", style(name).cyan())?;
+        writeln!(self.output, "  1) without credentials")?;
+        writeln!(
+            self.output,
+            "  2) username and password, both in the config file"
+        )?;
+        writeln!(
+            self.output,
+            "  3) username in the config file, password in an environment variable"
+        )?;
+        writeln!(self.output, "  4) the Kerberos ticket of the current user")?;
+        loop {
+            match self.line("Choice [1]: ")?.as_str() {
+                "" | "1" => return Ok(Credentials::None),
+                "2" => {
+                    let username = self.line("Username: ")?;
+                    let password = self.password("Password: ")?;
+                    return Ok(Credentials::Config { username, password });
+                }
+                "3" => {
+                    let username = self.line("Username: ")?;
+                    let default = format!(
+                        "{}_PASSWORD",
+                        name.to_uppercase()
+                            .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+                    );
+                    let var = self.line(&format!("Environment variable [{}]: ", default))?;
+                    let var = if var.is_empty() { default } else { var };
+                    return Ok(Credentials::Env { username, var });
+                }
+                "4" => return Ok(Credentials::Negotiate),
+                _ => writeln!(self.output, "Please answer with 1, 2, 3, or 4")?,
+            }
+        }
+    }
+
+    fn confirm(&mut self, question: &str, default: bool) -> io::Result<bool> {
+        loop {
+            match self.line(question)?.to_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => writeln!(self.output, "Please answer with y or n")?,
+            }
+        }
+    }
+
+    /// The trimmed answer, the end of the input is an empty answer.
+    fn line(&mut self, question: &str) -> io::Result<String> {
+        write!(self.output, "{}", question)?;
+        self.output.flush()?;
+        let mut answer = String::new();
+        self.input.read_line(&mut answer)?;
+        Ok(String::from(answer.trim()))
+    }
+
+    #[cfg(not(test))]
+    fn password(&mut self, question: &str) -> io::Result<String> {
+        rpassword::prompt_password(question)
+    }
+
+    #[cfg(test)]
+    fn password(&mut self, question: &str) -> io::Result<String> {
+        self.line(question)
+    }
+}
+
+impl Init {
+    /// The config file with these answers, with comments that explain the keys.
+    pub(crate) fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# The configuration of latest-maven-version, written by `latest-maven-version init`.\n");
+        out.push_str("# All keys are described in the README.\n\n");
+
+        out.push_str("[run]\n");
+        out.push_str("# The resolvers that are used when none are given with `--resolver`, as names or URLs.\n");
+        match &self.resolver {
+            Some((name, _)) => {
+                let _ = writeln!(out, "resolvers = [{}]", quote(name));
+            }
+            None => {
+                let _ = writeln!(out, "# resolvers = [{}]", quote(MAVEN_CENTRAL));
+            }
+        }
+        out.push_str(
+            "# The coordinates that are checked when none are given on the command line,\n",
+        );
+        out.push_str("# in the form of `{groupId}:{artifactId}[:{version}]*`.\n");
+        if self.checks.is_empty() {
+            out.push_str("checks = []\n");
+        } else {
+            out.push_str("checks = [\n");
+            for check in &self.checks {
+                let _ = writeln!(out, "    {},", quote(check));
+            }
+            out.push_str("]\n");
+        }
+
+        if let Some((name, url)) = &self.resolver {
+            let _ = writeln!(out, "\n[resolvers.{}]", key(name));
+            let _ = writeln!(out, "url = {}", quote(url));
+            match &self.credentials {
+                Credentials::None => {}
+                Credentials::Config { username, password } => {
+                    let _ = writeln!(out, "username = {}", quote(username));
+                    out.push_str("# Anyone who can read this file can read the password.\n");
+                    let _ = writeln!(out, "password = {}", quote(password));
+                }
+                Credentials::Env { username, var } => {
+                    let _ = writeln!(out, "username = {}", quote(username));
+                    out.push_str("# The password is read from this environment variable.\n");
+                    let _ = writeln!(out, "password_env = {}", quote(var));
+                }
+                Credentials::Negotiate => {
+                    out.push_str("# Authenticate with the Kerberos ticket of the current user, needs the `kerberos` feature.\n");
+                    out.push_str("negotiate = true\n");
+                }
+            }
+        }
+
+        out.push_str("\n# Options for certain coordinates, where the artifactId can be `*`.\n");
+        out.push_str("# [coordinates.\"org.neo4j:*\"]\n");
+        out.push_str("# include_pre_releases = false\n");
+        out.push_str("# exclude_qualifiers = [\"alpha\", \"beta\"]\n");
+        out.push_str("# min_version = \"4.0\"\n");
+        out
+    }
+
+    /// Writes the config file, which only the current user can read, as it might have a password.
+    pub(crate) fn write(&self, path: &Path) -> io::Result<()> {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // the mode is only used for new files, an overwritten file might have been readable before
+            if path.exists() {
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        let mut file = options.open(path)?;
+        file.write_all(self.render().as_bytes())
+    }
+}
+
+/// A TOML key, quoted only if it has to be.
+fn key(name: &str) -> String {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        String::from(name)
+    } else {
+        quote(name)
+    }
+}
+
+/// A TOML string, quoted and escaped.
+fn quote(value: &str) -> String {
+    toml::Value::String(String::from(value)).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigFile;
+    use test_case::test_case;
+
+    fn ask(answers: &str, pom: Option<&Pom>) -> Init {
+        let mut output = Vec::new();
+        let pom = pom.map(|pom| (Path::new("pom.xml"), pom));
+        Prompt::new(answers.as_bytes(), &mut output)
+            .ask(pom)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let init = ask("", None);
+        assert_eq!(
+            init,
+            Init {
+                resolver: None,
+                credentials: Credentials::None,
+                checks: Vec::new(),
+            }
+        );
+        let config = ConfigFile::parse(&init.render()).unwrap();
+        assert!(config.run.resolvers.is_empty());
+        assert!(config.run.checks.is_empty());
+    }
+
+    #[test_case("\n1\n" => Credentials::None; "none")]
+    #[test_case("\n2\nalice\ns3cure\n" => Credentials::Config { username: String::from("alice"), password: String::from("s3cure") }; "config")]
+    #[test_case("\n3\nalice\n\n" => Credentials::Env { username: String::from("alice"), var: String::from("INTERNAL_PASSWORD") }; "env")]
+    #[test_case("\n3\nalice\nNEXUS_PASS\n" => Credentials::Env { username: String::from("alice"), var: String::from("NEXUS_PASS") }; "named env")]
+    #[test_case("\n5\n4\n" => Credentials::Negotiate; "negotiate after invalid choice")]
+    fn test_credentials(answers: &str) -> Credentials {
+        let answers = format!("https://nexus.mycorp.com/maven\n{}", answers);
+        let init = ask(&answers, None);
+        assert_eq!(
+            init.resolver,
+            Some((
+                String::from("internal"),
+                String::from("https://nexus.mycorp.com/maven")
+            ))
+        );
+        let config = ConfigFile::parse(&init.render()).unwrap();
+        assert_eq!(config.run.resolvers, vec!["internal"]);
+        assert!(config.resolvers.contains_key("internal"));
+        init.credentials
+    }
+
+    #[test]
+    fn test_checks() {
+        let pom = Pom::parse(
+            r#"<project>
+                <dependencies>
+                    <dependency><groupId>org.neo4j</groupId><artifactId>neo4j</artifactId><version>4.1.0</version></dependency>
+                </dependencies>
+            </project>"#,
+        )
+        .unwrap();
+        let init = ask(
+            "https://repo.maven.apache.org/maven2/\n\ncom.foo:bar:^1\n:invalid\nqux\n\n",
+            Some(&pom),
+        );
+        assert_eq!(init.resolver, None);
+        assert_eq!(
+            init.checks,
+            vec!["org.neo4j:neo4j", "com.foo:bar:^1", "qux"]
+        );
+        let config = ConfigFile::parse(&init.render()).unwrap();
+        assert_eq!(
+            config.run.checks,
+            vec![
+                "org.neo4j:neo4j".parse().unwrap(),
+                "com.foo:bar:^1".parse().unwrap(),
+                "qux".parse().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skip_pom() {
+        let pom = Pom::parse(
+            "<project><dependencies><dependency><groupId>a</groupId><artifactId>b</artifactId></dependency></dependencies></project>",
+        )
+        .unwrap();
+        let init = ask("\nn\n", Some(&pom));
+        assert!(init.checks.is_empty());
+    }
+
+    #[test]
+    fn test_render_escapes() {
+        let init = Init {
+            resolver: Some((
+                String::from("my corp"),
+                String::from("https://repo.mycorp.com"),
+            )),
+            credentials: Credentials::Config {
+                username: String::from("alice"),
+                password: String::from("s3\"cure\\"),
+            },
+            checks: Vec::new(),
+        };
+        let config = ConfigFile::parse(&init.render()).unwrap();
+        let server = crate::Server {
+            id: None,
+            url: String::from("my corp"),
+            auth: None,
+        };
+        assert_eq!(
+            config.resolvers["my corp"].apply(server).auth,
+            Some(crate::Auth::basic("alice", "s3\"cure\\"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_write_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let init = Init {
+            resolver: Some((
+                String::from("internal"),
+                String::from("https://repo.mycorp.com"),
+            )),
+            credentials: Credentials::Config {
+                username: String::from("alice"),
+                password: String::from("s3cure"),
+            },
+            checks: Vec::new(),
+        };
+        let dir = crate::temp::private_dir().await.unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        init.write(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), init.render());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
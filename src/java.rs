@@ -0,0 +1,42 @@
+use crate::{gradle::Module, tree::Effective};
+
+/// The properties that name the Java version that a pom targets, in the order they are looked up.
+static JAVA_PROPERTIES: [&str; 4] = [
+    "maven.compiler.release",
+    "maven.compiler.target",
+    "maven.compiler.source",
+    "java.version",
+];
+
+/// The Java version that a version targets as its pom or its Gradle module tell, e.g. `11` or `1.8`.
+///
+/// The compiler properties of the pom and its parents come first, then the highest version of the Gradle variants.
+pub(crate) fn target(effective: &Effective, module: Option<&Module>) -> Option<String> {
+    JAVA_PROPERTIES
+        .iter()
+        .find_map(|property| effective.properties.get(property))
+        .filter(|java| !java.contains("${"))
+        .or_else(|| module?.jvm_version().map(|java| java.to_string()))
+}
+
+/// The feature release of a Java version, e.g. `8` for `1.8` and `17` for `17.0.2`.
+pub(crate) fn release(java: &str) -> Option<u64> {
+    let java = java.trim();
+    let java = java.strip_prefix("1.").unwrap_or(java);
+    java.split('.').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("11" => Some(11); "release")]
+    #[test_case("1.8" => Some(8); "old")]
+    #[test_case("17.0.2" => Some(17); "patch")]
+    #[test_case(" 21 " => Some(21); "whitespace")]
+    #[test_case("${java.version}" => None; "uninterpolated")]
+    fn test_release(java: &str) -> Option<u64> {
+        release(java)
+    }
+}
@@ -0,0 +1,273 @@
+use crate::resolvers::Auth;
+use console::style;
+use std::fmt::Display;
+
+/// Kerberos support needs the `kerberos` feature, which links against the system's GSSAPI library.
+#[derive(Debug)]
+#[cfg_attr(feature = "kerberos", allow(dead_code))]
+pub(crate) struct Unsupported;
+
+/// SPNEGO (`Authorization: Negotiate`) with the Kerberos ticket of the current user, e.g. from `kinit`.
+#[cfg(feature = "kerberos")]
+pub(crate) fn negotiate() -> Result<Auth, Unsupported> {
+    Ok(Auth::Signed(std::sync::Arc::new(negotiate::Negotiate)))
+}
+
+#[cfg(not(feature = "kerberos"))]
+pub(crate) fn negotiate() -> Result<Auth, Unsupported> {
+    Err(Unsupported)
+}
+
+impl Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Kerberos authentication is not available in this build, please install with {}",
+            style("cargo install latest-maven-version --features kerberos").cyan(),
+        )
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+#[cfg(feature = "kerberos")]
+mod negotiate {
+    use super::gssapi;
+    use crate::resolvers::RequestSigner;
+    use async_trait::async_trait;
+    use url::Url;
+
+    type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    /// Creates a new SPNEGO token for the `HTTP@{host}` service of every request.
+    ///
+    /// The Kerberos library caches the service tickets, so only the first request goes to the KDC.
+    #[derive(Debug)]
+    pub(super) struct Negotiate;
+
+    #[async_trait]
+    impl RequestSigner for Negotiate {
+        async fn sign(&self, url: &Url) -> Result<Vec<(String, String)>, BoxError> {
+            let host = url
+                .host_str()
+                .ok_or("Kerberos authentication needs a host")?
+                .to_owned();
+            let token = tokio::task::spawn_blocking(move || gssapi::spnego_token(&host)).await??;
+            Ok(vec![(
+                String::from("Authorization"),
+                format!("Negotiate {}", base64::encode(token)),
+            )])
+        }
+    }
+}
+
+/// The few GSSAPI functions that are needed to create an initial SPNEGO token.
+#[cfg(feature = "kerberos")]
+#[allow(non_camel_case_types)]
+mod gssapi {
+    use std::{ffi::c_void, fmt::Display, ptr};
+
+    type OM_uint32 = u32;
+    type gss_name_t = *mut c_void;
+    type gss_ctx_id_t = *mut c_void;
+
+    #[repr(C)]
+    struct gss_buffer_desc {
+        length: usize,
+        value: *mut c_void,
+    }
+
+    #[repr(C)]
+    struct gss_OID_desc {
+        length: OM_uint32,
+        elements: *mut c_void,
+    }
+
+    /// 1.2.840.113554.1.2.1.4, names like `HTTP@host`.
+    static NT_HOSTBASED_SERVICE: &[u8] = b"\x2a\x86\x48\x86\xf7\x12\x01\x02\x01\x04";
+
+    /// 1.3.6.1.5.5.2
+    static SPNEGO: &[u8] = b"\x2b\x06\x01\x05\x05\x02";
+
+    /// The calling and routine error bits of a major status.
+    const GSS_ERROR_MASK: OM_uint32 = 0xffff_0000;
+    const GSS_C_GSS_CODE: i32 = 1;
+    const GSS_C_MECH_CODE: i32 = 2;
+
+    #[cfg_attr(target_os = "macos", link(name = "GSS", kind = "framework"))]
+    #[cfg_attr(not(target_os = "macos"), link(name = "gssapi_krb5"))]
+    extern "C" {
+        fn gss_import_name(
+            minor_status: *mut OM_uint32,
+            input_name_buffer: *mut gss_buffer_desc,
+            input_name_type: *mut gss_OID_desc,
+            output_name: *mut gss_name_t,
+        ) -> OM_uint32;
+
+        #[allow(clippy::too_many_arguments)]
+        fn gss_init_sec_context(
+            minor_status: *mut OM_uint32,
+            initiator_cred_handle: *mut c_void,
+            context_handle: *mut gss_ctx_id_t,
+            target_name: gss_name_t,
+            mech_type: *mut gss_OID_desc,
+            req_flags: OM_uint32,
+            time_req: OM_uint32,
+            input_chan_bindings: *mut c_void,
+            input_token: *mut gss_buffer_desc,
+            actual_mech_type: *mut *mut gss_OID_desc,
+            output_token: *mut gss_buffer_desc,
+            ret_flags: *mut OM_uint32,
+            time_rec: *mut OM_uint32,
+        ) -> OM_uint32;
+
+        fn gss_display_status(
+            minor_status: *mut OM_uint32,
+            status_value: OM_uint32,
+            status_type: i32,
+            mech_type: *mut gss_OID_desc,
+            message_context: *mut OM_uint32,
+            status_string: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+
+        fn gss_release_buffer(
+            minor_status: *mut OM_uint32,
+            buffer: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+
+        fn gss_release_name(minor_status: *mut OM_uint32, name: *mut gss_name_t) -> OM_uint32;
+
+        fn gss_delete_sec_context(
+            minor_status: *mut OM_uint32,
+            context_handle: *mut gss_ctx_id_t,
+            output_token: *mut gss_buffer_desc,
+        ) -> OM_uint32;
+    }
+
+    #[derive(Debug)]
+    pub(super) struct GssError(String);
+
+    impl gss_buffer_desc {
+        fn empty() -> Self {
+            Self {
+                length: 0,
+                value: ptr::null_mut(),
+            }
+        }
+
+        fn to_vec(&self) -> Vec<u8> {
+            if self.value.is_null() {
+                return Vec::new();
+            }
+            // SAFETY: the buffer was filled by GSSAPI and is valid for `length` bytes
+            unsafe { std::slice::from_raw_parts(self.value as *const u8, self.length) }.to_vec()
+        }
+    }
+
+    impl gss_OID_desc {
+        fn new(oid: &'static [u8]) -> Self {
+            Self {
+                length: oid.len() as OM_uint32,
+                // GSSAPI does not modify the OID
+                elements: oid.as_ptr() as *mut c_void,
+            }
+        }
+    }
+
+    /// The initial token of the security context for `HTTP@{host}`.
+    pub(super) fn spnego_token(host: &str) -> Result<Vec<u8>, GssError> {
+        let service = format!("HTTP@{}", host);
+        let mut minor = 0;
+        let mut name_buffer = gss_buffer_desc {
+            length: service.len(),
+            value: service.as_ptr() as *mut c_void,
+        };
+        let mut name_type = gss_OID_desc::new(NT_HOSTBASED_SERVICE);
+        let mut name: gss_name_t = ptr::null_mut();
+        // SAFETY: all pointers are valid for the duration of the call
+        let major =
+            unsafe { gss_import_name(&mut minor, &mut name_buffer, &mut name_type, &mut name) };
+        check(major, minor)?;
+
+        let mut mech = gss_OID_desc::new(SPNEGO);
+        let mut context: gss_ctx_id_t = ptr::null_mut();
+        let mut output = gss_buffer_desc::empty();
+        // SAFETY: null pointers select the defaults (credentials of the current user, no input token)
+        let major = unsafe {
+            gss_init_sec_context(
+                &mut minor,
+                ptr::null_mut(),
+                &mut context,
+                name,
+                &mut mech,
+                0,
+                0,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut output,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+        let token = output.to_vec();
+
+        let mut ignored = 0;
+        // SAFETY: the handles were created above and are released exactly once
+        unsafe {
+            gss_release_buffer(&mut ignored, &mut output);
+            gss_delete_sec_context(&mut ignored, &mut context, ptr::null_mut());
+            gss_release_name(&mut ignored, &mut name);
+        }
+
+        check(major, minor)?;
+        Ok(token)
+    }
+
+    fn check(major: OM_uint32, minor: OM_uint32) -> Result<(), GssError> {
+        if major & GSS_ERROR_MASK == 0 {
+            return Ok(());
+        }
+        let mut messages = status_messages(major, GSS_C_GSS_CODE);
+        messages.extend(status_messages(minor, GSS_C_MECH_CODE));
+        Err(GssError(messages.join(": ")))
+    }
+
+    fn status_messages(status: OM_uint32, status_type: i32) -> Vec<String> {
+        let mut messages = Vec::new();
+        let mut message_context = 0;
+        loop {
+            let mut minor = 0;
+            let mut message = gss_buffer_desc::empty();
+            // SAFETY: all pointers are valid for the duration of the call
+            let major = unsafe {
+                gss_display_status(
+                    &mut minor,
+                    status,
+                    status_type,
+                    ptr::null_mut(),
+                    &mut message_context,
+                    &mut message,
+                )
+            };
+            if major & GSS_ERROR_MASK != 0 {
+                break;
+            }
+            messages.push(String::from_utf8_lossy(&message.to_vec()).trim().to_owned());
+            // SAFETY: the message was allocated by GSSAPI
+            unsafe { gss_release_buffer(&mut minor, &mut message) };
+            if message_context == 0 {
+                break;
+            }
+        }
+        messages
+    }
+
+    impl Display for GssError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Kerberos authentication failed: {}", self.0)
+        }
+    }
+
+    impl std::error::Error for GssError {}
+}
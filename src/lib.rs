@@ -0,0 +1,401 @@
+//! Finds the latest versions of Maven artifacts, like the `latest-maven-version` binary does.
+//!
+//! Coordinates are checked with a [`Resolver`], e.g. the one of [`maven_central`] or a [`UrlResolver`] for any other repository,
+//! which sends its requests with a [`Client`].
+//!
+//! ```no_run
+//! # #[cfg(feature = "runtime")]
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use latest_maven_version::{check_latest, client, maven_central, VersionCheck};
+//!
+//! let check = "org.neo4j:neo4j:^4".parse::<VersionCheck>()?;
+//! let client = client()?;
+//! for (requirement, latest) in check_latest(&maven_central(), &client, &check).await? {
+//!     match latest {
+//!         Some(latest) => println!("Latest version matching {}: {}", requirement, latest),
+//!         None => println!("No version matching {}", requirement),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+#[cfg(feature = "runtime")]
+use config::CoordinatesConfig;
+#[cfg(feature = "runtime")]
+use deps_dev::DepsDev;
+#[cfg(feature = "runtime")]
+use license::Policy;
+#[cfg(feature = "runtime")]
+use osv::{Osv, Severity};
+#[cfg(feature = "runtime")]
+use regex::Regex;
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+use resolvers::ClientOptions;
+#[cfg(feature = "runtime")]
+use signature::Gpg;
+#[cfg(feature = "runtime")]
+use sigstore::Cosign;
+use std::fmt::{self, Display};
+#[cfg(feature = "runtime")]
+use std::time::{Duration, SystemTime};
+use versions::Selection;
+#[cfg(feature = "runtime")]
+use versions::{Bump, Snapshots, VersionOrder};
+
+#[cfg(feature = "runtime")]
+pub use cache::{CachingResolver, DiskCache, MemoryCache, MetadataCache, NoCache};
+#[cfg(feature = "runtime")]
+pub use check::{CheckRequest, CheckRequestBuilder, CheckResult};
+pub use checksum::Algorithm;
+pub use metadata::Metadata;
+pub use progress::Progress;
+#[cfg(all(feature = "web-fetch", target_arch = "wasm32", target_os = "unknown"))]
+pub use resolvers::WebFetch;
+pub use resolvers::{
+    Auth, Client, Download, DynResolver, Error, ErrorKind, Fetch, FetchClient, FetchRequest,
+    FetchResponse, MaybeSendSync, MultiResolver, RequestOptions, RequestSigner, Resolver,
+    RoutingResolver, Strategy, TokenSource, UrlResolver,
+};
+pub use search::Query;
+#[cfg(feature = "runtime")]
+pub use tokio_util::sync::CancellationToken;
+pub use versions::{PublishedVersion, Requirement, Versions};
+
+mod artifactory;
+#[cfg(feature = "runtime")]
+mod aws;
+#[cfg(feature = "runtime")]
+mod cache;
+#[cfg(feature = "runtime")]
+mod check;
+mod checksum;
+#[cfg(feature = "runtime")]
+#[doc(hidden)]
+pub mod cli;
+#[cfg(feature = "runtime")]
+mod compare;
+#[cfg(feature = "runtime")]
+mod config;
+mod date;
+#[cfg(feature = "runtime")]
+mod deps_dev;
+#[cfg(feature = "runtime")]
+mod diff;
+#[cfg(test)]
+mod fixtures;
+#[cfg(feature = "runtime")]
+mod gcp;
+#[cfg(feature = "runtime")]
+mod gradle;
+mod http;
+#[cfg(feature = "runtime")]
+mod init;
+#[cfg(feature = "runtime")]
+mod java;
+#[cfg(feature = "runtime")]
+mod kerberos;
+#[cfg(feature = "runtime")]
+mod license;
+mod listing;
+mod maven_version;
+mod metadata;
+#[cfg(feature = "runtime")]
+mod mock_repo;
+#[cfg(feature = "runtime")]
+mod opts;
+#[cfg(feature = "runtime")]
+mod osv;
+mod parse;
+#[cfg(feature = "runtime")]
+mod pom;
+#[cfg(feature = "runtime")]
+mod presets;
+mod progress;
+mod regex;
+#[cfg(feature = "runtime")]
+mod release_notes;
+mod resolvers;
+mod runtime;
+mod search;
+#[cfg(feature = "runtime")]
+mod settings;
+#[cfg(feature = "runtime")]
+mod signature;
+#[cfg(feature = "runtime")]
+mod sigstore;
+#[cfg(any(feature = "runtime", test))]
+mod temp;
+#[cfg(feature = "runtime")]
+mod tree;
+#[cfg(feature = "runtime")]
+mod validate;
+#[cfg(feature = "runtime")]
+mod vcr;
+mod versions;
+#[cfg(feature = "runtime")]
+mod xml;
+
+#[cfg(feature = "runtime")]
+#[derive(Debug, Clone)]
+struct Server {
+    /// The repository id, as used in Maven settings and poms.
+    id: Option<String>,
+    url: String,
+    auth: Option<Auth>,
+}
+
+#[cfg(feature = "runtime")]
+#[derive(Debug, Clone)]
+struct Config {
+    include_pre_releases: bool,
+    snapshots: Snapshots,
+    order: VersionOrder,
+    merge_resolvers: bool,
+    directory_listing: bool,
+    use_release_tag: bool,
+    max_metadata_age: Option<Duration>,
+    strict_versions: bool,
+    resolve_snapshots: bool,
+    release_age: bool,
+    fail_on_update: Option<Bump>,
+    show_skipped: bool,
+    variants: bool,
+    verify_artifact: bool,
+    require_checksums: bool,
+    with_url: bool,
+    details: bool,
+    release_notes: bool,
+    /// Looks up the latest versions on deps.dev, with `--deps-dev`.
+    deps_dev: Option<DepsDev>,
+    /// Looks up known vulnerabilities, with `--audit` or `--avoid-vulnerable`.
+    osv: Option<Osv>,
+    avoid_vulnerable: bool,
+    /// Skips versions with vulnerabilities of at least this severity, and fails without others,
+    /// with `--deny-vulnerable`.
+    deny_vulnerable: Option<Severity>,
+    /// Skips versions that need a newer Java release than this, with `--max-java`.
+    max_java: Option<u64>,
+    /// Checks the licenses of the latest versions, with `--license-check`.
+    license_policy: Option<Policy>,
+    avoid_disallowed_licenses: bool,
+    /// Verifies the signatures of the latest versions, with `--verify-signature`.
+    gpg: Option<Gpg>,
+    /// Verifies the Sigstore bundles of the latest versions, with `--verify-sigstore`.
+    sigstore: Option<Cosign>,
+    flag_stale: Option<Duration>,
+    exclude_qualifiers: Vec<String>,
+    ignore_versions: Vec<Regex>,
+    min_version: Option<String>,
+    released_after: Option<SystemTime>,
+    released_before: Option<SystemTime>,
+    /// The options of the configuration file for certain coordinates.
+    coordinates: Vec<(Coordinates, CoordinatesConfig)>,
+    /// How many coordinates are checked at the same time, with `--jobs`.
+    jobs: usize,
+}
+
+#[cfg(feature = "runtime")]
+impl Config {
+    /// The versions to consider for the coordinates, including their options from the configuration file.
+    fn selection(&self, coordinates: &Coordinates) -> Selection {
+        let mut selection = Selection {
+            include_pre_releases: self.include_pre_releases,
+            snapshots: self.snapshots,
+            order: self.order,
+            exclude_qualifiers: self.exclude_qualifiers.clone(),
+            ignore_versions: self.ignore_versions.clone(),
+            min_version: self.min_version.clone(),
+            released_after: self.released_after,
+            released_before: self.released_before,
+        };
+        // exact coordinates sort after `group:*`, so that their options win
+        for (_, config) in self
+            .coordinates
+            .iter()
+            .filter(|(c, _)| c.matches(coordinates))
+        {
+            if let Some(include_pre_releases) = config.include_pre_releases {
+                selection.include_pre_releases = include_pre_releases;
+            }
+            if let Some(order) = config.ordering {
+                selection.order = order;
+            }
+            if let Some(exclude_qualifiers) = &config.exclude_qualifiers {
+                selection.exclude_qualifiers = exclude_qualifiers.clone();
+            }
+            selection
+                .ignore_versions
+                .extend(config.ignore_versions.iter().cloned());
+            if self.min_version.is_none() && config.min_version.is_some() {
+                selection.min_version = config.min_version.clone();
+            }
+        }
+        selection
+    }
+
+    /// Whether snapshots are considered for any coordinates, which needs the snapshot repositories.
+    fn matches_snapshots(&self) -> bool {
+        self.coordinates
+            .iter()
+            .filter_map(|(_, config)| config.include_pre_releases)
+            .chain(std::iter::once(self.include_pre_releases))
+            .any(|include_pre_releases| self.snapshots.are_matched(include_pre_releases))
+    }
+}
+
+/// The groupId and the artifactId of an artifact, e.g. `org.neo4j:neo4j`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Coordinates {
+    group_id: String,
+    artifact: String,
+}
+
+/// The artifact of coordinates like `org.neo4j.gds:*`, which check all artifacts of the group.
+static ANY_ARTIFACT: &str = "*";
+
+impl Coordinates {
+    fn any_artifact(group_id: &str) -> Self {
+        Self {
+            group_id: String::from(group_id),
+            artifact: String::from(ANY_ARTIFACT),
+        }
+    }
+
+    fn is_any_artifact(&self) -> bool {
+        self.artifact == ANY_ARTIFACT
+    }
+
+    /// Whether these coordinates are the other ones, or all artifacts of their group.
+    fn matches(&self, other: &Coordinates) -> bool {
+        self.group_id == other.group_id
+            && (self.is_any_artifact() || self.artifact == other.artifact)
+    }
+
+    pub fn new<T, U>(group_id: T, artifact: U) -> Self
+    where
+        T: Into<String>,
+        U: Into<String>,
+    {
+        Self {
+            group_id: group_id.into(),
+            artifact: artifact.into(),
+        }
+    }
+
+    pub fn group_id(&self) -> &str {
+        &self.group_id
+    }
+
+    pub fn artifact(&self) -> &str {
+        &self.artifact
+    }
+}
+
+impl Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.group_id, self.artifact)
+    }
+}
+
+/// Coordinates and the requirements that their latest versions must match, e.g. `org.neo4j:neo4j:^4`.
+///
+/// It is parsed like the coordinates of the binary, including a current version like `org.neo4j:neo4j@4.1.0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionCheck {
+    coordinates: Coordinates,
+    versions: Vec<Requirement>,
+    /// The version that is currently used, e.g. from `group:artifact@1.2.3` or a pom.
+    current: Option<PublishedVersion>,
+    /// Whether the coordinates are the parent of a pom from `--pom`.
+    parent: bool,
+}
+
+impl VersionCheck {
+    /// Checks the coordinates for the latest version matching each requirement, or for the latest version at all.
+    pub fn new(coordinates: Coordinates, versions: Vec<Requirement>) -> Self {
+        Self {
+            coordinates,
+            versions,
+            current: None,
+            parent: false,
+        }
+    }
+
+    pub fn coordinates(&self) -> &Coordinates {
+        &self.coordinates
+    }
+}
+
+/// Finds the latest version of the coordinates for each requirement of the check.
+///
+/// Pre releases and snapshots are not considered, and the versions are ordered like semver versions.
+pub async fn check_latest<R, C>(
+    resolver: &R,
+    client: &C,
+    check: &VersionCheck,
+) -> Result<Vec<(Requirement, Option<PublishedVersion>)>, Error>
+where
+    R: Resolver + Sync,
+    C: Client,
+{
+    let versions = resolver.resolve(&check.coordinates, client).await?;
+    Ok(versions.latest_versions(&Selection::default(), check.versions.clone()))
+}
+
+/// Like [`check_latest`], and reports the start and the end of the check to the observer.
+pub async fn check_latest_with_progress<R, C>(
+    resolver: &R,
+    client: &C,
+    check: &VersionCheck,
+    progress: &dyn Progress,
+) -> Result<Vec<(Requirement, Option<PublishedVersion>)>, Error>
+where
+    R: Resolver + Sync,
+    C: Client,
+{
+    progress.on_check_started(&check.coordinates);
+    let result = check_latest(resolver, client, check).await;
+    progress.on_check_finished(&check.coordinates);
+    result
+}
+
+/// Finds the latest version of the coordinates for each requirement of the check, without an async runtime.
+///
+/// The requests are sent on the calling thread with the [`blocking_client`], which a single check
+/// does not need to share with other checks.
+#[cfg(feature = "blocking")]
+pub fn check_latest_blocking<R>(
+    resolver: &R,
+    client: &impl Client,
+    check: &VersionCheck,
+) -> Result<Vec<(Requirement, Option<PublishedVersion>)>, Error>
+where
+    R: Resolver + Sync,
+{
+    futures_executor::block_on(check_latest(resolver, client, check))
+}
+
+/// The HTTP client with the default options of the binary.
+#[cfg(feature = "runtime")]
+pub fn client() -> Result<impl Client, Error> {
+    resolvers::client(client_options())
+}
+
+/// A client that sends its requests on the calling thread, for [`check_latest_blocking`].
+#[cfg(feature = "blocking")]
+pub fn blocking_client() -> Result<impl Client, Error> {
+    resolvers::blocking_client(client_options())
+}
+
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+fn client_options() -> ClientOptions {
+    ClientOptions {
+        max_redirects: http::DEFAULT_MAX_REDIRECTS,
+        ..ClientOptions::default()
+    }
+}
+
+/// Resolves versions from Maven Central.
+pub fn maven_central() -> UrlResolver {
+    UrlResolver::new(resolvers::MAVEN_CENTRAL, None).expect("Maven Central is a valid URL")
+}
@@ -0,0 +1,229 @@
+use console::style;
+use serde::Deserialize;
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+/// Common names and URLs of licenses in poms, and their SPDX identifier.
+///
+/// The names are compared after [`normalize`], so they are lowercase and without a leading `the`.
+static ALIASES: [(&str, &str); 34] = [
+    ("apache license, version 2.0", "Apache-2.0"),
+    ("apache software license, version 2.0", "Apache-2.0"),
+    ("apache license 2.0", "Apache-2.0"),
+    ("apache 2.0", "Apache-2.0"),
+    ("apache 2", "Apache-2.0"),
+    ("asl 2.0", "Apache-2.0"),
+    ("apache.org/licenses/license-2.0", "Apache-2.0"),
+    ("mit license", "MIT"),
+    ("opensource.org/licenses/mit", "MIT"),
+    ("new bsd license", "BSD-3-Clause"),
+    ("bsd 3-clause license", "BSD-3-Clause"),
+    ("bsd license 3", "BSD-3-Clause"),
+    ("opensource.org/licenses/bsd-3-clause", "BSD-3-Clause"),
+    ("simplified bsd license", "BSD-2-Clause"),
+    ("bsd 2-clause license", "BSD-2-Clause"),
+    ("opensource.org/licenses/bsd-2-clause", "BSD-2-Clause"),
+    ("eclipse public license - v 1.0", "EPL-1.0"),
+    ("eclipse public license 1.0", "EPL-1.0"),
+    ("eclipse public license - v 2.0", "EPL-2.0"),
+    ("eclipse public license v2.0", "EPL-2.0"),
+    ("eclipse public license 2.0", "EPL-2.0"),
+    ("eclipse.org/legal/epl-2.0", "EPL-2.0"),
+    ("eclipse distribution license - v 1.0", "BSD-3-Clause"),
+    ("gnu general public license, version 2", "GPL-2.0"),
+    ("gnu general public license, version 3", "GPL-3.0"),
+    ("gnu lesser general public license, version 2.1", "LGPL-2.1"),
+    ("gnu lesser general public license, version 3", "LGPL-3.0"),
+    ("gnu affero general public license, version 3", "AGPL-3.0"),
+    ("gnu affero general public license v3", "AGPL-3.0"),
+    (
+        "common development and distribution license 1.0",
+        "CDDL-1.0",
+    ),
+    ("cddl 1.1", "CDDL-1.1"),
+    ("mozilla public license, version 2.0", "MPL-2.0"),
+    ("mozilla public license 2.0", "MPL-2.0"),
+    ("server side public license, version 1", "SSPL-1.0"),
+];
+
+/// Which licenses the latest versions may have, read from a TOML file with `--license-check`.
+///
+/// Licenses are given by their SPDX identifier, e.g. `Apache-2.0`, or by their name or URL as the poms declare them.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Policy {
+    /// The licenses that are allowed, any license that is not forbidden if it is empty.
+    allowed: Vec<String>,
+    /// The licenses that are never allowed.
+    forbidden: Vec<String>,
+    /// Whether versions whose pom declares no license are allowed.
+    allow_undeclared: bool,
+}
+
+/// Whether a version can be used under the policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    Allowed,
+    /// The pom declares no license, and the policy does not allow that.
+    Undeclared,
+    /// None of the declared licenses is allowed.
+    Disallowed(Vec<String>),
+}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl Policy {
+    pub(crate) fn read(path: &Path) -> Result<Self, Error> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| Error::Read(path.to_path_buf(), e))?;
+        toml::from_str(&content).map_err(|e| Error::Parse(path.to_path_buf(), e))
+    }
+
+    /// Checks the licenses that a pom declares.
+    ///
+    /// Several licenses are alternatives, so that a version is allowed if any of them is.
+    pub(crate) fn check(&self, licenses: &[String]) -> Verdict {
+        if licenses.is_empty() {
+            return if self.allow_undeclared {
+                Verdict::Allowed
+            } else {
+                Verdict::Undeclared
+            };
+        }
+        if licenses.iter().any(|license| self.allows(license)) {
+            Verdict::Allowed
+        } else {
+            Verdict::Disallowed(licenses.to_vec())
+        }
+    }
+
+    fn allows(&self, license: &str) -> bool {
+        let license = spdx(license);
+        let is = |other: &String| spdx(other).eq_ignore_ascii_case(&license);
+        !self.forbidden.iter().any(is) && (self.allowed.is_empty() || self.allowed.iter().any(is))
+    }
+}
+
+/// The SPDX identifier of the license, or its normalized name if it is not a known one.
+fn spdx(license: &str) -> String {
+    let normalized = normalize(license);
+    ALIASES
+        .iter()
+        .find(|(alias, id)| *alias == normalized || id.eq_ignore_ascii_case(&normalized))
+        .map_or(normalized, |(_, id)| String::from(*id))
+}
+
+/// Lowercases the name and strips a leading `the`, or the scheme and the extension of a URL.
+fn normalize(license: &str) -> String {
+    let license = license.trim().to_lowercase();
+    let license = license.strip_prefix("the ").unwrap_or(&license);
+    match license
+        .strip_prefix("https://")
+        .or_else(|| license.strip_prefix("http://"))
+    {
+        Some(url) => {
+            let url = url
+                .strip_prefix("www.")
+                .unwrap_or(url)
+                .trim_end_matches('/');
+            let url = [".txt", ".html", ".php"]
+                .iter()
+                .find_map(|extension| url.strip_suffix(extension))
+                .unwrap_or(url);
+            String::from(url)
+        }
+        None => String::from(license),
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Read(path, e) => write!(
+                f,
+                "Could not read the license policy {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+            Error::Parse(path, e) => write!(
+                f,
+                "Could not parse the license policy {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Read(_, src) => Some(src),
+            Error::Parse(_, src) => Some(src),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn policy() -> Policy {
+        toml::from_str(
+            r#"
+            allowed = ["Apache-2.0", "MIT", "EPL-2.0"]
+            forbidden = ["GPL-3.0"]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test_case("Apache-2.0" => "Apache-2.0"; "spdx")]
+    #[test_case("The Apache Software License, Version 2.0" => "Apache-2.0"; "name")]
+    #[test_case("https://www.apache.org/licenses/LICENSE-2.0.txt" => "Apache-2.0"; "url")]
+    #[test_case("http://opensource.org/licenses/MIT" => "MIT"; "http url")]
+    #[test_case("Some Proprietary License" => "some proprietary license"; "unknown")]
+    fn test_spdx(license: &str) -> String {
+        spdx(license)
+    }
+
+    #[test_case(&["The Apache Software License, Version 2.0"] => Verdict::Allowed; "allowed")]
+    #[test_case(&["GNU General Public License, Version 3", "MIT License"] => Verdict::Allowed; "alternative")]
+    #[test_case(&["GPL-3.0"] => Verdict::Disallowed(vec![String::from("GPL-3.0")]); "forbidden")]
+    #[test_case(&["BUSL-1.1"] => Verdict::Disallowed(vec![String::from("BUSL-1.1")]); "not allowed")]
+    #[test_case(&[] => Verdict::Undeclared; "undeclared")]
+    fn test_check(licenses: &[&str]) -> Verdict {
+        let licenses = licenses
+            .iter()
+            .copied()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        policy().check(&licenses)
+    }
+
+    #[test]
+    fn test_forbidden_only() {
+        let policy = toml::from_str::<Policy>(
+            r#"
+            forbidden = ["AGPL-3.0", "SSPL-1.0"]
+            allow_undeclared = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(policy.check(&[String::from("BUSL-1.1")]), Verdict::Allowed);
+        assert_eq!(
+            policy.check(&[String::from("GNU Affero General Public License, Version 3")]),
+            Verdict::Disallowed(vec![String::from(
+                "GNU Affero General Public License, Version 3"
+            )])
+        );
+        assert_eq!(policy.check(&[]), Verdict::Allowed);
+    }
+}
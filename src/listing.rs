@@ -0,0 +1,236 @@
+use crate::{date::MONTHS, versions::parse_timestamp, Versions};
+use serde::Deserialize;
+use std::{ops::Range, time::SystemTime};
+
+/// The JSON directory listing of the Artifactory storage API.
+#[derive(Debug, Deserialize)]
+struct StorageListing {
+    children: Vec<StorageChild>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StorageChild {
+    uri: String,
+    folder: bool,
+}
+
+/// Parses the version directories from an HTML or JSON directory index.
+///
+/// Only directories are considered, the parent directory and files like `maven-metadata.xml` are skipped.
+pub(crate) fn parse_versions(input: &str) -> Versions {
+    parse_directories(input).into_iter().collect()
+}
+
+/// Parses the names of all directories from an HTML or JSON directory index.
+pub(crate) fn parse_directories(input: &str) -> Vec<String> {
+    let directories = match serde_json::from_str::<StorageListing>(input) {
+        Ok(listing) => listing
+            .children
+            .into_iter()
+            .filter(|child| child.folder)
+            .map(|child| child.uri)
+            .collect::<Vec<_>>(),
+        Err(_) => html_directories(input)
+            .into_iter()
+            .map(|(href, _)| href)
+            .collect(),
+    };
+
+    let mut names = Vec::new();
+    for directory in directories {
+        if let Some(name) = directory_name(&directory) {
+            if !names.iter().any(|n| n == name) {
+                names.push(String::from(name));
+            }
+        }
+    }
+    names
+}
+
+/// Parses the directories of an HTML directory index with the date that is shown next to them,
+/// e.g. `2020-08-27 15:37` on Maven Central or `27-Aug-2020 15:37` on Apache and nginx.
+///
+/// The dates are in UTC. Directories without a date are skipped, and JSON indexes do not have dates.
+pub(crate) fn parse_dates(input: &str) -> Vec<(String, SystemTime)> {
+    html_directories(input)
+        .into_iter()
+        .filter_map(|(href, text)| {
+            let name = directory_name(&href)?;
+            Some((String::from(name), parse_date(text)?))
+        })
+        .collect()
+}
+
+/// The last segment of the directory, without the parent directory.
+fn directory_name(directory: &str) -> Option<&str> {
+    let name = directory.trim_matches('/').rsplit('/').next()?;
+    Some(name).filter(|name| !name.is_empty() && *name != "..")
+}
+
+/// The `href`s of all links that point to a directory, and the text until the next link.
+fn html_directories(input: &str) -> Vec<(String, &str)> {
+    let mut directories = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("href=") {
+        rest = &rest[start + "href=".len()..];
+        let quote = match rest.chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => quote,
+            _ => continue,
+        };
+        rest = &rest[1..];
+        let end = match rest.find(quote) {
+            Some(end) => end,
+            None => break,
+        };
+        let href = &rest[..end];
+        rest = &rest[end + 1..];
+        if href.ends_with('/') && !href.starts_with('?') && !href.starts_with('#') {
+            let text = &rest[..rest.find("href=").unwrap_or(rest.len())];
+            directories.push((String::from(href), text));
+        }
+    }
+    directories
+}
+
+/// The first date in the text, with an optional time, e.g. `2020-08-27 15:37:17`.
+fn parse_date(text: &str) -> Option<SystemTime> {
+    text.char_indices().find_map(|(idx, _)| {
+        let text = &text[idx..];
+        let (year, month, day, rest) = iso_date(text).or_else(|| index_date(text))?;
+        let (hour, minute, second) = rest
+            .strip_prefix(' ')
+            .and_then(|time| time_of_day(time.trim_start()))
+            .unwrap_or_default();
+        parse_timestamp(&format!(
+            "{}{:02}{}{:02}{:02}{:02}",
+            year, month, day, hour, minute, second
+        ))
+    })
+}
+
+/// A date like `2020-08-27`.
+fn iso_date(text: &str) -> Option<(&str, u32, &str, &str)> {
+    if text.get(4..5)? != "-" || text.get(7..8)? != "-" {
+        return None;
+    }
+    let month = digits(text, 5..7)?.parse().ok()?;
+    Some((
+        digits(text, 0..4)?,
+        month,
+        digits(text, 8..10)?,
+        &text[10..],
+    ))
+}
+
+/// A date like `27-Aug-2020`.
+fn index_date(text: &str) -> Option<(&str, u32, &str, &str)> {
+    if text.get(2..3)? != "-" || text.get(6..7)? != "-" {
+        return None;
+    }
+    let month = text.get(3..6)?;
+    let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(month))? + 1;
+    Some((
+        digits(text, 7..11)?,
+        month as u32,
+        digits(text, 0..2)?,
+        &text[11..],
+    ))
+}
+
+/// A time like `15:37` or `15:37:17`.
+fn time_of_day(text: &str) -> Option<(u32, u32, u32)> {
+    if text.get(2..3)? != ":" {
+        return None;
+    }
+    let hour = digits(text, 0..2)?.parse().ok()?;
+    let minute = digits(text, 3..5)?.parse().ok()?;
+    let second = match text.get(5..6) {
+        Some(":") => digits(text, 6..8)?.parse().ok()?,
+        _ => 0,
+    };
+    Some((hour, minute, second))
+}
+
+fn digits(text: &str, range: Range<usize>) -> Option<&str> {
+    text.get(range)
+        .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_html_index() {
+        let input = r#"<html><body><h1>Index of /com/foo/bar</h1>
+            <a href="../">../</a>
+            <a href="1.0.0/" title="1.0.0/">1.0.0/</a>
+            <a href='1.1.0/'>1.1.0/</a>
+            <a href="https://repo.example.com/com/foo/bar/2.0.0/">2.0.0/</a>
+            <a href="maven-metadata.xml">maven-metadata.xml</a>
+            <a href="?C=M;O=A/">Last modified</a>
+            </body></html>"#;
+        assert_eq!(
+            parse_versions(input),
+            Versions::from(vec!["1.0.0", "1.1.0", "2.0.0"])
+        );
+    }
+
+    #[test]
+    fn test_json_index() {
+        let input = r#"{
+          "repo": "libs-release",
+          "path": "/com/foo/bar",
+          "children": [
+            { "uri": "/1.0.0", "folder": true },
+            { "uri": "/maven-metadata.xml", "folder": false },
+            { "uri": "/1.1.0", "folder": true }
+          ]
+        }"#;
+        assert_eq!(
+            parse_versions(input),
+            Versions::from(vec!["1.0.0", "1.1.0"])
+        );
+    }
+
+    #[test]
+    fn test_dates() {
+        let input = r#"<html><body><h1>Index of /com/foo/bar</h1>
+            <a href="../">../</a>
+            <a href="1.0.0/" title="1.0.0/">1.0.0/</a>                 2020-08-27 15:37         -
+            <a href="1.1.0/">1.1.0/</a>                                27-Aug-2020 15:37:17     -
+            <a href="1.2.0/">1.2.0/</a>                                2021-01-02               -
+            <a href="2.0.0/">2.0.0/</a>                                -
+            <a href="maven-metadata.xml">maven-metadata.xml</a>        2021-01-02 10:00      1234
+            </body></html>"#;
+        let dates = parse_dates(input)
+            .into_iter()
+            .map(|(version, date)| {
+                let secs = date.duration_since(SystemTime::UNIX_EPOCH).unwrap();
+                (version, secs.as_secs())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                (String::from("1.0.0"), 1598542620),
+                (String::from("1.1.0"), 1598542637),
+                (String::from("1.2.0"), 1609545600),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_dates_in_json_index() {
+        let input = r#"{ "children": [ { "uri": "/1.0.0", "folder": true } ] }"#;
+        assert_eq!(parse_dates(input), vec![]);
+    }
+
+    #[test_case(""; "empty")]
+    #[test_case("<html><a href=\"../\">../</a></html>"; "only parent")]
+    #[test_case("<a href=unquoted/>"; "unquoted")]
+    fn test_no_versions(input: &str) {
+        assert_eq!(parse_versions(input), Versions::default());
+    }
+}
@@ -17,6 +17,16 @@
 //! If you do not have a fairly recent make (on macOS, homebrew can install a newer version),
 //! or don't want to use make, you can also run `cargo install --path .`.
 //!
+//! ## HTTP backends
+//!
+//! The requests are sent with `reqwest` by default.
+//! The `backend-ureq` feature sends them with `ureq` instead, which has a much smaller dependency tree:
+//!
+//!     $ cargo install --path . --no-default-features --features backend-ureq
+//!
+//! The `ureq` backend supports neither `--ca-cert` nor `--http2-prior-knowledge`, and it ignores the idle timeout and the TCP keepalive of the connections.
+//! If both backends are enabled, `reqwest` is used.
+//!
 //! # Usage
 //!
 //! Run `latest-maven-version --help` for an overview of all available options.
@@ -34,9 +44,854 @@
 //! You can also enter multiple coordinates, each with their own versions to check against.
 //! The result is printed after all versions were checked successfully.
 //!
+//! ### Alternative ranges
+//!
+//! Every qualifier is a separate bucket with its own latest version.
+//! To get a single latest version of several ranges instead, combine them with `||`, e.g. `~1.1 || ~1.3`.
+//! Exclusions with `!=` apply to all alternatives.
+//!
+//!     $ latest-maven-version 'org.neo4j.gds:proc:~1.1 || ~1.3'
+//!
+//! ### Maven version ranges
+//!
+//! A qualifier can also be a Maven range, e.g. `[1.0,2.0)`, `(,1.5]`, or `[1.2.3]` for exactly that version.
+//! Versions in Maven ranges are completed with zeros, so `(,1.5]` does not match `1.5.1`.
+//! Quote the range for the shell, and use `[1,2) || [3,4)` instead of a union like `[1,2),[3,4)`.
+//!
+//!     $ latest-maven-version 'org.neo4j.gds:proc:[1.1,1.3)'
+//!
+//! ### Excluding versions of a range
+//!
+//! A qualifier can exclude single versions with `!=`, e.g. `^1,!=1.4.2` skips a broken `1.4.2` and stays on the `1.x` range.
+//! Exclusions can follow any range, including Maven ranges like `[1.0,2.0),!=1.4.2`, and on their own, e.g. `!=1.4.2`, they exclude from all versions.
+//!
+//!     $ latest-maven-version 'org.neo4j.gds:proc:^1,!=1.4.2'
+//!
 //! ### Pre Release Versions
 //!
 //! Pre-releases can be included with the `--include-pre-releases` flag (or `-i` for short).
+//! `-SNAPSHOT` versions count as pre-releases, unless they are handled separately:
+//! `--include-snapshots` considers them even without `--include-pre-releases`,
+//! and `--exclude-snapshots` never considers them, e.g. in internal repositories full of snapshots.
+//!
+//! ### Excluding qualifiers
+//!
+//! Instead of all or no pre-releases, `--exclude-qualifier` drops the versions with a certain qualifier,
+//! e.g. `--include-pre-releases --exclude-qualifier alpha --exclude-qualifier beta` considers release candidates and milestones,
+//! but no alphas or betas. The qualifiers are words of the version, compared ignoring case,
+//! so `alpha` matches `1.0.0-alpha01` as well as `1.0.0.Alpha2`.
+//! Qualifiers that should always be excluded can be listed in the `versions` table of the configuration file.
+//!
+//! ```toml
+//! [versions]
+//! exclude_qualifiers = ["alpha", "beta"]
+//! ```
+//!
+//! ### Ignoring versions
+//!
+//! Single versions that should never be used, e.g. a withdrawn release, are dropped with `--ignore-version`,
+//! which takes a regular expression that is matched against the version as it appears in the metadata.
+//! The pattern can match anywhere in the version unless it is anchored with `^` or `$`.
+//! The patterns use the syntax of the [regex crate](https://docs.rs/regex/latest/regex/#syntax).
+//!
+//!     $ latest-maven-version org.apache.logging.log4j:log4j-core --ignore-version '^2\.17\.0$'
+//!
+//! The patterns can also be listed in the configuration file, for all coordinates or only for certain ones.
+//! The artifactId of the coordinates can be `*` to match all artifacts of a group.
+//!
+//! ```toml
+//! [versions]
+//! ignore_versions = ['-rc\d+$']
+//!
+//! [coordinates."org.apache.logging.log4j:log4j-core"]
+//! ignore_versions = ['^2\.17\.0$']
+//! ```
+//!
+//! ### Minimum version
+//!
+//! Use `--min-version` to never consider versions that are older than the given version,
+//! so that wide requirements like `*` don't find an ancient `0.x` version.
+//! The minimum version itself is still considered, and it is compared in the order of `--ordering`.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc:'<2' --min-version 1.5
+//!
+//! The configuration file can have a minimum version for certain coordinates, which is used unless `--min-version` is given.
+//!
+//! ```toml
+//! [coordinates."org.neo4j.gds:*"]
+//! min_version = "2.0"
+//! ```
+//!
+//! ### Java version
+//!
+//! Use `--max-java` to skip the versions that need a newer Java than the one you run, e.g. `--max-java 11`.
+//! The Java version of a version is read from the compiler properties of its pom and its parents, like `maven.compiler.release`,
+//! or from the variants of its Gradle module metadata. Versions whose Java version is unknown are not skipped.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc --max-java 11
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 2.3.5
+//!     Skipped 2.6.0 (Java 17), 2.5.0 (Java 17), 2.4.0 (Java 17), which need a newer Java than 11
+//!
+//! ### Publication dates
+//!
+//! Use `--released-before` to only consider versions that were published before a point in time,
+//! e.g. to wait two weeks before upgrading to a new release, and `--released-after` to only consider recent versions.
+//! Both take a duration before now, like `14d`, or a date in UTC, like `2024-01-31`.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc --released-before 14d
+//!
+//! The dates are read from the directory index of the repository, which takes another request.
+//! Versions without a date in the index are not considered, so this works for repositories like Maven Central
+//! that show the dates next to the version directories.
+//!
+//! ### Release age
+//!
+//! Use `--release-age` to also print how long ago the latest versions were published.
+//! The dates are read from the directory index of the repository, like for `--released-before`.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc --release-age
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 2.1.0, released 2 years ago
+//!
+//! ### Stale dependencies
+//!
+//! Use `--flag-stale` to flag coordinates whose newest version was published longer ago than the given duration,
+//! which points to a dependency that is no longer maintained.
+//! The newest version is the newest of all versions, regardless of the requirements, and its date is read from the directory index of the repository.
+//! Versions without a date are never flagged.
+//!
+//!     $ latest-maven-version com.google.code.findbugs:jsr305 --flag-stale 730d
+//!     Latest version(s) for com.google.code.findbugs:jsr305:
+//!     Latest version matching *: 3.0.2
+//!     The newest version 3.0.2 was released 9 years ago, the artifact might be abandoned
+//!
+//! ### Current version
+//!
+//! Add the version that is currently used after an `@` to see how big the update to each latest version is,
+//! `major`, `minor`, `patch`, or `pre-release` if only the pre release or qualifier changes.
+//! With `--pom`, the versions that the pom declares are used.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc@1.1.4
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 1.3.1, a minor update from 1.1.4
+//!
+//! Use `--fail-on-update` to exit with 1 if any update of at least the given size is available, e.g. `--fail-on-update minor`.
+//!
+//! Use `--show-skipped` to also list the versions between the current and the latest version, which the update jumps over.
+//! Only versions that would be considered are listed, e.g. pre releases only with `--include-pre-releases`.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc@1.1.4 --show-skipped
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 1.3.1, a minor update from 1.1.4
+//!     The update skips 3 version(s): 1.2.0, 1.2.3, 1.3.0
+//!
+//! ### Gradle variants
+//!
+//! Libraries that are published with Gradle have a Gradle Module Metadata file next to their pom, which lists their variants,
+//! e.g. the targets of a Kotlin Multiplatform library.
+//! Use `--variants` to also print the variants of the latest versions.
+//! With a current version, the variants that the current version has but the latest version does not are highlighted,
+//! as an update would break a project that uses them.
+//!
+//!     $ latest-maven-version org.jetbrains.kotlinx:kotlinx-coroutines-core@1.6.4 --variants
+//!     Latest version(s) for org.jetbrains.kotlinx:kotlinx-coroutines-core:
+//!     Latest version matching *: 1.7.0, a minor update from 1.6.4
+//!     The variants of 1.7.0 are jvmApiElements-published (jvm), linuxX64ApiElements-published (native, linux_x64), ...
+//!
+//! ### Verifying artifacts
+//!
+//! Sometimes the metadata lists a version whose upload failed, so that its pom or jar is missing.
+//! Use `--verify-artifact` to check that the files of the latest versions exist.
+//! The pom of every latest version is read to find its packaging, and the artifact is checked with a `HEAD` request, without downloading it.
+//! Poms with `pom` packaging, e.g. BOMs, have no artifact to check.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc --verify-artifact
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 1.3.1
+//!     The files proc-1.3.1.jar of 1.3.1 are missing, the version might be a broken upload
+//!
+//! ### Download URLs
+//!
+//! Use `--with-url` to also print where the artifact of the latest versions can be downloaded, on the resolver that has it.
+//! The URL is of the jar, or of the pom if there is no jar, e.g. for a BOM, and it is checked with a `HEAD` request.
+//! Snapshots use the file of their latest timestamped build, as snapshot repositories usually have no `-SNAPSHOT` files.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc --with-url
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 1.3.1, at https://repo.maven.apache.org/maven2/org/neo4j/gds/proc/1.3.1/proc-1.3.1.jar
+//!
+//! ### Signatures
+//!
+//! Use `--verify-signature` to verify the `.asc` signature of the artifact of the latest versions with `gpg`, and to report who signed it.
+//! Missing keys are fetched from the keyserver of the `gpg` configuration, and the signer of every artifact is remembered in the user's data directory,
+//! so that a later version that is signed by a different key is reported. Use `--keyring` to only trust the keys of a keyring instead.
+//! The command exits with 1 if a version is not signed, or if it is signed by a different key than before, and fails if a signature does not match.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc --verify-signature
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 1.3.1
+//!     1.3.1 is signed by Neo4j Release <release@neo4j.com> (0123456789ABCDEF0123456789ABCDEF01234567), which is trusted on first use
+//!
+//! ### Sigstore
+//!
+//! Use `--verify-sigstore` to verify the Sigstore bundle that is published next to the artifact of the latest versions with `cosign`, as `.sigstore.json` or `.sigstore`,
+//! and to report who signed it. A bundle with an in-toto attestation, e.g. SLSA provenance, is verified against its predicate type, and the builder that it names is reported as well.
+//! Use `--sigstore-identity` and `--sigstore-issuer` to only accept certificates whose identity and OIDC issuer match a regular expression.
+//! Without them, any signer is accepted, which is reported after the signer.
+//! Versions without a bundle are only reported, but the command exits with 1 if a bundle cannot be verified.
+//!
+//!     $ latest-maven-version org.example:lib --verify-sigstore --sigstore-issuer '^https://token.actions.githubusercontent.com$'
+//!     Latest version(s) for org.example:lib:
+//!     Latest version matching *: 1.4.0
+//!     1.4.0 has https://slsa.dev/provenance/v1 provenance signed by https://github.com/example/lib/.github/workflows/release.yml@refs/tags/v1.4.0 (https://token.actions.githubusercontent.com), built by https://github.com/actions/runner/github-hosted
+//!     The identity of 1.4.0 was not checked, use --sigstore-identity to only accept the expected signer
+//!
+//! ### Project details
+//!
+//! Use `--details` to also print what the pom of the latest versions tells about the project: its name and description, its licenses, its project URL, and its source repository.
+//! Only what the pom declares itself is printed, not what it inherits from its parent.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc --details
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 1.3.1
+//!     1.3.1 is Neo4j Graph Data Science :: Procedures: Neo4j Graph Data Science :: Procedures
+//!     1.3.1 is licensed under GNU General Public License, Version 3
+//!     Project: https://github.com/neo4j/graph-data-science/
+//!     Source: https://github.com/neo4j/graph-data-science/
+//!
+//! ### Release notes
+//!
+//! Use `--release-notes` to also print a link to the release notes of the latest versions, if the pom points to a repository on GitHub or GitLab.
+//! With a current version that is outdated, a link to the changes between both versions is printed as well.
+//! The links are guessed from the tag in the pom or are assumed to be `v{version}`, so they might not exist.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc@1.1.4 --release-notes
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 1.3.1, a minor update from 1.1.4
+//!     Release notes: https://github.com/neo4j/graph-data-science/releases/tag/1.3.1
+//!     Changes: https://github.com/neo4j/graph-data-science/compare/1.1.4...1.3.1
+//!
+//! ### Vulnerabilities
+//!
+//! Use `--audit` to also print the known vulnerabilities of the latest versions from the [OSV database](https://osv.dev), by their CVE if they have one, and with their severity if it is known.
+//!
+//!     $ latest-maven-version org.apache.logging.log4j:log4j-core:2.14 --audit
+//!     Latest version(s) for org.apache.logging.log4j:log4j-core:
+//!     Latest version matching ~2.14: 2.14.1
+//!     2.14.1 has known vulnerabilities:
+//!       CVE-2021-44228 (critical): Remote code injection in Log4j
+//!       CVE-2021-45046 (critical): Incomplete fix for Apache Log4j vulnerability
+//!
+//! Use `--avoid-vulnerable` to skip the versions with known vulnerabilities, and get the newest matching version without any instead.
+//! If every matching version has known vulnerabilities, the latest version is kept and its vulnerabilities are printed.
+//!
+//! Use `--deny-vulnerable` as a gate in CI: it skips the versions with known vulnerabilities like `--avoid-vulnerable`,
+//! but exits with 1 if every matching version has them.
+//! With a severity, e.g. `--deny-vulnerable=high`, only vulnerabilities of at least that severity are denied.
+//! The severity is the one that GitHub assigned to its advisory, or else the one of the CVSS v3 score.
+//! Vulnerabilities of unknown severity are always denied.
+//!
+//!     $ latest-maven-version org.apache.logging.log4j:log4j-core:2.14 --deny-vulnerable=high
+//!     Latest version(s) for org.apache.logging.log4j:log4j-core:
+//!     Latest version matching ~2.14: 2.14.1
+//!     Every matching version has known vulnerabilities of high severity or above
+//!     2.14.1 has known vulnerabilities:
+//!       CVE-2021-44228 (critical): Remote code injection in Log4j
+//!       CVE-2021-45046 (critical): Incomplete fix for Apache Log4j vulnerability
+//!
+//! ### License policy
+//!
+//! Use `--license-check <POLICY>` to check the licenses of the latest versions against a policy, and fail if any of them is not allowed.
+//! The policy is a TOML file that lists the allowed and the forbidden licenses, by their SPDX identifier or by their name or URL in the pom.
+//! Common names like `The Apache Software License, Version 2.0` are recognized as their SPDX identifier.
+//! Licenses are inherited from parent poms, and if a pom declares several licenses, any of them can be chosen.
+//!
+//!     # allowed licenses, or any that is not forbidden if this is empty
+//!     allowed = ["Apache-2.0", "MIT", "BSD-3-Clause", "EPL-2.0"]
+//!     forbidden = ["AGPL-3.0", "SSPL-1.0", "BUSL-1.1"]
+//!     # versions whose pom declares no license fail, unless this is set
+//!     allow_undeclared = false
+//!
+//!     $ latest-maven-version com.example:lib --license-check policy.toml
+//!     Latest version(s) for com.example:lib:
+//!     Latest version matching *: 2.0.0
+//!     2.0.0 is licensed under Business Source License 1.1, which the license policy does not allow
+//!
+//! Use `--avoid-disallowed-licenses` to skip the versions whose licenses are not allowed, and get the newest matching version with an allowed license instead.
+//! If no matching version has an allowed license, the latest version is kept and the check fails.
+//!
+//! ### deps.dev
+//!
+//! Use `--deps-dev` to also print what [deps.dev](https://deps.dev) knows about the latest versions:
+//! how many packages depend on them, the [OpenSSF Scorecard](https://securityscorecards.dev) of their source repository,
+//! and the security advisories that affect them. This helps to judge whether a new release is safe to adopt.
+//! deps.dev only knows the versions on Maven Central, others are skipped.
+//!
+//!     $ latest-maven-version org.apache.logging.log4j:log4j-core:2.14 --deps-dev
+//!     Latest version(s) for org.apache.logging.log4j:log4j-core:
+//!     Latest version matching ~2.14: 2.14.1
+//!     2.14.1 has 3642 dependents, 418 of them direct
+//!     The OpenSSF Scorecard of github.com/apache/logging-log4j2 is 6.9/10
+//!     2.14.1 is affected by CVE-2021-44228: Remote code injection in Log4j
+//!     2.14.1 is affected by CVE-2021-45046: Incomplete fix for Apache Log4j vulnerability
+//!
+//! ### Options per coordinates
+//!
+//! The `coordinates` tables of the configuration file override the global options for certain coordinates.
+//! The artifactId can be `*` for all artifacts of a group, and the options of exact coordinates take precedence.
+//!
+//! ```toml
+//! [coordinates."org.springframework:*"]
+//! include_pre_releases = true
+//! exclude_qualifiers = ["alpha", "beta"]
+//! ordering = "maven"
+//! resolver = "https://repo.spring.io/milestone"
+//! ```
+//!
+//! `include_pre_releases` and `ordering` replace `--include-pre-releases` and `--ordering`,
+//! `exclude_qualifiers` replaces the globally excluded qualifiers,
+//! and `resolver` is a URL or the name of a resolver that is used instead of the other resolvers.
+//! `ignore_versions` are added to the global patterns, and `min_version` is used unless `--min-version` is given.
+//!
+//! ### Unparseable versions
+//!
+//! Versions are parsed leniently, e.g. `1.337` as `1.337.0`, but some versions cannot be parsed at all, e.g. `r09`.
+//! These versions are never matched and silently skipped, unless `--strict-versions` is used, which fails with the list of them.
+//! Versions that are excluded anyway, e.g. with `--ignore-version`, are not reported.
+//!
+//!     $ latest-maven-version com.google.guava:guava --strict-versions --ignore-version '^r\d+$'
+//!
+//! ### Version ordering
+//!
+//! Versions are ordered by semantic versioning by default, where any qualifier makes a pre-release.
+//! With `--ordering maven`, they are ordered like Maven orders them instead:
+//! the qualifiers `alpha` < `beta` < `milestone` < `rc` < `snapshot` < release < `sp` are known, ignoring case,
+//! and `Final`, `GA`, and `RELEASE` are the release itself.
+//! Versions like `1.0.0.Final`, `2.3.4.RELEASE`, or `31.1-jre` are then releases, and `2.3.4.SR1` is newer than `2.3.4.RELEASE`.
+//! Either way, versions are printed as they are published, so that they can be pasted into a pom.
+//!
+//!     $ latest-maven-version org.springframework:spring-core --ordering maven
+//!
+//! ### Release tags
+//!
+//! Use `--use-release-tag` to also print the `<latest>` and `<release>` tags of the metadata, and when it was last updated.
+//! Some repositories maintain these tags more reliably than the version list, so a tag that is not the newest version
+//! of the list is highlighted.
+//!
+//! ### Stale metadata
+//!
+//! The `<lastUpdated>` timestamp of the metadata shows whether a mirror still syncs.
+//! Use `--max-metadata-age` to fail when the metadata was last updated longer ago than the given duration, e.g. `--max-metadata-age 30d`.
+//! With this option or `--use-release-tag`, the age of the metadata is printed as well, e.g. `2 days ago`.
+//! Metadata without a `<lastUpdated>` is not checked.
+//!
+//! ### Snapshot builds
+//!
+//! A `-SNAPSHOT` version stands for its latest build, which is deployed with a timestamp and build number.
+//! With `--resolve-snapshots`, a matching snapshot is followed by that build, as read from the metadata of the snapshot version.
+//! The snapshot repository of a resolver is asked first.
+//!
+//!     $ latest-maven-version org.neo4j.gds:proc --include-pre-releases --resolve-snapshots
+//!     Latest version(s) for org.neo4j.gds:proc:
+//!     Latest version matching *: 1.4.0-SNAPSHOT (1.4.0-20240101.123456-7)
+//!
+//! ### All artifacts of a group
+//!
+//! With `*` as the artifact, e.g. `org.neo4j.gds:*`, all artifacts of the group are checked.
+//! The artifacts are read from the directory index of the group, the Artifactory storage API, or the local repository.
+//! Subgroups that show up in the index are skipped, and JitPack and resolver plugins cannot list their artifacts.
+//!
+//!     $ latest-maven-version 'org.neo4j.gds:*:2'
+//!
+//! ### Multiple resolvers
+//!
+//! The `--resolver` option (or `-r` for short) can be given multiple times.
+//! By default, the resolvers are tried in order and the first one that knows about the coordinates is used.
+//! With `--merge-resolvers` (or `-m` for short), all resolvers are queried concurrently and their versions are combined.
+//! This is useful when, for example, releases and snapshots are published to different repositories.
+//!
+//! Some repositories never publish `maven-metadata.xml`.
+//! With `--directory-listing`, the version directories are read from the HTML or JSON directory index instead.
+//!
+//! ### Listing all versions
+//!
+//! The `list` subcommand prints all versions of the coordinates instead of the latest one, newest first.
+//! The versions of the coordinates filter the list, and pre releases are only listed with `--include-pre-releases`.
+//! Use `--limit` to print only the newest versions and `--reverse` to print the oldest version first.
+//!
+//!     $ latest-maven-version list org.neo4j.gds:proc:1.1:1.3 --limit 3
+//!     Versions of org.neo4j.gds:proc:
+//!     1.3.1
+//!     1.3.0
+//!     1.2.3
+//!
+//! ### Counting versions
+//!
+//! The `count` subcommand prints how many versions of the coordinates there are, and how many of them are stable or pre releases.
+//! The versions of the coordinates filter the counted versions, and `--ordering` decides what a pre release is.
+//!
+//!     $ latest-maven-version count org.neo4j.gds:proc org.neo4j.gds:proc:1.3
+//!     org.neo4j.gds:proc: 14 versions, 11 stable, 3 pre releases
+//!     org.neo4j.gds:proc: 3 versions, 2 stable, 1 pre releases
+//!
+//! ### Dependency tree
+//!
+//! The `deps` subcommand prints the dependencies of the newest matching version as a tree, to see what a new version brings along.
+//! The poms are fetched from the same resolvers, including their parents and imported BOMs for the versions.
+//! Only the dependencies that end up on the classpath are listed, i.e. not the optional, `test`, or `provided` ones,
+//! and like in Maven, a dependency that is already listed closer to the root is not expanded again.
+//! `--depth` limits how many levels are listed, 3 by default.
+//!
+//!     $ latest-maven-version deps org.neo4j.gds:proc --depth 2
+//!     Dependencies of org.neo4j.gds:proc:1.3.1:
+//!     ├── org.neo4j.gds:core:1.3.1
+//!     │   ├── org.neo4j.gds:annotations:1.3.1
+//!     │   └── org.jctools:jctools-core:3.0.0
+//!     └── org.neo4j.gds:algo:1.3.1
+//!         └── org.neo4j.gds:core:1.3.1 (already listed)
+//!
+//! ### Comparing versions
+//!
+//! The `compare` subcommand prints what changed structurally between two versions, as far as their poms tell:
+//! the added (`+`), removed (`-`), and changed (`~`) dependencies that end up on the classpath,
+//! and changes of the licenses and of the Java version that the versions target.
+//! The Java version is read from the compiler properties of the pom, or from the Gradle module metadata.
+//!
+//!     $ latest-maven-version compare org.neo4j.gds:proc 1.1.4 1.3.1
+//!     Changes of org.neo4j.gds:proc from 1.1.4 to 1.3.1:
+//!     + org.neo4j.gds:pregel-proc:1.3.1 (compile)
+//!     ~ org.neo4j.gds:core 1.1.4 -> 1.3.1
+//!     ~ org.neo4j.gds:algo 1.1.4 -> 1.3.1
+//!     The Java target changed from 1.8 to 11
+//!
+//! ### Searching
+//!
+//! The `search` subcommand finds artifacts by a part of their name, or with `--class` by a class that they contain,
+//! and prints each of them with its newest version.
+//! Maven Central is searched with the search of search.maven.org, and Artifactory resolvers with the search of Artifactory.
+//! Other resolvers, e.g. Nexus or plain HTTP repositories, cannot be searched.
+//! `--limit` sets how many artifacts are asked from each resolver, 20 by default.
+//!
+//!     $ latest-maven-version search --class com.google.common.collect.ImmutableList --limit 2
+//!     com.google.guava:guava: 33.0.0-jre
+//!     com.google.guava:guava-jdk5: 17.0
+//!
+//! The search also suggests what might have been meant when coordinates cannot be found,
+//! together with the other artifacts of the group, e.g. a typo in the artifact or a group that is one level off:
+//!
+//!     $ latest-maven-version io.projectreactor:reactor-netty
+//!     Error: The coordinates io.projectreactor:reactor-netty could not be found using the resolver https://repo.maven.apache.org/maven2/.
+//!     ...
+//!
+//!     Did you mean:
+//!         io.projectreactor.netty:reactor-netty
+//!
+//! ### Downloading artifacts
+//!
+//! The `download` subcommand downloads the artifact of the newest matching version to the current directory, or to `--dir`.
+//! The file is the jar, unless `--packaging` names another extension, e.g. `pom`, and `--classifier` selects a file like the `sources` jar.
+//! The checksum is read from the strongest sidecar file, e.g. `.sha512` before `.sha1`, and the file is not saved if it does not match.
+//! The artifact is not limited by `--max-response-size`.
+//!
+//!     $ latest-maven-version download org.neo4j.gds:proc:1.1 --dir libs
+//!     Downloaded 1.1.6 of org.neo4j.gds:proc to libs/proc-1.1.6.jar, the SHA-1 checksum matches
+//!
+//! ### Checksums
+//!
+//! Use `--require-checksums` to verify all metadata against its `.sha512`, `.sha256`, or `.sha1` sidecar file, whichever is the strongest the repository has.
+//! A check fails if the metadata does not match, or if the repository has no checksum for it.
+//! This takes another request for each metadata file, which is then not read from the cache.
+//! Downloads are always verified if the repository has a checksum, and with `--require-checksums` they fail without one.
+//!
+//!     $ latest-maven-version --require-checksums download org.neo4j.gds:proc
+//!     Downloaded 1.3.1 of org.neo4j.gds:proc to ./proc-1.3.1.jar, the SHA-1 checksum matches
+//!
+//! ### Comparing resolvers
+//!
+//! The `compare-resolvers` subcommand resolves the coordinates against all given resolvers
+//! and reports the versions that some resolvers are missing, e.g. to find out if a mirror lags behind.
+//! It exits with 1 if any resolver is missing versions.
+//!
+//!     $ latest-maven-version compare-resolvers org.neo4j:neo4j -r https://repo.maven.apache.org/maven2 -r https://nexus.mycorp.com/repository/maven-central
+//!
+//! ### Clojars
+//!
+//! Clojars can be used as resolver with `clojars`.
+//! Coordinates can also be given in the Clojure style `groupId/artifact`,
+//! and like in Leiningen, a single name like `ring` is used as both groupId and artifact.
+//! Pre-release qualifiers are compared case-insensitively, so that `1.0.0-RC1` is newer than `1.0.0-alpha1`.
+//!
+//! ### JitPack
+//!
+//! JitPack can be used as resolver with `jitpack`, e.g. for `com.github.user:repo` coordinates.
+//! Git tags like `v1.2.3` are compared as versions, commit hashes and branch snapshots are ignored.
+//! Since JitPack builds on demand, requests to JitPack wait up to 2 minutes.
+//!
+//! ### Resolver plugins
+//!
+//! Any executable can be used as resolver with `exec:./my-resolver`.
+//! It is started once per coordinates and reads a single line of JSON from stdin, e.g. `{"groupId": "org.neo4j", "artifactId": "neo4j"}`.
+//! It must write the versions as JSON to stdout, e.g. `{"versions": ["4.4.0", "5.1.0"]}`.
+//! An empty list means that the coordinates are unknown, and a non-zero exit code is reported as an error together with stderr.
+//! Executables are only run when they are given with `--resolver` or in the config file, never from a pom or the Maven settings.
+//!
+//! ### Azure Artifacts
+//!
+//! Azure Artifacts feeds can be used as resolver with `azure:{organization}/[{project}/]{feed}`.
+//! Unless `--user` is given, a personal access token is read from the `AZURE_DEVOPS_EXT_PAT` or
+//! `SYSTEM_ACCESSTOKEN` environment variable.
+//!
+//! ### Local repository
+//!
+//! Resolvers can also be `file://` URLs, e.g. for repositories that are mirrored to a network share.
+//!
+//! The local repository can be used as resolver with `local`, or `local:/path/to/repository`.
+//! It reads `<localRepository>` from the Maven settings and defaults to `~/.m2/repository`.
+//! Together with `--merge-resolvers`, this shows versions that are only installed locally.
+//!
+//! ### Artifactory
+//!
+//! Artifactory repositories can use the version search API instead of reading `maven-metadata.xml`,
+//! by prefixing the repository URL with `artifactory:`, e.g. `artifactory:https://repo.mycorp.com/artifactory/libs-release`.
+//! This is much faster for virtual repositories that aggregate many remote repositories.
+//!
+//! ### Amazon S3
+//!
+//! Repositories in S3 buckets can be used as resolver with `s3://{bucket}/{prefix}`.
+//! Requests are signed with the credentials from `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`,
+//! or from the `AWS_PROFILE` in `~/.aws/credentials`, and the region is read from `AWS_REGION` or `~/.aws/config`.
+//! S3 compatible storage can be used with `AWS_ENDPOINT_URL`.
+//!
+//! ### Google Artifact Registry
+//!
+//! Artifact Registry repositories can be used as resolver with
+//! `artifactregistry://{location}-maven.pkg.dev/{project}/{repository}` or their https URL.
+//! Unless `--user` is given, OAuth access tokens from the Application Default Credentials are used,
+//! i.e. from `GOOGLE_APPLICATION_CREDENTIALS`, `gcloud auth application-default login`, or the metadata server.
+//!
+//! ### Google Cloud Storage
+//!
+//! Repositories in Cloud Storage buckets can be used as resolver with `gs://{bucket}/{prefix}`.
+//! Like for Artifact Registry, OAuth access tokens from the Application Default Credentials are used.
+//! Without any credentials, the requests are anonymous, e.g. for public buckets.
+//!
+//! ### Configuration file
+//!
+//! Additional configuration is read from `latest-maven-version/config.toml` in the user's config directory
+//! (e.g. `~/.config/latest-maven-version/config.toml` on Linux), or from the file given with `--config`.
+//!
+//! The `routes` table maps groupId prefixes to resolvers.
+//! Coordinates whose groupId matches a route use that resolver instead of the ones given on the command line.
+//! When multiple routes match, the longest prefix wins.
+//!
+//! ```toml
+//! [routes]
+//! "com.mycorp" = "https://nexus.mycorp.com/repository/maven-releases"
+//! ```
+//!
+//! The `resolvers` table defines named resolvers that can be used in place of a URL,
+//! on the command line (`--resolver internal`) as well as in routes.
+//! The `type` is either `maven` (the default) or `artifactory`, and the `timeout` is given in seconds.
+//! Requests that fail with a server or network error are retried `retries` times (none by default),
+//! waiting `backoff` milliseconds (500 by default) before the first retry and twice as long before every further one.
+//! With `jitter = true`, the delays are randomized. These settings default to the retry options on the command line.
+//! The `headers` are added to every request, e.g. for gateways that authenticate with custom headers.
+//! Like credentials, they are not sent when the resolver redirects to a different host.
+//! Credentials and headers are sent with every request, unless `preemptive_auth = false` is set.
+//! Then they are only sent after the server rejected the anonymous request with a 401, or with a 404,
+//! as some servers hide their resources from anonymous users.
+//! With `max_concurrent_requests`, at most that many requests are sent to the host of the resolver at the same time.
+//!
+//! ```toml
+//! [resolvers.internal]
+//! url = "https://repo.mycorp.com/artifactory/libs-release"
+//! type = "artifactory"
+//! username = "alice"
+//! password = "s3cure"
+//! timeout = 60
+//! retries = 3
+//! backoff = 250
+//! jitter = true
+//! max_concurrent_requests = 4
+//! headers = { X-JFrog-Art-Api = "api-key" }
+//! ```
+//!
+//! Like Maven repositories, a resolver can have a separate `snapshots` URL, e.g. for the Sonatype OSS repositories.
+//! The snapshot repository uses the same type and credentials, and is only queried together with the release
+//! repository when pre-releases are included.
+//!
+//! ```toml
+//! [resolvers.ossrh]
+//! url = "https://oss.sonatype.org/content/repositories/releases"
+//! snapshots = "https://oss.sonatype.org/content/repositories/snapshots"
+//! ```
+//!
+//! Instead of the `password`, a resolver can read it from the environment variable in `password_env`.
+//!
+//! The `run` table holds what is checked without coordinates on the command line.
+//! Its `resolvers`, as URLs or names, are used when none are given with `--resolver`,
+//! and its `checks` are checked when no coordinates or poms are given, e.g. with `latest-maven-version --config tracked.toml`.
+//!
+//! ```toml
+//! [run]
+//! resolvers = ["internal"]
+//! checks = ["org.neo4j:neo4j:^4", "com.mycorp:app"]
+//!
+//! [resolvers.internal]
+//! url = "https://nexus.mycorp.com/repository/maven-public"
+//! username = "ci"
+//! password_env = "NEXUS_PASSWORD"
+//! ```
+//!
+//! The `init` subcommand writes a commented configuration file to `--config`, or to the default file.
+//! It asks for the resolver, how its credentials are kept, and the coordinates to check,
+//! and offers the dependencies of the `pom.xml` in the current directory. An existing file is only overwritten with `--force`.
+//! On unix, only the current user can read the file, as it might hold the password of the resolver.
+//!
+//!     $ latest-maven-version --config tracked.toml init
+//!
+//! The `config validate` subcommand reports the problems of the configuration file with their line numbers, instead of failing in the middle of a run.
+//! It parses the file, every check, pattern, and version, checks the URLs of the resolvers and the resolvers that are referenced by name,
+//! and whether the environment variables of `password_env` are set. It exits with 1 if there are problems.
+//!
+//!     $ latest-maven-version --config tracked.toml config validate
+//!     tracked.toml:3: The artifact may not be empty in org.neo4j:
+//!     tracked.toml:8: The password of internal is read from NEXUS_PASSWORD, which is not set
+//!
+//! The `profiles` table holds named sets of coordinates, which are checked with `--profile NAME`, e.g. for releases, snapshots, or the artifacts of a team.
+//! A profile has `checks`, the `resolvers` for them, and `include_pre_releases`, and it takes the values it does not set from the profile it `extends`.
+//! `--profile` can be given multiple times, and the coordinates of every profile are resolved with the resolvers of that profile,
+//! or with the ones of the run if it has none.
+//!
+//! ```toml
+//! [profiles.base]
+//! resolvers = ["internal"]
+//!
+//! [profiles.releases]
+//! extends = "base"
+//! checks = ["com.mycorp:app", "com.mycorp:client"]
+//!
+//! [profiles.snapshots]
+//! extends = "releases"
+//! include_pre_releases = true
+//! ```
+//!
+//!     $ latest-maven-version --profile releases --profile platform-team
+//!
+//! ### Kerberos
+//!
+//! Resolvers behind Active Directory SSO can authenticate with the Kerberos ticket of the current user (e.g. from `kinit`)
+//! by setting `negotiate = true` in their configuration, unless other credentials are given.
+//! This needs the `kerberos` feature, which links against the system's GSSAPI library:
+//!
+//!     $ cargo install latest-maven-version --features kerberos
+//!
+//! ```toml
+//! [resolvers.corp]
+//! url = "https://artifactory.mycorp.com/artifactory/libs-release"
+//! type = "artifactory"
+//! negotiate = true
+//! ```
+//!
+//! ### Maven settings
+//!
+//! With `--use-maven-settings`, mirrors, server credentials, and proxies are read from `~/.m2/settings.xml`.
+//! A different settings file can be given with `--maven-settings`.
+//! Mirrors replace the resolvers they match (Maven Central is matched as `central`) and use the
+//! credentials of the `<server>` with the same id. `${env.NAME}` placeholders are replaced with environment variables.
+//! Passwords that are encrypted with the master password of `settings-security.xml` are not supported,
+//! their credentials are skipped with a warning.
+//!
+//! Credentials are only sent to the scheme, host, and port of a resolver.
+//! When a resolver redirects to a different host, e.g. a CDN, the credentials are dropped, and they are never sent
+//! over plain http after a redirect from https. Use `--forward-auth-on-redirect` for mirror setups that need them.
+//! Up to 10 redirects are followed per request, which can be changed with `--max-redirects`, where `0` disallows redirects.
+//! With `--verbose`, the final URL of redirected requests is reported on stderr.
+//!
+//! ### Request headers
+//!
+//! With `--header` (or `-H` for short), a header is added to all requests to the resolvers, e.g. for API gateways.
+//! Like credentials, the headers are not sent to other origins after a redirect, nor to other services like OSV.
+//! The option can be given multiple times. Headers for a single resolver can be set in the configuration file.
+//!
+//!     $ latest-maven-version org.neo4j:neo4j -H 'X-Tenant: mycorp'
+//!
+//! A different User-Agent can be sent with `--user-agent`, e.g. for repository firewalls with an allowlist.
+//! A value that starts with `+` is appended to the default User-Agent instead.
+//!
+//!     $ latest-maven-version org.neo4j:neo4j --user-agent '+mycorp-ci/1.0'
+//!
+//! ### Timeouts
+//!
+//! Requests time out after 30 seconds, which can be changed with `--timeout`, e.g. for slow VPN links.
+//! With `--connect-timeout`, establishing the connection can fail fast as well.
+//! Durations are given in seconds or with a unit, e.g. `500ms`, `2m`, `12h`, or `30d`.
+//! The `timeout` of a resolver in the configuration file takes precedence.
+//!
+//!     $ latest-maven-version org.neo4j:neo4j --connect-timeout 2s --timeout 2m
+//!
+//! ### Retries
+//!
+//! Requests that fail with a server or network error, e.g. a 502 from a proxy, are retried `--retries` times.
+//! The first retry waits for `--retry-backoff` (500ms by default), and every further retry twice as long.
+//! With `--retry-jitter`, the delays are randomized, so that concurrent requests do not retry at the same time.
+//! Retries are reported on stderr with `--verbose` (or `-v` for short).
+//! Rate limited requests (status 429) are retried up to three times in any case, after the delay from the
+//! `Retry-After` header. If the server asks to wait for more than a minute, the request fails instead.
+//!
+//!     $ latest-maven-version org.neo4j:neo4j --retries 3 --retry-backoff 1s --retry-jitter -v
+//!
+//! ### Concurrent checks
+//!
+//! At most 8 coordinates are checked at the same time, which can be changed with `--jobs` (or `-j` for short).
+//! This bounds the concurrent requests, e.g. for a large pom or SBOM behind a web application firewall that blocks bursts of requests.
+//!
+//!     $ latest-maven-version --pom pom.xml -j 4
+//!
+//! With `--max-requests-per-host`, at most that many requests are sent to the same host at the same time,
+//! e.g. so that several resolvers do not overload a slow internal server while Maven Central could take more.
+//! The `max_concurrent_requests` of a resolver in the configuration file takes precedence.
+//!
+//!     $ latest-maven-version --pom pom.xml -r internal -r central --max-requests-per-host 2
+//!
+//! ### Progress
+//!
+//! With `--progress`, a line on stderr shows how many checks are running and done, and how many requests and retries were sent,
+//! e.g. while checking a large pom. The line is only shown when stderr is a terminal, and it is cleared before the results are printed.
+//!
+//!     $ latest-maven-version --pom pom.xml --progress
+//!
+//! ### Proxies
+//!
+//! With `--proxy`, all requests go through the given proxy instead of the one from the Maven settings.
+//! Credentials can be part of the proxy URL, or given with `--proxy-user`, which also applies to the proxy from
+//! the Maven settings. The proxy password is read from the environment variable named by `--proxy-password-env`,
+//! or from a secure prompt. Proxy credentials are separate from the resolver credentials.
+//!
+//!     $ PROXY_PASSWORD=s3cure latest-maven-version org.neo4j:neo4j --proxy http://squid.mycorp.com:3128 --proxy-user alice --proxy-password-env PROXY_PASSWORD
+//!
+//! ### IP versions and address overrides
+//!
+//! With `--ipv4` or `--ipv6`, only addresses of that IP version are used, e.g. when IPv6 lookups or routes are broken.
+//! `--resolve host:addr` connects to the given address instead of resolving the host.
+//! The option can be given multiple times, the address part can be a comma separated list,
+//! and IPv6 addresses can be written in brackets. Unlike the `--resolve` of curl, it takes no port:
+//! the override applies to every port of the host, and the connections use the port of the URL.
+//!
+//!     $ latest-maven-version org.neo4j:neo4j -r https://nexus.mycorp.com/repository/maven-central --resolve nexus.mycorp.com:10.0.0.5 --ipv4
+//!
+//! ### HTTP/2
+//!
+//! HTTP/2 is used for every server that offers it over https, and all requests to that server share a single connection.
+//! The first request to a server is sent before the others, so that they can reuse its connection.
+//! For internal servers that support HTTP/2 without negotiating it, e.g. over plain http, use `--http2-prior-knowledge`.
+//! This applies to all resolvers, so all of them must support HTTP/2.
+//!
+//! ### Connection pool
+//!
+//! Connections are kept open for later requests. Idle connections are closed after `--pool-idle-timeout` (`90s` by default),
+//! and `--pool-max-idle-per-host` limits how many idle connections are kept per host, where `0` disables reuse.
+//! The TCP keepalive interval is `60s`, which can be changed with `--tcp-keepalive` or disabled with `--no-tcp-keepalive`.
+//! The same settings can be given in the `pool` table of the configuration file, in seconds, where a `keepalive` of `0` disables it.
+//!
+//! ```toml
+//! [pool]
+//! idle_timeout = 300
+//! max_idle_per_host = 4
+//! keepalive = 30
+//! ```
+//!
+//! ### DNS cache
+//!
+//! The hosts of all resolvers are looked up once at the start, and every connection to them reuses those addresses,
+//! instead of asking the system resolver again. Use `--no-dns-cache` to look them up for every new connection.
+//! Hosts that are only reached through a redirect or a proxy are not cached.
+//!
+//! ### Response size limit
+//!
+//! Responses that are larger than `64M` fail with an error, instead of being read into memory,
+//! e.g. when a misconfigured resolver returns a huge HTML page. The limit can be changed with `--max-response-size`,
+//! e.g. `--max-response-size 512K` or `--max-response-size 1G`.
+//! The limit is meant for metadata and does not apply to the artifacts of `download` or of the checks of a file,
+//! e.g. with `--verify-signature` or `--verify-sigstore`, which are read into memory completely, as they are checked before they are saved.
+//! Metadata is parsed while it is received, and the rest of a response is skipped once all versions are read.
+//! Metadata does not have to be UTF-8: the charset of the `Content-Type` is used, or else the encoding
+//! of the XML declaration, e.g. `<?xml version="1.0" encoding="ISO-8859-1"?>` in older repositories.
+//!
+//! ### Mismatched coordinates
+//!
+//! The `groupId` and `artifactId` of the metadata are compared to the requested coordinates.
+//! Metadata for other coordinates means that the resolver is misconfigured, e.g. a virtual repository or rewrite rule
+//! that routes to the wrong artifact. This is reported as a warning, use `--strict` to fail instead.
+//!
+//! ### Cache
+//!
+//! Downloaded metadata is cached in `latest-maven-version` in the user's cache directory
+//! (e.g. `~/.cache/latest-maven-version` on Linux), so that repeated runs do not download it again.
+//! Cached responses are used for `10m`, which can be changed with `--cache-ttl`.
+//! Use `--refresh` to download everything again, or `--no-cache` to neither read nor write the cache.
+//! Local repositories are never cached.
+//! Within a run, every URL is requested only once, even when the same coordinates are checked multiple times,
+//! e.g. by the modules of a project.
+//!
+//! The `cache` subcommand shows the size of the cache with `stats`, removes all cached responses with `clear`,
+//! and removes the ones that are older than `--cache-ttl` with `prune`.
+//!
+//!     $ latest-maven-version cache prune --cache-ttl 1m
+//!
+//! ### Recording and replaying
+//!
+//! `--record <dir>` writes the responses of the repositories as fixture files into the directory,
+//! and `--replay <dir>` answers the same requests from these files later, without any network access.
+//! This reproduces a problem with a repository, e.g. an Artifactory that behaves differently, without access to it,
+//! and makes integration tests deterministic.
+//! Every fixture is a JSON file with the URL, the status, and the body of one response; not found and other error responses are recorded as well.
+//! Requests that were not recorded fail when they are replayed, and local repositories are never recorded.
+//!
+//!     $ latest-maven-version --resolver https://artifactory.mycorp.com/artifactory/maven --record fixtures com.mycorp:lib
+//!     $ latest-maven-version --resolver https://artifactory.mycorp.com/artifactory/maven --replay fixtures com.mycorp:lib
+//!
+//! ### Mock repository
+//!
+//! The hidden `mock-repo` subcommand serves a maven2 repository over plain HTTP, to test this tool and other Maven clients without a real repository manager.
+//! It serves a directory in the maven2 layout, or a TOML fixture file that declares the versions of artifacts.
+//! Fixture artifacts get metadata, directory indexes, and a pom for every version, and `status` answers all of their requests with an error instead.
+//! Checksums are computed for every file that does not have its own `.sha1`, `.sha256`, or `.sha512` file.
+//!
+//! ```toml
+//! [artifacts."com.example:lib"]
+//! versions = ["1.0.0", "1.1.0", "2.0.0-RC1"]
+//!
+//! [artifacts."com.example:flaky"]
+//! status = 503
+//! ```
+//!
+//!     $ latest-maven-version mock-repo fixture.toml --listen 127.0.0.1:8081
+//!     $ latest-maven-version --resolver http://127.0.0.1:8081 com.example:lib
+//!
+//! ### Custom CA certificates
+//!
+//! Private CAs or TLS-intercepting proxies can be trusted with `--ca-cert path/to/ca.pem`,
+//! in addition to the default root certificates. The option can be given multiple times,
+//! and a file can contain multiple certificates.
+//!
+//! ### Checking a pom
+//!
+//! With `--pom path/to/pom.xml`, all dependencies and plugins of the pom are checked.
+//! The `<repositories>` and `<pluginRepositories>` of the pom are tried first for those coordinates,
+//! using the credentials from the Maven settings if `--use-maven-settings` is given.
+//! Only http and https repositories are used, other URLs, e.g. `file:` or the `exec:` and `local:` resolvers, are ignored with a warning.
+//!
+//! The `<parent>` of the pom is checked as well, since it manages the versions of most dependencies, e.g. `spring-boot-starter-parent`.
+//! Newer versions of the parents are listed again at the end. A parent that cannot be found, e.g. the root of a multi-module project, is skipped.
+//!
+//!     $ latest-maven-version --pom pom.xml
+//!     Latest version(s) for the parent pom org.springframework.boot:spring-boot-starter-parent:
+//!     Latest version matching *: 3.3.4, a minor update from 3.2.5
+//!     ...
+//!     The parent poms can be updated, which also updates the versions they manage:
+//!     org.springframework.boot:spring-boot-starter-parent from 3.2.5 to 3.3.4, a minor update
 //!
 //! ### Version overrides
 //!
@@ -44,6 +899,131 @@
 //! Previous matches will – depending on the range – consume all versions that would have also been matched by later qualifiers.
 //! Try to define the qualifiers in the order from most restrictive to least.
 //!
+//! # Library
+//!
+//! The checks are also available as the `latest_maven_version` library, to embed them in other tools instead of running the binary.
+//! It exports `Coordinates`, `VersionCheck`, `Versions`, the `Resolver` and `Client` traits, and `check_latest`,
+//! which finds the latest version matching each requirement of a check.
+//!
+//! ```
+//! use latest_maven_version::{check_latest, client, maven_central, VersionCheck};
+//!
+//! let check = "org.neo4j:neo4j:^4".parse::<VersionCheck>()?;
+//! let client = client()?;
+//! for (requirement, latest) in check_latest(&maven_central(), &client, &check).await? {
+//!     match latest {
+//!         Some(latest) => println!("Latest version matching {}: {}", requirement, latest),
+//!         None => println!("No version matching {}", requirement),
+//!     }
+//! }
+//! ```
+//!
+//! A failed check returns an `Error` with the `ErrorKind` of the failed request.
+//! The same `Error` reports invalid input, e.g. from parsing a `VersionCheck`, invalid resolver URLs, and clients that cannot be built,
+//! without a `resolver` and `url` since no request was sent.
+//! Its `code`, e.g. `coordinates-not-found` or `server-error`, is stable across releases, and `is_retryable` tells whether checking again later might succeed,
+//! which is the case for connection errors, timeouts, 5xx responses, and rate limits.
+//!
+//! Other repositories are resolved with a `UrlResolver`, e.g. `UrlResolver::new("https://nexus.mycorp.com/repository/maven-public", Some(Auth::basic("user", "pass")))`.
+//! Another HTTP library can send the requests by implementing `Client`, whose `request` gets the `RequestOptions` of the resolver, e.g. its `auth`.
+//! Custom resolvers implement `Resolver`, which uses the `Query` of a search and returns the `Download` of a file.
+//!
+//! With the `blocking` feature, `check_latest_blocking` runs a check without an async runtime.
+//! Its `blocking_client` sends the requests with `ureq` on the calling thread, which suits scripts and tools that check a few coordinates,
+//! and retries wait with a timer that does not need a runtime either.
+//! Without the default features, this does not depend on tokio at all:
+//!
+//!     latest-maven-version = { version = "0.9", default-features = false, features = ["blocking"] }
+//!
+//! The binary, `client`, `CheckRequest`, the caches, and `exec:` resolvers need the `runtime` feature, which every HTTP backend enables.
+//!
+//! ```
+//! use latest_maven_version::{blocking_client, check_latest_blocking, maven_central, VersionCheck};
+//!
+//! let check = "org.neo4j:neo4j:^4".parse::<VersionCheck>()?;
+//! let latest = check_latest_blocking(&maven_central(), &blocking_client()?, &check)?;
+//! ```
+//!
+//! Resolvers of different types are combined at runtime as `Arc<dyn DynResolver>`, which every resolver implements and which is a `Resolver` again,
+//! e.g. in a `MultiResolver` with the `Fallback` or `Merge` `Strategy`, or in a `RoutingResolver` that picks the resolver by the groupId.
+//! A `MultiResolver` without any resolvers is rejected with the `no-resolvers` error.
+//! Clients are shared the same way as `Arc<dyn Client>`.
+//!
+//! ```
+//! use latest_maven_version::{maven_central, DynResolver, MultiResolver, Strategy, UrlResolver};
+//! use std::sync::Arc;
+//!
+//! let resolvers: Vec<Arc<dyn DynResolver>> = vec![
+//!     Arc::new(UrlResolver::new("https://nexus.mycorp.com/repository/maven-public", None)?),
+//!     Arc::new(maven_central()),
+//! ];
+//! let resolver = MultiResolver::new(resolvers, Strategy::Fallback)?;
+//! ```
+//!
+//! `CheckRequest::builder()` sets up the checks with the options of the binary that are not about the output, without going through the command line.
+//! It takes the repositories and their credentials, whether to merge them, the pre-release policy, the qualifiers and versions to ignore, the minimum version,
+//! how many checks and requests run at the same time, and the timeout of the requests. `build` reports invalid options as an `Error` with the `invalid-input` or `invalid-resolver` code,
+//! and `run` returns the latest versions of each check in the order of the checks.
+//!
+//! ```
+//! use latest_maven_version::{client, CheckRequest};
+//!
+//! let request = CheckRequest::builder()
+//!     .check("org.neo4j:neo4j:^4".parse()?)
+//!     .resolver("https://nexus.mycorp.com/repository/maven-public")
+//!     .include_pre_releases(true)
+//!     .exclude_qualifier("alpha")
+//!     .jobs(4)
+//!     .build()?;
+//! for (coordinates, result) in request.run(&client()?).await {
+//!     println!("{}: {:?}", coordinates, result?);
+//! }
+//! ```
+//!
+//! `run_until_cancelled` stops a run when its `CancellationToken` is cancelled, e.g. when the caller of a service disconnects.
+//! The checks that are still running are dropped with their requests, and the results of the checks that finished before are returned.
+//!
+//! ```
+//! use latest_maven_version::CancellationToken;
+//!
+//! let token = CancellationToken::new();
+//! let on_disconnect = token.clone(); // on_disconnect.cancel() stops the run
+//! let finished = request.run_until_cancelled(&client, &token).await;
+//! ```
+//!
+//! A `CachingResolver` caches the metadata of any resolver in a `MetadataCache`, which is either a `MemoryCache`, a `DiskCache` like the one of the CLI, or `NoCache`.
+//! The trait has a `get` and a `put` of the response for a URL, to keep the responses in another store, e.g. Redis.
+//!
+//! ```
+//! use latest_maven_version::{maven_central, CachingResolver, MemoryCache};
+//! use std::{sync::Arc, time::Duration};
+//!
+//! let resolver = CachingResolver::new(maven_central(), Arc::new(MemoryCache::new(Duration::from_secs(600))));
+//! ```
+//!
+//! A `Progress` observer is told when checks start and finish with `check_latest_with_progress`,
+//! and about every request and retry of a `UrlResolver` that was given the observer with `with_progress`.
+//!
+//! A `FetchClient` sends the requests with the fetch API of a host.
+//! It takes a `Fetch` with one method that takes a `FetchRequest` and returns the status, headers, and body of the response, and the host follows redirects.
+//! Like the other clients, it rejects responses over 64MiB, which `with_max_response_size` changes.
+//! Without the default features, the crate builds for `wasm32-unknown-unknown`, with the resolvers, the versions, and the parsing of requirements.
+//! The `web-fetch` feature adds `WebFetch` there, which sends the requests with the global `fetch` of a browser, a web worker, or Node.js:
+//!
+//!     $ cargo build --target wasm32-unknown-unknown --no-default-features --features web-fetch
+//!
+//! ```
+//! use latest_maven_version::{check_latest, maven_central, FetchClient, VersionCheck, WebFetch};
+//!
+//! let check = "org.neo4j:neo4j:^4".parse::<VersionCheck>()?;
+//! let latest = check_latest(&maven_central(), &FetchClient::new(WebFetch::new()), &check).await?;
+//! ```
+//!
+//! The browser checks CORS, so that only repositories that allow the origin of the page can be read.
+//! Other hosts implement `Fetch` with their own bindings.
+//! There, `local:` repositories are rejected, as there is no file system, and retries wait with the timers of the browser.
+//! The traits `Fetch`, `Client`, and `DynResolver` are implemented with `#[async_trait(?Send)]` there, and need neither `Send` nor `Sync`, so that they can hold the futures of `wasm-bindgen`.
+//!
 //! # Examples
 //!
 //! Matching against minor-compatible releases.
@@ -93,156 +1073,8 @@
 //!     Latest version matching *: 4.1.1
 //!
 //!
-use color_eyre::eyre::Result;
-use console::{style, Term};
-use resolvers::{Client, Resolver, UrlResolver};
-use semver::{Version, VersionReq};
-use std::sync::Arc;
-use versions::Versions;
-
-mod metadata;
-mod opts;
-mod resolvers;
-mod versions;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    if Term::stdout().features().is_attended() {
-        color_eyre::config::HookBuilder::default()
-            .display_env_section(false)
-            .install()?
-    }
-
-    let mut opts = opts::Opts::new();
-    let config = opts.config();
-
-    let server = opts.resolver_server();
-    let resolver = UrlResolver::new(server.url, server.auth)?;
-    let client = resolvers::client();
-
-    let checks = opts.into_version_checks();
-
-    let results = run(resolver, client, config, checks).await?;
-
-    for CheckResult {
-        coordinates,
-        versions,
-    } in results
-    {
-        println!(
-            "Latest version(s) for {}:{}:",
-            style(coordinates.group_id).magenta(),
-            style(coordinates.artifact).blue()
-        );
-
-        for (req, latest) in versions {
-            if let Some(latest) = latest {
-                println!(
-                    "Latest version matching {}: {}",
-                    style(req).cyan().bold(),
-                    style(latest).green().bold()
-                );
-            } else {
-                println!("No version matching {}", style(req).yellow().bold());
-            }
-        }
-    }
-
-    Ok(())
-}
-
-async fn run<R, C>(
-    resolver: R,
-    client: C,
-    config: Config,
-    checks: Vec<VersionCheck>,
-) -> Result<Vec<CheckResult>>
-where
-    R: Resolver + Send + Sync + 'static,
-    C: Client + Send + Sync + 'static,
-{
-    let resolver = Arc::new(resolver);
-    let client = Arc::new(client);
-
-    let tasks = checks
-        .into_iter()
-        .map(|check| {
-            let resolver = Arc::clone(&resolver);
-            let client = Arc::clone(&client);
-            tokio::spawn(run_check(
-                resolver,
-                client,
-                config.include_pre_releases,
-                check,
-            ))
-        })
-        .collect::<Vec<_>>();
-
-    let mut results = Vec::with_capacity(tasks.len());
-    for task in tasks {
-        let result = task.await??;
-        results.push(result);
-    }
-    Ok(results)
-}
-
-async fn run_check(
-    resolver: Arc<impl Resolver>,
-    client: Arc<impl Client>,
-    include_pre_releases: bool,
-    check: VersionCheck,
-) -> Result<CheckResult> {
-    let VersionCheck {
-        coordinates,
-        versions,
-    } = check;
-
-    let all_versions = resolver.resolve(&coordinates, &*client).await?;
-    let versions = all_versions.latest_versions(include_pre_releases, versions);
-    Ok(CheckResult {
-        coordinates,
-        versions,
-    })
-}
-
-#[derive(Debug)]
-struct Server {
-    url: String,
-    auth: Option<(String, String)>,
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Config {
-    include_pre_releases: bool,
-}
-
-#[derive(Debug, Clone, PartialEq)]
-struct Coordinates {
-    group_id: String,
-    artifact: String,
-}
-
-impl Coordinates {
-    #[cfg(test)]
-    fn new<T, U>(group_id: T, artifact: U) -> Self
-    where
-        T: Into<String>,
-        U: Into<String>,
-    {
-        Self {
-            group_id: group_id.into(),
-            artifact: artifact.into(),
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq)]
-struct VersionCheck {
-    coordinates: Coordinates,
-    versions: Vec<VersionReq>,
-}
-#[derive(Debug)]
-struct CheckResult {
-    coordinates: Coordinates,
-    versions: Vec<(VersionReq, Option<Version>)>,
+async fn main() -> color_eyre::eyre::Result<()> {
+    latest_maven_version::cli::main().await
 }
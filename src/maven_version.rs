@@ -0,0 +1,324 @@
+use std::cmp::Ordering;
+
+/// A version that is ordered like Maven orders versions, following its `ComparableVersion`.
+///
+/// The version is split into numbers and qualifiers at `.`, `-`, and between digits and letters,
+/// where a `-` starts a nested list. Qualifiers are ordered
+/// `alpha` < `beta` < `milestone` < `rc` < `snapshot` < release < `sp` < any other qualifier,
+/// ignoring case, and `ga`, `final`, and `release` are the same as the release itself.
+/// Trailing zeros are ignored, so `1`, `1.0`, and `1.0.0.Final` are the same version.
+#[derive(Debug, Clone)]
+pub(crate) struct MavenVersion {
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    /// The digits of a number, without leading zeros, so that numbers of any size can be compared.
+    Int(String),
+    /// The comparable form of a qualifier, see [`comparable_qualifier`].
+    Qualifier(String),
+    List(Vec<Item>),
+}
+
+/// The known qualifiers in their order, where the empty qualifier is the release.
+const QUALIFIERS: [&str; 7] = ["alpha", "beta", "milestone", "rc", "snapshot", "", "sp"];
+
+/// The comparable form of the release qualifier.
+const RELEASE: &str = "5";
+
+impl MavenVersion {
+    /// Whether the version has a qualifier that orders before the release, e.g. `alpha` or `SNAPSHOT`.
+    ///
+    /// Other qualifiers, e.g. `Final`, `sp1`, or `jre`, are released versions.
+    pub(crate) fn is_pre_release(&self) -> bool {
+        fn is_pre_release(items: &[Item]) -> bool {
+            items.iter().any(|item| match item {
+                Item::Int(_) => false,
+                Item::Qualifier(qualifier) => qualifier.as_str() < RELEASE,
+                Item::List(items) => is_pre_release(items),
+            })
+        }
+        is_pre_release(&self.items)
+    }
+}
+
+impl From<&str> for MavenVersion {
+    fn from(version: &str) -> Self {
+        let version = version.to_lowercase();
+        // the innermost list is the last one, every list is the last item of its parent
+        let mut lists = vec![Vec::new()];
+        let mut start = 0;
+        let mut is_digit = false;
+
+        for (idx, c) in version.char_indices() {
+            let list = lists.last_mut().expect("the root list is never removed");
+            match c {
+                '.' | '-' => {
+                    list.push(if idx == start {
+                        Item::Int(String::from("0"))
+                    } else {
+                        Item::parse(is_digit, &version[start..idx])
+                    });
+                    start = idx + 1;
+                    if c == '-' {
+                        lists.push(Vec::new());
+                    }
+                }
+                c if c.is_ascii_digit() => {
+                    if !is_digit && idx > start {
+                        list.push(Item::qualifier(&version[start..idx], true));
+                        start = idx;
+                        lists.push(Vec::new());
+                    }
+                    is_digit = true;
+                }
+                _ => {
+                    if is_digit && idx > start {
+                        list.push(Item::parse(true, &version[start..idx]));
+                        start = idx;
+                        lists.push(Vec::new());
+                    }
+                    is_digit = false;
+                }
+            }
+        }
+        if version.len() > start {
+            let list = lists.last_mut().expect("the root list is never removed");
+            list.push(Item::parse(is_digit, &version[start..]));
+        }
+
+        // normalize the innermost lists first, so that their parents can remove them if they become empty
+        let mut items = lists.pop().expect("the root list is never removed");
+        normalize(&mut items);
+        while let Some(mut parent) = lists.pop() {
+            parent.push(Item::List(items));
+            normalize(&mut parent);
+            items = parent;
+        }
+        Self { items }
+    }
+}
+
+impl Item {
+    fn parse(is_digit: bool, text: &str) -> Self {
+        if is_digit {
+            let digits = text.trim_start_matches('0');
+            Item::Int(String::from(if digits.is_empty() { "0" } else { digits }))
+        } else {
+            Item::qualifier(text, false)
+        }
+    }
+
+    /// A single letter before a number is short for a qualifier, e.g. `1.0a1` for `1.0-alpha-1`.
+    fn qualifier(text: &str, followed_by_digit: bool) -> Self {
+        let text = match text {
+            "a" if followed_by_digit => "alpha",
+            "b" if followed_by_digit => "beta",
+            "m" if followed_by_digit => "milestone",
+            text => text,
+        };
+        Item::Qualifier(comparable_qualifier(text))
+    }
+
+    /// Whether the item is the same as no item at all, e.g. a `0` or `final`.
+    fn is_null(&self) -> bool {
+        match self {
+            Item::Int(digits) => digits == "0",
+            Item::Qualifier(qualifier) => qualifier == RELEASE,
+            Item::List(items) => items.is_empty(),
+        }
+    }
+
+    fn compare(&self, other: Option<&Item>) -> Ordering {
+        match (self, other) {
+            (Item::Int(_), None) => {
+                if self.is_null() {
+                    Ordering::Equal
+                } else {
+                    Ordering::Greater
+                }
+            }
+            (Item::Int(lhs), Some(Item::Int(rhs))) => (lhs.len(), lhs).cmp(&(rhs.len(), rhs)),
+            (Item::Int(_), Some(_)) => Ordering::Greater,
+            (Item::Qualifier(qualifier), None) => qualifier.as_str().cmp(RELEASE),
+            (Item::Qualifier(lhs), Some(Item::Qualifier(rhs))) => lhs.cmp(rhs),
+            (Item::Qualifier(_), Some(_)) => Ordering::Less,
+            (Item::List(items), None) => items
+                .first()
+                .map_or(Ordering::Equal, |first| first.compare(None)),
+            (Item::List(_), Some(Item::Int(_))) => Ordering::Less,
+            (Item::List(_), Some(Item::Qualifier(_))) => Ordering::Greater,
+            (Item::List(lhs), Some(Item::List(rhs))) => compare_lists(lhs, rhs),
+        }
+    }
+}
+
+/// Known qualifiers compare by their position, unknown ones after them in lexical order.
+fn comparable_qualifier(qualifier: &str) -> String {
+    let qualifier = match qualifier {
+        "ga" | "final" | "release" => "",
+        "cr" => "rc",
+        qualifier => qualifier,
+    };
+    match QUALIFIERS.iter().position(|known| *known == qualifier) {
+        Some(idx) => idx.to_string(),
+        None => format!("{}-{}", QUALIFIERS.len(), qualifier),
+    }
+}
+
+/// Removes the trailing items that are the same as no item, also before a nested list.
+fn normalize(items: &mut Vec<Item>) {
+    let mut idx = items.len();
+    while idx > 0 {
+        idx -= 1;
+        if items[idx].is_null() {
+            items.remove(idx);
+        } else if !matches!(items[idx], Item::List(_)) {
+            break;
+        }
+    }
+}
+
+fn compare_lists(lhs: &[Item], rhs: &[Item]) -> Ordering {
+    for idx in 0..lhs.len().max(rhs.len()) {
+        let ordering = match (lhs.get(idx), rhs.get(idx)) {
+            (Some(lhs), rhs) => lhs.compare(rhs),
+            (None, Some(rhs)) => rhs.compare(None).reverse(),
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+impl Ord for MavenVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_lists(&self.items, &other.items)
+    }
+}
+
+impl PartialOrd for MavenVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for MavenVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MavenVersion {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn assert_ascending(versions: &[&str]) {
+        for (idx, lhs) in versions.iter().enumerate() {
+            for rhs in &versions[idx + 1..] {
+                assert!(
+                    MavenVersion::from(*lhs) < MavenVersion::from(*rhs),
+                    "{} < {}",
+                    lhs,
+                    rhs
+                );
+                assert!(
+                    MavenVersion::from(*rhs) > MavenVersion::from(*lhs),
+                    "{} > {}",
+                    rhs,
+                    lhs
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_qualifier_order() {
+        assert_ascending(&[
+            "1-alpha2snapshot",
+            "1-alpha2",
+            "1-alpha-123",
+            "1-beta-2",
+            "1-beta123",
+            "1-m2",
+            "1-m11",
+            "1-rc",
+            "1-cr2",
+            "1-rc123",
+            "1-SNAPSHOT",
+            "1",
+            "1-sp",
+            "1-sp2",
+            "1-sp123",
+            "1-abc",
+            "1-def",
+            "1-pom-1",
+            "1-1-snapshot",
+            "1-1",
+            "1-2",
+            "1-123",
+        ]);
+    }
+
+    #[test]
+    fn test_number_order() {
+        assert_ascending(&[
+            "2.0", "2-1", "2.0.a", "2.0.0.a", "2.0.2", "2.0.123", "2.1.0", "2.1-a", "2.1b",
+            "2.1-c", "2.1-1", "2.1.0.1", "2.2", "2.123", "11.a2", "11.a11", "11.b2", "11.b11",
+            "11.m2", "11.m11", "11", "11.a", "11b", "11c", "11m",
+        ]);
+    }
+
+    #[test]
+    fn test_release_qualifiers() {
+        assert_ascending(&[
+            "2.3.4.RC1",
+            "2.3.4.RELEASE",
+            "2.3.4.SR1",
+            "2.3.5.M1",
+            "2.3.5.RELEASE",
+        ]);
+        assert_ascending(&["1.0.0.CR1", "1.0.0.Final", "1.0.1.Final"]);
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        assert_ascending(&["1.0.20240101123456", "1.0.99999999999999999999", "1.1"]);
+    }
+
+    #[test_case("1", "1.0")]
+    #[test_case("1", "1.0.0")]
+    #[test_case("1.0", "1-0")]
+    #[test_case("1", "1-ga")]
+    #[test_case("1", "1.0.0.Final")]
+    #[test_case("1", "1-RELEASE")]
+    #[test_case("1-cr", "1-rc")]
+    #[test_case("1x", "1-x")]
+    #[test_case("1a1", "1-alpha-1")]
+    #[test_case("1b2", "1-beta-2")]
+    #[test_case("1m3", "1-milestone-3")]
+    #[test_case("1.0-SNAPSHOT", "1-snapshot")]
+    #[test_case("1.01", "1.1")]
+    fn test_equal(lhs: &str, rhs: &str) {
+        assert_eq!(MavenVersion::from(lhs), MavenVersion::from(rhs));
+    }
+
+    #[test_case("1.0.0" => false; "release")]
+    #[test_case("1.0.0.Final" => false; "final qualifier")]
+    #[test_case("2.3.4.RELEASE" => false; "spring release")]
+    #[test_case("31.1-jre" => false; "unknown qualifier")]
+    #[test_case("1.0-sp1" => false; "service pack")]
+    #[test_case("1.0-alpha-1" => true; "alpha")]
+    #[test_case("1.0.0.M1" => true; "milestone")]
+    #[test_case("1.0.0.CR1" => true; "candidate release")]
+    #[test_case("1.0-SNAPSHOT" => true; "snapshot")]
+    fn test_is_pre_release(version: &str) -> bool {
+        MavenVersion::from(version).is_pre_release()
+    }
+}
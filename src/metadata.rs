@@ -1,9 +1,77 @@
-use std::iter::FromIterator;
+use encoding_rs::{Decoder, Encoding, UTF_8};
 use xmlparser::{ElementEnd as EE, Error, Token, Tokenizer};
 
 pub(crate) struct Parser<'a> {
     tok: Tokenizer<'a>,
     state: State,
+    details: Details,
+}
+
+/// The versions of a metadata document, and the coordinates that it claims to be about.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub(crate) group_id: Option<String>,
+    pub(crate) artifact_id: Option<String>,
+    pub(crate) versions: Vec<String>,
+    /// The `<latest>` tag, the newest version including snapshots.
+    pub(crate) latest: Option<String>,
+    /// The `<release>` tag, the newest version that is not a snapshot.
+    pub(crate) release: Option<String>,
+    /// The `<lastUpdated>` tag, a timestamp like `20200827153717`.
+    pub(crate) last_updated: Option<String>,
+    /// The `<timestamp>` of the latest `<snapshot>` build, like `20240101.123456`.
+    ///
+    /// Only the metadata of a snapshot version, next to its builds, has one.
+    pub(crate) snapshot_timestamp: Option<String>,
+    /// The `<buildNumber>` of the latest `<snapshot>` build.
+    pub(crate) snapshot_build_number: Option<String>,
+}
+
+impl Metadata {
+    /// The `<groupId>` of the document, if it has one.
+    pub fn group_id(&self) -> Option<&str> {
+        self.group_id.as_deref()
+    }
+
+    /// The `<artifactId>` of the document, if it has one.
+    pub fn artifact_id(&self) -> Option<&str> {
+        self.artifact_id.as_deref()
+    }
+
+    /// The versions in the order of the document.
+    pub fn versions(&self) -> &[String] {
+        &self.versions
+    }
+}
+
+/// Collects the elements besides the versions: the `groupId` and `artifactId` of the root element,
+/// and the `latest`, `release`, `lastUpdated`, and `snapshot` of its `versioning`.
+#[derive(Debug, Default)]
+struct Details {
+    depth: usize,
+    versioning: Versioning,
+    in_snapshot: bool,
+    field: Option<Field>,
+    metadata: Metadata,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+enum Versioning {
+    #[default]
+    Before,
+    Open,
+    Closed,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Field {
+    GroupId,
+    ArtifactId,
+    Latest,
+    Release,
+    LastUpdated,
+    SnapshotTimestamp,
+    SnapshotBuildNumber,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -21,21 +89,212 @@ impl<'a> From<&'a str> for Parser<'a> {
         Parser {
             tok: Tokenizer::from(input),
             state: State::ExpectFirstVersionStart,
+            details: Details::default(),
         }
     }
 }
 
-impl<'a> Parser<'a> {
-    pub(crate) fn parse_into<T>(input: &'a str) -> Result<T, Error>
-    where
-        T: FromIterator<&'a str>,
-    {
-        let parser = Self::from(input);
-        parser.collect::<Result<T, Error>>()
+/// Elements are matched by their local name, so that namespaced documents,
+/// e.g. `<m:version>` or a default `xmlns` on the root, are read like plain ones.
+const VERSION_TAG: &str = "version";
+const GROUP_ID_TAG: &str = "groupId";
+const ARTIFACT_ID_TAG: &str = "artifactId";
+const VERSIONING_TAG: &str = "versioning";
+const LATEST_TAG: &str = "latest";
+const RELEASE_TAG: &str = "release";
+const LAST_UPDATED_TAG: &str = "lastUpdated";
+const SNAPSHOT_TAG: &str = "snapshot";
+const TIMESTAMP_TAG: &str = "timestamp";
+const BUILD_NUMBER_TAG: &str = "buildNumber";
+
+/// The metadata of a complete document.
+pub(crate) fn parse(input: &str) -> Result<Metadata, Error> {
+    let mut parser = Parser::from(input);
+    let versions = parser
+        .by_ref()
+        .map(|version| version.map(String::from))
+        .collect::<Result<_, _>>()?;
+    // the `lastUpdated` follows the versions
+    while parser.details.is_reading() {
+        match parser.tok.next() {
+            Some(token) => parser.details.advance(token?),
+            None => break,
+        }
     }
+    Ok(parser.details.into_metadata(versions))
 }
 
-const VERSION_TAG: &str = "version";
+/// The metadata of a complete document in any encoding, e.g. a file on disk.
+pub(crate) fn parse_bytes(input: &[u8]) -> Result<Metadata, Error> {
+    let mut parser = StreamParser::new();
+    parser.feed(input)?;
+    parser.finish()
+}
+
+/// A minimal metadata document, which parses into the same metadata again.
+#[cfg(feature = "runtime")]
+pub(crate) fn document(metadata: &Metadata) -> String {
+    fn element(document: &mut String, tag: &str, text: &str) {
+        if !text.contains("]]>") {
+            document.push_str(&format!("<{}><![CDATA[{}]]></{}>", tag, text, tag));
+        }
+    }
+
+    let mut document = String::from("<metadata>");
+    for (tag, text) in [
+        (GROUP_ID_TAG, &metadata.group_id),
+        (ARTIFACT_ID_TAG, &metadata.artifact_id),
+    ] {
+        if let Some(text) = text {
+            element(&mut document, tag, text);
+        }
+    }
+    document.push_str("<versioning>");
+    for (tag, text) in [
+        (LATEST_TAG, &metadata.latest),
+        (RELEASE_TAG, &metadata.release),
+    ] {
+        if let Some(text) = text {
+            element(&mut document, tag, text);
+        }
+    }
+    document.push_str("<versions>");
+    for version in &metadata.versions {
+        element(&mut document, VERSION_TAG, version);
+    }
+    document.push_str("</versions>");
+    if let Some(last_updated) = &metadata.last_updated {
+        element(&mut document, LAST_UPDATED_TAG, last_updated);
+    }
+    if metadata.snapshot_timestamp.is_some() || metadata.snapshot_build_number.is_some() {
+        document.push_str("<snapshot>");
+        for (tag, text) in [
+            (TIMESTAMP_TAG, &metadata.snapshot_timestamp),
+            (BUILD_NUMBER_TAG, &metadata.snapshot_build_number),
+        ] {
+            if let Some(text) = text {
+                element(&mut document, tag, text);
+            }
+        }
+        document.push_str("</snapshot>");
+    }
+    document.push_str("</versioning></metadata>");
+    document
+}
+
+impl Details {
+    fn advance(&mut self, token: Token<'_>) {
+        match token {
+            Token::ElementStart { local, .. } => {
+                self.depth += 1;
+                let in_versioning = matches!(self.versioning, Versioning::Open);
+                self.field = match (self.depth, local.as_str()) {
+                    (2, GROUP_ID_TAG) => Some(Field::GroupId),
+                    (2, ARTIFACT_ID_TAG) => Some(Field::ArtifactId),
+                    (2, VERSIONING_TAG) => {
+                        self.versioning = Versioning::Open;
+                        None
+                    }
+                    (3, LATEST_TAG) if in_versioning => Some(Field::Latest),
+                    (3, RELEASE_TAG) if in_versioning => Some(Field::Release),
+                    (3, LAST_UPDATED_TAG) if in_versioning => Some(Field::LastUpdated),
+                    (3, SNAPSHOT_TAG) if in_versioning => {
+                        self.in_snapshot = true;
+                        None
+                    }
+                    (4, TIMESTAMP_TAG) if self.in_snapshot => Some(Field::SnapshotTimestamp),
+                    (4, BUILD_NUMBER_TAG) if self.in_snapshot => Some(Field::SnapshotBuildNumber),
+                    _ => None,
+                };
+            }
+            Token::ElementEnd { end: EE::Open, .. } => {}
+            Token::ElementEnd { .. } => {
+                if self.depth == 2 && self.is_reading() {
+                    self.versioning = Versioning::Closed;
+                }
+                if self.depth == 3 {
+                    self.in_snapshot = false;
+                }
+                self.depth = self.depth.saturating_sub(1);
+                self.field = None;
+            }
+            Token::Text { text } | Token::Cdata { text, .. } => {
+                let metadata = &mut self.metadata;
+                let value = match self.field {
+                    Some(Field::GroupId) => &mut metadata.group_id,
+                    Some(Field::ArtifactId) => &mut metadata.artifact_id,
+                    Some(Field::Latest) => &mut metadata.latest,
+                    Some(Field::Release) => &mut metadata.release,
+                    Some(Field::LastUpdated) => &mut metadata.last_updated,
+                    Some(Field::SnapshotTimestamp) => &mut metadata.snapshot_timestamp,
+                    Some(Field::SnapshotBuildNumber) => &mut metadata.snapshot_build_number,
+                    None => return,
+                };
+                value
+                    .get_or_insert_with(String::new)
+                    .push_str(text.as_str().trim());
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the `versioning` is still open, whose `lastUpdated` follows the versions.
+    fn is_reading(&self) -> bool {
+        matches!(self.versioning, Versioning::Open)
+    }
+
+    fn into_metadata(self, versions: Vec<String>) -> Metadata {
+        Metadata {
+            versions,
+            ..self.metadata
+        }
+    }
+}
+
+impl State {
+    /// Moves to the next state and returns the version, if the token is the text of one.
+    fn advance<'a>(&mut self, token: Token<'a>) -> Option<&'a str> {
+        match self {
+            State::ExpectFirstVersionStart => match token {
+                Token::ElementStart { local, .. } if local.as_str() == VERSION_TAG => {
+                    *self = State::ExpectVersionEnd;
+                }
+                _ => {}
+            },
+            State::ExpectNextVersionStart => match token {
+                Token::ElementStart { local, .. } if local.as_str() == VERSION_TAG => {
+                    *self = State::ExpectVersionEnd;
+                }
+                Token::ElementEnd {
+                    end: EE::Close(_, _),
+                    ..
+                } => {
+                    *self = State::Eoi;
+                }
+                _ => {}
+            },
+            State::ExpectVersionEnd => {
+                if let Token::ElementEnd { end: EE::Open, .. } = token {
+                    *self = State::ExpectVersion;
+                }
+            }
+            State::ExpectVersion => match token {
+                Token::Text { text } | Token::Cdata { text, .. } => {
+                    return Some(text.as_str().trim())
+                }
+                Token::ElementEnd {
+                    end: EE::Close(_, _),
+                    ..
+                } => {
+                    *self = State::ExpectNextVersionStart;
+                }
+                _ => {}
+            },
+            State::Eoi => {}
+        }
+        None
+    }
+}
 
 impl<'a> Iterator for Parser<'a> {
     type Item = Result<&'a str, Error>;
@@ -45,49 +304,17 @@ impl<'a> Iterator for Parser<'a> {
             return None;
         }
 
-        while let Some(token) = self.tok.next() {
+        for token in self.tok.by_ref() {
             let token = match token {
                 Ok(token) => token,
                 Err(e) => return Some(Err(e)),
             };
-            match self.state {
-                State::ExpectFirstVersionStart => match token {
-                    Token::ElementStart { local, .. } if local.as_str() == VERSION_TAG => {
-                        self.state = State::ExpectVersionEnd;
-                    }
-                    _ => {}
-                },
-                State::ExpectNextVersionStart => match token {
-                    Token::ElementStart { local, .. } if local.as_str() == VERSION_TAG => {
-                        self.state = State::ExpectVersionEnd;
-                    }
-                    Token::ElementEnd {
-                        end: EE::Close(_, _),
-                        ..
-                    } => {
-                        self.state = State::Eoi;
-                        break;
-                    }
-                    _ => {}
-                },
-                State::ExpectVersionEnd => {
-                    if let Token::ElementEnd { end: EE::Open, .. } = token {
-                        self.state = State::ExpectVersion;
-                    }
-                }
-                State::ExpectVersion => match token {
-                    Token::Text { text } | Token::Cdata { text, .. } => {
-                        return Some(Ok(text.as_str().trim()))
-                    }
-                    Token::ElementEnd {
-                        end: EE::Close(_, _),
-                        ..
-                    } => {
-                        self.state = State::ExpectNextVersionStart;
-                    }
-                    _ => {}
-                },
-                State::Eoi => break,
+            self.details.advance(token);
+            if let Some(version) = self.state.advance(token) {
+                return Some(Ok(version));
+            }
+            if let State::Eoi = self.state {
+                break;
             }
         }
 
@@ -95,6 +322,191 @@ impl<'a> Iterator for Parser<'a> {
     }
 }
 
+/// Parses the versions from the chunks of a response, while it is still being received.
+///
+/// Only the text after the last complete token is kept between chunks,
+/// so the complete document is never in memory.
+///
+/// The text is decoded with the charset of the response, or else the encoding of the XML declaration,
+/// e.g. `ISO-8859-1` in older repositories. A byte order mark overrides both, and the default is UTF-8.
+pub(crate) struct StreamParser {
+    text: String,
+    /// The bytes before the encoding is known, which is after the XML declaration.
+    prolog: Vec<u8>,
+    decoder: Option<Decoder>,
+    /// Until the root element starts, the text is parsed as a document instead of a fragment.
+    in_prolog: bool,
+    state: State,
+    details: Details,
+    versions: Vec<String>,
+}
+
+impl StreamParser {
+    pub(crate) fn new() -> Self {
+        Self::with_charset(None)
+    }
+
+    /// Decodes the input with the charset, e.g. of the `Content-Type`, instead of the XML declaration.
+    ///
+    /// An unknown charset is ignored.
+    pub(crate) fn with_charset(charset: Option<&str>) -> Self {
+        Self {
+            text: String::new(),
+            prolog: Vec::new(),
+            decoder: charset
+                .and_then(|charset| Encoding::for_label(charset.as_bytes()))
+                .map(|encoding| encoding.new_decoder()),
+            in_prolog: true,
+            state: State::ExpectFirstVersionStart,
+            details: Details::default(),
+            versions: Vec::new(),
+        }
+    }
+
+    /// Whether the versions and the rest of the `versioning` are complete,
+    /// so that the rest of the response can be skipped.
+    pub(crate) fn is_done(&self) -> bool {
+        matches!(self.state, State::Eoi) && !self.details.is_reading()
+    }
+
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        if self.decoder.is_some() {
+            self.decode(chunk, false);
+        } else {
+            self.prolog.extend_from_slice(chunk);
+            match declared_encoding(&self.prolog) {
+                Some(encoding) => self.decode_prolog(encoding, false),
+                None => return Ok(()),
+            }
+        }
+
+        // text ends before a `<`, so tokens before the last one are complete,
+        // unless the `<` is part of a comment or CDATA, which then fails to parse until it is complete
+        match self.text.rfind('<') {
+            Some(end) if end > 0 => self.parse(end, false),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> Result<Metadata, Error> {
+        if self.decoder.is_some() {
+            self.decode(&[], true);
+        } else {
+            let encoding = declared_encoding(&self.prolog).unwrap_or(UTF_8);
+            self.decode_prolog(encoding, true);
+        }
+        self.parse(self.text.len(), true)?;
+        Ok(self.details.into_metadata(self.versions))
+    }
+
+    fn decode_prolog(&mut self, encoding: &'static Encoding, last: bool) {
+        self.decoder = Some(encoding.new_decoder());
+        let prolog = std::mem::take(&mut self.prolog);
+        self.decode(&prolog, last);
+    }
+
+    /// Appends the decoded input to the text, an incomplete sequence at the end is kept by the decoder.
+    fn decode(&mut self, input: &[u8], last: bool) {
+        let decoder = self.decoder.as_mut().expect("the encoding is known");
+        let capacity = decoder
+            .max_utf8_buffer_length(input.len())
+            .expect("chunks are small enough");
+        self.text.reserve(capacity);
+        let _ = decoder.decode_to_string(input, &mut self.text, last);
+    }
+
+    /// Parses the text until `end` and removes the complete tokens.
+    ///
+    /// An error is only returned at the end of the input, before that the failing token might just be incomplete.
+    fn parse(&mut self, end: usize, at_end: bool) -> Result<(), Error> {
+        let text = &self.text[..end];
+        let tokenizer = if self.in_prolog {
+            Tokenizer::from(text)
+        } else {
+            Tokenizer::from_fragment(text, 0..text.len())
+        };
+        let mut parsed = 0;
+        let in_prolog = self.in_prolog;
+        for token in tokenizer {
+            let token = match token {
+                Ok(token) => token,
+                Err(e) if at_end => return Err(e),
+                Err(_) => break,
+            };
+            parsed = token_end(&token);
+            if let Token::ElementStart { .. } = token {
+                self.in_prolog = false;
+            }
+            self.details.advance(token);
+            if let Some(version) = self.state.advance(token) {
+                self.versions.push(String::from(version));
+            }
+            if self.is_done() {
+                break;
+            }
+        }
+        // the prolog is parsed again with the next chunk, as a document can only be parsed from its start
+        if !(in_prolog && self.in_prolog) {
+            self.text.drain(..parsed);
+        }
+        Ok(())
+    }
+}
+
+/// The encoding of the XML declaration at the start of the input,
+/// or `None` while the input might still be the start of the declaration.
+///
+/// Input without a declaration is UTF-8, as is a declaration of UTF-16 that could be read as ASCII.
+fn declared_encoding(input: &[u8]) -> Option<&'static Encoding> {
+    const DECLARATION: &[u8] = b"<?xml";
+    /// An encoding is always near the start, a longer declaration is not waited for.
+    const MAX_DECLARATION: usize = 1024;
+
+    let input = input.strip_prefix(b"\xef\xbb\xbf").unwrap_or(input);
+    if !input.starts_with(DECLARATION) {
+        return if DECLARATION.starts_with(input) {
+            None
+        } else {
+            Some(UTF_8)
+        };
+    }
+    let end = match input.windows(2).position(|w| w == b"?>") {
+        Some(end) => end,
+        None if input.len() > MAX_DECLARATION => return Some(UTF_8),
+        None => return None,
+    };
+    let declaration = String::from_utf8_lossy(&input[DECLARATION.len()..end]);
+    let encoding = declaration
+        .split_once("encoding")
+        .and_then(|(_, rest)| rest.trim_start().strip_prefix('='))
+        .map(|rest| rest.trim_start())
+        .and_then(|rest| {
+            let quote = rest.chars().next().filter(|&c| c == '"' || c == '\'')?;
+            rest[1..].split(quote).next()
+        })
+        .and_then(|label| Encoding::for_label(label.as_bytes()));
+    Some(encoding.map_or(UTF_8, Encoding::output_encoding))
+}
+
+/// The end of the token in the text that it was parsed from.
+fn token_end(token: &Token<'_>) -> usize {
+    let span = match token {
+        Token::Declaration { span, .. }
+        | Token::ProcessingInstruction { span, .. }
+        | Token::Comment { span, .. }
+        | Token::DtdStart { span, .. }
+        | Token::EmptyDtd { span, .. }
+        | Token::EntityDeclaration { span, .. }
+        | Token::DtdEnd { span }
+        | Token::ElementStart { span, .. }
+        | Token::Attribute { span, .. }
+        | Token::ElementEnd { span, .. }
+        | Token::Cdata { span, .. } => span,
+        Token::Text { text } => text,
+    };
+    span.end()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,8 +518,8 @@ mod tests {
     #[test_case("<version></version>"; "version without versions")]
     #[test_case("<versions><version></version></versions>"; "version without content")]
     fn test_empty_xml(input: &str) {
-        let versions = Parser::parse_into::<Vec<_>>(input).unwrap();
-        assert_eq!(versions, Vec::<&str>::new());
+        let versions = parse(input).unwrap().versions;
+        assert_eq!(versions, Vec::<String>::new());
     }
 
     #[test_case("<versions><version>   </version></versions>" => vec![""]; "whitespace only")]
@@ -116,13 +528,14 @@ mod tests {
     #[test_case("<versions><version><![CDATA[1.0.0]]></version></versions>" => vec!["1.0.0"]; "1.0.0 in CDATA")]
     #[test_case("<versions><version><![CDATA[   1.0.0    ]]></version></versions>" => vec!["1.0.0"]; "1.0.0 in CDATA with whitespace")]
     #[test_case("<versions><version>foo</version></versions>" => vec!["foo"]; "accepts anything")]
-    fn test_minimal_xml(input: &str) -> Vec<&str> {
-        Parser::parse_into(input).unwrap()
+    #[test_case("<m:versions xmlns:m=\"http://maven.apache.org/METADATA/1.1.0\"><m:version>1.0.0</m:version><m:version>2.0.0</m:version></m:versions>" => vec!["1.0.0", "2.0.0"]; "prefixed namespace")]
+    #[test_case("<versions xmlns=\"http://maven.apache.org/METADATA/1.1.0\"><version>1.0.0</version></versions>" => vec!["1.0.0"]; "default namespace")]
+    #[test_case("<m:versions xmlns:m=\"urn:m\"><version>1.0.0</version><n:version xmlns:n=\"urn:n\">2.0.0</n:version></m:versions>" => vec!["1.0.0", "2.0.0"]; "mixed prefixes")]
+    fn test_minimal_xml(input: &str) -> Vec<String> {
+        parse(input).unwrap().versions
     }
 
-    #[test]
-    fn test_full_xml() {
-        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+    static FULL_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
         <metadata>
           <groupId>org.neo4j.gds</groupId>
           <artifactId>proc</artifactId>
@@ -161,9 +574,18 @@ mod tests {
         </metadata>
         "#;
 
-        let versions = Parser::parse_into::<Vec<_>>(input).unwrap();
+    #[test]
+    fn test_full_xml() {
+        let input = FULL_XML;
+
+        let metadata = parse(input).unwrap();
+        assert_eq!(metadata.group_id.as_deref(), Some("org.neo4j.gds"));
+        assert_eq!(metadata.artifact_id.as_deref(), Some("proc"));
+        assert_eq!(metadata.latest.as_deref(), Some("1.4.0-alpha03"));
+        assert_eq!(metadata.release.as_deref(), Some("1.4.0-alpha03"));
+        assert_eq!(metadata.last_updated.as_deref(), Some("20200827153717"));
         assert_eq!(
-            versions,
+            metadata.versions,
             vec![
                 "0.9.2",
                 "0.9.3",
@@ -193,4 +615,138 @@ mod tests {
             ]
         );
     }
+
+    fn stream(input: &str, chunk_size: usize) -> Result<Metadata, Error> {
+        let mut parser = StreamParser::new();
+        for chunk in input.as_bytes().chunks(chunk_size) {
+            parser.feed(chunk)?;
+        }
+        parser.finish()
+    }
+
+    #[test_case(1; "single bytes")]
+    #[test_case(7; "small chunks")]
+    #[test_case(64; "larger chunks")]
+    #[test_case(4096; "single chunk")]
+    fn test_stream_full_xml(chunk_size: usize) {
+        let expected = parse(FULL_XML).unwrap();
+        assert_eq!(stream(FULL_XML, chunk_size).unwrap(), expected);
+    }
+
+    #[test_case("<versions><version>1.0.0</version><version>2.0.0</version></versions><version>3.0.0</version>" => vec!["1.0.0", "2.0.0"]; "stops after the versions")]
+    #[test_case("<!DOCTYPE metadata><metadata><versions><version>1.0.0</version></versions></metadata>" => vec!["1.0.0"]; "doctype")]
+    #[test_case("<metadata><!-- <version>0.1.0</version> --><versions><version>1.0.0</version></versions></metadata>" => vec!["1.0.0"]; "comment with tags")]
+    #[test_case("<versions><version><![CDATA[1.0.0<beta>]]></version></versions>" => vec!["1.0.0<beta>"]; "cdata with tags")]
+    #[test_case("<?xml version=\"1.0\"?><m:metadata xmlns:m=\"http://maven.apache.org/METADATA/1.1.0\"><m:versioning><m:versions><m:version>1.0.0</m:version></m:versions></m:versioning></m:metadata>" => vec!["1.0.0"]; "prefixed namespace")]
+    #[test_case("<versions><version>1.0.0-\u{e4}\u{f6}\u{fc}</version></versions>" => vec!["1.0.0-\u{e4}\u{f6}\u{fc}"]; "multi byte characters")]
+    fn test_stream_xml(input: &str) -> Vec<String> {
+        let expected = parse(input).unwrap().versions;
+        for chunk_size in 1..input.len() {
+            let versions = stream(input, chunk_size).unwrap().versions;
+            assert_eq!(versions, expected, "chunks of {}", chunk_size);
+        }
+        stream(input, input.len()).unwrap().versions
+    }
+
+    #[test]
+    fn test_stream_invalid_utf8() {
+        let mut parser = StreamParser::new();
+        parser
+            .feed(b"<versions><version>1.0\xff</version></versions>")
+            .unwrap();
+        assert_eq!(parser.finish().unwrap().versions, vec!["1.0\u{fffd}"]);
+    }
+
+    #[test_case(b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><versions><version>1.0-\xe4</version></versions>", None => vec!["1.0-\u{e4}"]; "declared latin1")]
+    #[test_case(b"<?xml version='1.0' encoding = 'windows-1252'?><versions><version>1.0-\x80</version></versions>", None => vec!["1.0-\u{20ac}"]; "declared windows-1252")]
+    #[test_case(b"<?xml version=\"1.0\" encoding=\"UTF-16\"?><versions><version>1.0-\xc3\xa4</version></versions>", None => vec!["1.0-\u{e4}"]; "declared utf-16 without bom")]
+    #[test_case(b"<?xml version=\"1.0\" encoding=\"unknown\"?><versions><version>1.0-\xc3\xa4</version></versions>", None => vec!["1.0-\u{e4}"]; "unknown encoding")]
+    #[test_case(b"<versions><version>1.0-\xe4</version></versions>", Some("ISO-8859-1") => vec!["1.0-\u{e4}"]; "charset")]
+    #[test_case(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?><versions><version>1.0-\xe4</version></versions>", Some("latin1") => vec!["1.0-\u{e4}"]; "charset before declaration")]
+    #[test_case(b"\xef\xbb\xbf<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><versions><version>1.0-\xc3\xa4</version></versions>", Some("latin1") => vec!["1.0-\u{e4}"]; "bom before charset")]
+    #[test_case(b"\xff\xfe<\x00v\x00e\x00r\x00s\x00i\x00o\x00n\x00s\x00>\x00<\x00v\x00e\x00r\x00s\x00i\x00o\x00n\x00>\x001\x00.\x000\x00<\x00/\x00v\x00e\x00r\x00s\x00i\x00o\x00n\x00>\x00", None => vec!["1.0"]; "utf-16 bom")]
+    fn test_stream_encoding(input: &[u8], charset: Option<&str>) -> Vec<String> {
+        let parse = |chunk_size: usize| {
+            let mut parser = StreamParser::with_charset(charset);
+            for chunk in input.chunks(chunk_size) {
+                parser.feed(chunk).unwrap();
+            }
+            parser.finish().unwrap().versions
+        };
+        let expected = parse(input.len());
+        for chunk_size in 1..input.len() {
+            assert_eq!(parse(chunk_size), expected, "chunks of {}", chunk_size);
+        }
+        expected
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_document() {
+        let metadata = parse(FULL_XML).unwrap();
+        assert_eq!(parse(&document(&metadata)).unwrap(), metadata);
+        let metadata = Metadata {
+            versions: metadata.versions,
+            ..Metadata::default()
+        };
+        assert_eq!(parse(&document(&metadata)).unwrap(), metadata);
+    }
+
+    #[test_case("<metadata><groupId>com.foo</groupId><artifactId>bar</artifactId></metadata>", Some("com.foo"), Some("bar"); "identity")]
+    #[test_case("<metadata><versioning><versions><version>1.0.0</version></versions></versioning></metadata>", None, None; "missing identity")]
+    #[test_case("<metadata><groupId> com.foo </groupId><artifactId><![CDATA[bar]]></artifactId></metadata>", Some("com.foo"), Some("bar"); "whitespace and cdata")]
+    #[test_case("<m:metadata xmlns:m=\"urn:m\"><m:groupId>com.foo</m:groupId><m:artifactId>bar</m:artifactId></m:metadata>", Some("com.foo"), Some("bar"); "namespaced")]
+    #[test_case("<metadata><plugins><plugin><artifactId>plugin</artifactId></plugin></plugins><groupId>com.foo</groupId></metadata>", Some("com.foo"), None; "nested elements are ignored")]
+    #[test_case("<metadata><groupId/><artifactId>bar</artifactId></metadata>", None, Some("bar"); "empty element")]
+    #[test_case("<metadata><versioning><groupId>com.foo</groupId></versioning><artifactId>bar</artifactId></metadata>", None, Some("bar"); "identity in versioning")]
+    fn test_identity(input: &str, group_id: Option<&str>, artifact_id: Option<&str>) {
+        let metadata = parse(input).unwrap();
+        for chunk_size in 1..input.len() {
+            let streamed = stream(input, chunk_size).unwrap();
+            assert_eq!(streamed, metadata, "chunks of {}", chunk_size);
+        }
+        assert_eq!(metadata.group_id.as_deref(), group_id);
+        assert_eq!(metadata.artifact_id.as_deref(), artifact_id);
+    }
+
+    #[test_case("<metadata><versioning><latest>2.0.0-SNAPSHOT</latest><release>1.0.0</release><versions><version>1.0.0</version></versions><lastUpdated>20200827153717</lastUpdated></versioning></metadata>", Some("2.0.0-SNAPSHOT"), Some("1.0.0"), Some("20200827153717"); "tags")]
+    #[test_case("<metadata><versioning><versions><version>1.0.0</version></versions></versioning></metadata>", None, None, None; "no tags")]
+    #[test_case("<metadata><versioning><versions><version>1.0.0</version></versions><lastUpdated>1</lastUpdated></versioning><lastUpdated>2</lastUpdated></metadata>", None, None, Some("1"); "only tags in versioning")]
+    #[test_case("<metadata><latest>2.0.0</latest><versioning><versions><version>1.0.0</version></versions></versioning></metadata>", None, None, None; "tags outside of versioning")]
+    #[test_case("<metadata><plugins><plugin><versioning><release>2.0.0</release></versioning></plugin></plugins><versioning><versions><version>1.0.0</version></versions></versioning></metadata>", None, None, None; "nested versioning")]
+    fn test_tags(
+        input: &str,
+        latest: Option<&str>,
+        release: Option<&str>,
+        last_updated: Option<&str>,
+    ) {
+        let metadata = parse(input).unwrap();
+        for chunk_size in 1..input.len() {
+            let streamed = stream(input, chunk_size).unwrap();
+            assert_eq!(streamed, metadata, "chunks of {}", chunk_size);
+        }
+        assert_eq!(metadata.latest.as_deref(), latest);
+        assert_eq!(metadata.release.as_deref(), release);
+        assert_eq!(metadata.last_updated.as_deref(), last_updated);
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case("<metadata><groupId>com.foo</groupId><artifactId>bar</artifactId><version>1.4.0-SNAPSHOT</version><versioning><snapshot><timestamp>20240101.123456</timestamp><buildNumber>7</buildNumber></snapshot><lastUpdated>20240101123456</lastUpdated><snapshotVersions><snapshotVersion><extension>jar</extension><value>1.4.0-20240101.123456-7</value><updated>20240101123456</updated></snapshotVersion></snapshotVersions></versioning></metadata>", Some("20240101.123456"), Some("7"); "snapshot version")]
+    #[test_case("<metadata><version>1.4.0-SNAPSHOT</version><versioning><snapshot><localCopy>true</localCopy></snapshot></versioning></metadata>", None, None; "local copy")]
+    #[test_case("<metadata><snapshot><timestamp>20240101.123456</timestamp></snapshot><versioning><snapshotVersions><snapshotVersion><snapshot><buildNumber>7</buildNumber></snapshot></snapshotVersion></snapshotVersions></versioning></metadata>", None, None; "snapshot outside of versioning")]
+    fn test_snapshot(input: &str, timestamp: Option<&str>, build_number: Option<&str>) {
+        let metadata = parse(input).unwrap();
+        for chunk_size in 1..input.len() {
+            let streamed = stream(input, chunk_size).unwrap();
+            assert_eq!(streamed, metadata, "chunks of {}", chunk_size);
+        }
+        assert_eq!(metadata.snapshot_timestamp.as_deref(), timestamp);
+        assert_eq!(metadata.snapshot_build_number.as_deref(), build_number);
+        assert_eq!(parse(&document(&metadata)).unwrap(), metadata);
+    }
+
+    #[test]
+    fn test_stream_invalid_xml() {
+        assert!(stream("<versions><version>1.0.0</version><</versions>", 8).is_err());
+    }
 }
@@ -0,0 +1,465 @@
+use crate::{
+    config::coordinates_table,
+    metadata::{self, Metadata},
+    Coordinates,
+};
+use console::style;
+use serde::Deserialize;
+use std::{
+    fmt::Display,
+    io,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+/// A maven2 repository for tests, served over plain HTTP by the hidden `mock-repo` subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MockRepo {
+    /// The files of a directory in the maven2 layout, e.g. a local repository.
+    Dir(PathBuf),
+    /// The artifacts of a fixture file.
+    Fixture(Vec<(Coordinates, MockArtifact)>),
+}
+
+/// The artifacts of a fixture file, keyed by `groupId:artifactId`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Fixture {
+    #[serde(deserialize_with = "coordinates_table")]
+    artifacts: Vec<(Coordinates, MockArtifact)>,
+}
+
+/// An artifact of a fixture file, which gets metadata, directory indexes, and a pom for every version.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct MockArtifact {
+    /// The versions in the metadata, oldest first.
+    versions: Vec<String>,
+    /// Answer all requests for the artifact with this status instead, e.g. `503`.
+    status: Option<u16>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    Read(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl MockRepo {
+    /// Serves the directory, or the artifacts of the fixture file.
+    pub(crate) fn load(path: &Path) -> Result<Self, Error> {
+        if path.is_dir() {
+            return Ok(Self::Dir(path.to_path_buf()));
+        }
+        let content =
+            std::fs::read_to_string(path).map_err(|e| Error::Read(path.to_path_buf(), e))?;
+        Self::parse(&content).map_err(|e| Error::Parse(path.to_path_buf(), e))
+    }
+
+    fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        let fixture = toml::from_str::<Fixture>(content)?;
+        Ok(Self::Fixture(fixture.artifacts))
+    }
+
+    /// The response for the path of a request, with checksums for files that do not have their own.
+    fn respond(&self, path: &str) -> Response {
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        if segments.contains(&"..") {
+            return Response::status(400);
+        }
+        let response = self.file(&segments, path.ends_with('/'));
+        if response.status != 404 {
+            return response;
+        }
+        let file = segments.last().copied().unwrap_or_default();
+        let checksum = CHECKSUMS
+            .iter()
+            .find_map(|(ext, algorithm)| Some((file.strip_suffix(ext)?, algorithm)));
+        match checksum {
+            Some((file, algorithm)) => {
+                let mut segments = segments.clone();
+                *segments.last_mut().unwrap() = file;
+                let response = self.file(&segments, false);
+                if response.status != 200 {
+                    return response;
+                }
+                let digest = ring::digest::digest(algorithm, &response.body);
+                Response::ok("text/plain", hex(digest.as_ref()).into_bytes())
+            }
+            None => response,
+        }
+    }
+
+    fn file(&self, segments: &[&str], is_dir: bool) -> Response {
+        match self {
+            MockRepo::Dir(dir) => dir_file(dir, segments),
+            MockRepo::Fixture(artifacts) => fixture_file(artifacts, segments, is_dir),
+        }
+    }
+}
+
+static CHECKSUMS: [(&str, &ring::digest::Algorithm); 3] = [
+    (".sha1", &ring::digest::SHA1_FOR_LEGACY_USE_ONLY),
+    (".sha256", &ring::digest::SHA256),
+    (".sha512", &ring::digest::SHA512),
+];
+
+fn dir_file(dir: &Path, segments: &[&str]) -> Response {
+    let path = segments
+        .iter()
+        .fold(dir.to_path_buf(), |path, s| path.join(s));
+    if path
+        .components()
+        .any(|component| matches!(component, Component::ParentDir))
+    {
+        return Response::status(400);
+    }
+    if path.is_dir() {
+        let entries = match std::fs::read_dir(&path) {
+            Ok(entries) => entries,
+            Err(_) => return Response::status(500),
+        };
+        let mut names = entries
+            .filter_map(Result::ok)
+            .map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if entry.path().is_dir() {
+                    format!("{}/", name)
+                } else {
+                    name
+                }
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        return listing(&names);
+    }
+    match std::fs::read(&path) {
+        Ok(body) => Response::ok(content_type(&path.to_string_lossy()), body),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Response::status(404),
+        Err(_) => Response::status(500),
+    }
+}
+
+fn fixture_file(
+    artifacts: &[(Coordinates, MockArtifact)],
+    segments: &[&str],
+    is_dir: bool,
+) -> Response {
+    let matching = |coordinates: &Coordinates, depth: usize| {
+        let group = coordinates.group_id.split('.');
+        segments.len() >= depth
+            && group
+                .chain(Some(coordinates.artifact.as_str()))
+                .take(depth)
+                .eq(segments[..depth].iter().copied())
+    };
+
+    for (coordinates, artifact) in artifacts {
+        let depth = coordinates.group_id.split('.').count() + 1;
+        if !matching(coordinates, depth) {
+            continue;
+        }
+        if let Some(status) = artifact.status {
+            return Response::status(status);
+        }
+        return match &segments[depth..] {
+            [] if is_dir => {
+                let mut names = artifact
+                    .versions
+                    .iter()
+                    .map(|version| format!("{}/", version))
+                    .collect::<Vec<_>>();
+                names.push(String::from("maven-metadata.xml"));
+                listing(&names)
+            }
+            ["maven-metadata.xml"] => {
+                let metadata = Metadata {
+                    group_id: Some(coordinates.group_id.clone()),
+                    artifact_id: Some(coordinates.artifact.clone()),
+                    versions: artifact.versions.clone(),
+                    latest: artifact.versions.last().cloned(),
+                    release: artifact
+                        .versions
+                        .iter()
+                        .rev()
+                        .find(|version| !version.ends_with("-SNAPSHOT"))
+                        .cloned(),
+                    ..Metadata::default()
+                };
+                Response::ok("text/xml", metadata::document(&metadata).into_bytes())
+            }
+            [version] if is_dir && artifact.versions.iter().any(|v| v == version) => {
+                listing(&[format!("{}-{}.pom", coordinates.artifact, version)])
+            }
+            [version, file]
+                if artifact.versions.iter().any(|v| v == version)
+                    && *file == format!("{}-{}.pom", coordinates.artifact, version) =>
+            {
+                Response::ok("text/xml", pom(coordinates, version).into_bytes())
+            }
+            _ => Response::status(404),
+        };
+    }
+
+    // the directory of a group lists the artifacts and subgroups in it
+    let mut names = artifacts
+        .iter()
+        .filter(|(coordinates, _)| matching(coordinates, segments.len()))
+        .filter_map(|(coordinates, _)| {
+            let group = coordinates.group_id.split('.');
+            let name = group
+                .chain(Some(coordinates.artifact.as_str()))
+                .nth(segments.len())?;
+            Some(format!("{}/", name))
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    if is_dir && !names.is_empty() {
+        listing(&names)
+    } else {
+        Response::status(404)
+    }
+}
+
+fn pom(coordinates: &Coordinates, version: &str) -> String {
+    format!(
+        "<project><modelVersion>4.0.0</modelVersion><groupId>{}</groupId><artifactId>{}</artifactId><version>{}</version></project>",
+        coordinates.group_id, coordinates.artifact, version
+    )
+}
+
+/// An HTML directory index, like the one of Maven Central.
+fn listing(names: &[String]) -> Response {
+    let links = names
+        .iter()
+        .map(|name| format!("<a href=\"{0}\">{0}</a>\n", name))
+        .collect::<String>();
+    let body = format!(
+        "<html><body><pre><a href=\"../\">../</a>\n{}</pre></body></html>",
+        links
+    );
+    Response::ok("text/html", body.into_bytes())
+}
+
+fn content_type(path: &str) -> &'static str {
+    match path.rsplit('.').next() {
+        Some("xml") | Some("pom") => "text/xml",
+        Some("json") | Some("module") => "application/json",
+        Some("sha1") | Some("sha256") | Some("sha512") | Some("md5") | Some("asc") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl Response {
+    fn ok(content_type: &'static str, body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type,
+            body,
+        }
+    }
+
+    fn status(status: u16) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: Vec::new(),
+        }
+    }
+}
+
+/// Answers the `GET` and `HEAD` requests of the connections, until the listener fails.
+pub(crate) async fn serve(repo: MockRepo, listener: TcpListener) -> io::Result<()> {
+    let repo = Arc::new(repo);
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let repo = Arc::clone(&repo);
+        tokio::spawn(async move {
+            // a broken connection only concerns its client
+            let _ = handle(&repo, stream).await;
+        });
+    }
+}
+
+/// Answers one request and closes the connection.
+async fn handle(repo: &MockRepo, mut stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(&mut stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or("/");
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let response = match method {
+        "GET" | "HEAD" => repo.respond(path),
+        _ => Response::status(405),
+    };
+    eprintln!("{} {} {}", method, target, status(response.status));
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    if method != "HEAD" {
+        stream.write_all(&response.body).await?;
+    }
+    stream.shutdown().await
+}
+
+fn status(status: u16) -> impl Display {
+    match status {
+        200..=299 => style(status).green(),
+        400..=499 => style(status).yellow(),
+        _ => style(status).red(),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Read(path, e) => write!(
+                f,
+                "Could not read the repository {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+            Error::Parse(path, e) => write!(
+                f,
+                "Could not parse the fixture file {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Read(_, src) => Some(src),
+            Error::Parse(_, src) => Some(src),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tokio::io::AsyncReadExt;
+
+    fn fixture() -> MockRepo {
+        MockRepo::parse(
+            r#"
+            [artifacts."com.example:lib"]
+            versions = ["1.0.0", "1.1.0", "2.0.0-SNAPSHOT"]
+
+            [artifacts."com.example:flaky"]
+            status = 503
+
+            [artifacts."com.example.sub:tool"]
+            versions = ["0.1.0"]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test_case("/com/example/lib/maven-metadata.xml" => (200, String::from("<metadata><groupId><![CDATA[com.example]]></groupId><artifactId><![CDATA[lib]]></artifactId><versioning><latest><![CDATA[2.0.0-SNAPSHOT]]></latest><release><![CDATA[1.1.0]]></release><versions><version><![CDATA[1.0.0]]></version><version><![CDATA[1.1.0]]></version><version><![CDATA[2.0.0-SNAPSHOT]]></version></versions></versioning></metadata>")); "metadata")]
+    #[test_case("/com/example/lib/1.1.0/lib-1.1.0.pom" => (200, String::from("<project><modelVersion>4.0.0</modelVersion><groupId>com.example</groupId><artifactId>lib</artifactId><version>1.1.0</version></project>")); "pom")]
+    #[test_case("/com/example/lib/1.1.0/lib-1.1.0.pom.sha1" => (200, String::from("a57e0efd01f4f7b5eb84862c26e061d32f081481")); "checksum")]
+    #[test_case("/com/example/lib/3.0.0/lib-3.0.0.pom" => (404, String::new()); "unknown version")]
+    #[test_case("/com/example/flaky/maven-metadata.xml" => (503, String::new()); "status")]
+    #[test_case("/com/example/../lib/maven-metadata.xml" => (400, String::new()); "parent directory")]
+    fn test_fixture(path: &str) -> (u16, String) {
+        let response = fixture().respond(path);
+        (response.status, String::from_utf8(response.body).unwrap())
+    }
+
+    #[test_case("/com/example/" => vec!["flaky", "lib", "sub"]; "group")]
+    #[test_case("/com/example/lib/" => vec!["1.0.0", "1.1.0", "2.0.0-SNAPSHOT"]; "versions")]
+    #[test_case("/com/example/sub/tool/0.1.0/" => Vec::<String>::new(); "version")]
+    fn test_fixture_listing(path: &str) -> Vec<String> {
+        let response = fixture().respond(path);
+        assert_eq!(response.status, 200);
+        crate::listing::parse_directories(&String::from_utf8(response.body).unwrap())
+    }
+
+    #[test]
+    fn test_fixture_checksum() {
+        let pom = fixture().respond("/com/example/lib/1.1.0/lib-1.1.0.pom");
+        let checksum = fixture().respond("/com/example/lib/1.1.0/lib-1.1.0.pom.sha256");
+        let digest = ring::digest::digest(&ring::digest::SHA256, &pom.body);
+        assert_eq!(checksum.body, hex(digest.as_ref()).into_bytes());
+    }
+
+    #[test]
+    fn test_invalid_fixture() {
+        let err = MockRepo::parse("[artifacts.lib]\nversions = []").unwrap_err();
+        assert!(err.to_string().contains("invalid coordinates `lib`"));
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(fixture(), listener));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                b"GET /com/example/flaky/maven-metadata.xml HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(response.ends_with("Content-Length: 0\r\nConnection: close\r\n\r\n"));
+    }
+}
@@ -0,0 +1,440 @@
+use crate::{
+    http::{ApiClient, ApiResponse},
+    Coordinates,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+static OSV_API: &str = "https://api.osv.dev/v1";
+
+/// The ecosystem of Maven packages in OSV, whose names are `group:artifact`.
+static ECOSYSTEM: &str = "Maven";
+
+/// The most queries that OSV accepts in one batch.
+const MAX_BATCH: usize = 1000;
+
+/// Looks up the known vulnerabilities of versions in the OSV database at https://osv.dev.
+#[derive(Debug, Clone)]
+pub(crate) struct Osv {
+    http: ApiClient,
+}
+
+/// A known vulnerability of a version.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct Vulnerability {
+    /// The id in OSV, e.g. `GHSA-jfh8-c2jp-5v3q`.
+    pub(crate) id: String,
+    /// Other ids of the same vulnerability, e.g. `CVE-2021-44228`.
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    pub(crate) summary: Option<String>,
+    /// The CVSS vectors of the vulnerability.
+    #[serde(default)]
+    severity: Vec<Score>,
+    /// The severity that GitHub assigned to its advisories.
+    #[serde(default)]
+    database_specific: Option<DatabaseSpecific>,
+}
+
+/// How severe a vulnerability is, by the ranges of the CVSS v3 base score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct Score {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct DatabaseSpecific {
+    severity: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryResponse {
+    #[serde(default)]
+    vulns: Vec<Vulnerability>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResponse {
+    results: Vec<QueryResponse>,
+}
+
+impl Osv {
+    pub(crate) fn new(http: ApiClient) -> Self {
+        Self { http }
+    }
+
+    /// The known vulnerabilities of the version.
+    pub(crate) async fn vulnerabilities(
+        &self,
+        coordinates: &Coordinates,
+        version: &str,
+    ) -> Result<Vec<Vulnerability>, BoxError> {
+        let query = query(coordinates, version);
+        let response = self.post("query", &query).await?;
+        Ok(serde_json::from_str::<QueryResponse>(&response)?.vulns)
+    }
+
+    /// Whether each of the versions has known vulnerabilities, with as few requests as possible.
+    pub(crate) async fn are_vulnerable(
+        &self,
+        coordinates: &Coordinates,
+        versions: &[&str],
+    ) -> Result<Vec<bool>, BoxError> {
+        let found = self.batch(coordinates, versions).await?;
+        Ok(found.iter().map(|found| !found.is_empty()).collect())
+    }
+
+    /// The known vulnerabilities of each of the versions.
+    ///
+    /// The batch query only tells the ids, so that each distinct vulnerability is looked up once more.
+    pub(crate) async fn affecting(
+        &self,
+        coordinates: &Coordinates,
+        versions: &[&str],
+    ) -> Result<Vec<Vec<Vulnerability>>, BoxError> {
+        let found = self.batch(coordinates, versions).await?;
+        let mut known = HashMap::new();
+        for vulnerability in found.iter().flatten() {
+            if !known.contains_key(&vulnerability.id) {
+                let response = self.get(&format!("vulns/{}", vulnerability.id)).await?;
+                let details = serde_json::from_str::<Vulnerability>(&response)?;
+                known.insert(vulnerability.id.clone(), details);
+            }
+        }
+        Ok(found
+            .into_iter()
+            .map(|found| found.iter().map(|v| known[&v.id].clone()).collect())
+            .collect())
+    }
+
+    /// The vulnerabilities of each of the versions, with only their ids.
+    async fn batch(
+        &self,
+        coordinates: &Coordinates,
+        versions: &[&str],
+    ) -> Result<Vec<Vec<Vulnerability>>, BoxError> {
+        let mut vulnerable = Vec::with_capacity(versions.len());
+        for versions in versions.chunks(MAX_BATCH) {
+            let queries = versions
+                .iter()
+                .map(|version| query(coordinates, version))
+                .collect::<Vec<_>>();
+            let response = self
+                .post("querybatch", &json!({ "queries": queries }))
+                .await?;
+            let results = serde_json::from_str::<BatchResponse>(&response)?.results;
+            if results.len() != versions.len() {
+                return Err(format!(
+                    "OSV answered {} queries with {} results",
+                    versions.len(),
+                    results.len()
+                )
+                .into());
+            }
+            vulnerable.extend(results.into_iter().map(|result| result.vulns));
+        }
+        Ok(vulnerable)
+    }
+
+    async fn get(&self, endpoint: &str) -> Result<String, BoxError> {
+        let response = self
+            .http
+            .get(format!("{}/{}", OSV_API, endpoint))
+            .send()
+            .await?;
+        check(response)
+    }
+
+    async fn post(&self, endpoint: &str, body: &serde_json::Value) -> Result<String, BoxError> {
+        let response = self
+            .http
+            .post(format!("{}/{}", OSV_API, endpoint))
+            .json(body)
+            .send()
+            .await?;
+        check(response)
+    }
+}
+
+fn check(response: ApiResponse) -> Result<String, BoxError> {
+    if !response.is_success() {
+        return Err(format!(
+            "The OSV request failed with {}: {}",
+            response.status, response.body
+        )
+        .into());
+    }
+    Ok(response.body)
+}
+
+fn query(coordinates: &Coordinates, version: &str) -> serde_json::Value {
+    json!({
+        "package": {
+            "name": format!("{}:{}", coordinates.group_id, coordinates.artifact),
+            "ecosystem": ECOSYSTEM,
+        },
+        "version": version,
+    })
+}
+
+impl Vulnerability {
+    /// The CVE of the vulnerability if it has one, otherwise its OSV id.
+    pub(crate) fn name(&self) -> &str {
+        self.aliases
+            .iter()
+            .find(|alias| alias.starts_with("CVE-"))
+            .unwrap_or(&self.id)
+    }
+
+    /// The severity that GitHub assigned, or else the one of the highest CVSS v3 score.
+    pub(crate) fn severity(&self) -> Option<Severity> {
+        let assigned = self
+            .database_specific
+            .as_ref()
+            .and_then(|specific| specific.severity.as_deref())
+            .and_then(Severity::parse);
+        assigned.or_else(|| {
+            self.severity
+                .iter()
+                .filter(|score| score.kind == "CVSS_V3")
+                .filter_map(|score| cvss3_score(&score.score))
+                .map(Severity::of_score)
+                .max()
+        })
+    }
+
+    /// Whether the vulnerability is at least this severe, which it might be if its severity is unknown.
+    pub(crate) fn is_at_least(&self, severity: Severity) -> bool {
+        self.severity().is_none_or(|own| own >= severity)
+    }
+}
+
+impl Severity {
+    /// Parses the severity, `moderate` is what GitHub calls medium.
+    pub(crate) fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "low" => Some(Severity::Low),
+            "medium" | "moderate" => Some(Severity::Medium),
+            "high" => Some(Severity::High),
+            "critical" => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+
+    fn of_score(score: f64) -> Self {
+        if score >= 9.0 {
+            Severity::Critical
+        } else if score >= 7.0 {
+            Severity::High
+        } else if score >= 4.0 {
+            Severity::Medium
+        } else {
+            Severity::Low
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+/// The base score of a CVSS v3 vector like `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`.
+fn cvss3_score(vector: &str) -> Option<f64> {
+    let metrics = vector
+        .strip_prefix("CVSS:3.")?
+        .split('/')
+        .skip(1)
+        .filter_map(|metric| metric.split_once(':'))
+        .collect::<HashMap<_, _>>();
+    let changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let attack_vector = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let attack_complexity = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let privileges = match (*metrics.get("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let interaction = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact = |metric| match *metrics.get(metric)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let base = 1.0 - (1.0 - impact("C")?) * (1.0 - impact("I")?) * (1.0 - impact("A")?);
+    let impact = if changed {
+        7.52 * (base - 0.029) - 3.25 * (base - 0.02_f64).powi(15)
+    } else {
+        6.42 * base
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * attack_vector * attack_complexity * privileges * interaction;
+    let score = if changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+    Some(round_up(score.min(10.0)))
+}
+
+/// Rounds up to one decimal, the way the CVSS v3.1 specification does to avoid floating point errors.
+fn round_up(score: f64) -> f64 {
+    let scaled = (score * 100_000.0).round() as u64;
+    if scaled.is_multiple_of(10_000) {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_parse_vulnerabilities() {
+        let response = serde_json::from_str::<QueryResponse>(
+            r#"{
+                "vulns": [
+                    {
+                        "id": "GHSA-jfh8-c2jp-5v3q",
+                        "summary": "Remote code injection in Log4j",
+                        "aliases": ["CVE-2021-44228"],
+                        "modified": "2024-01-01T00:00:00Z"
+                    },
+                    { "id": "GHSA-p6xc-xr62-6r2g" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let names = response
+            .vulns
+            .iter()
+            .map(Vulnerability::name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["CVE-2021-44228", "GHSA-p6xc-xr62-6r2g"]);
+        assert_eq!(
+            response.vulns[0].summary.as_deref(),
+            Some("Remote code injection in Log4j")
+        );
+    }
+
+    #[test]
+    fn test_parse_no_vulnerabilities() {
+        let response = serde_json::from_str::<BatchResponse>(
+            r#"{ "results": [ {}, { "vulns": [ { "id": "GHSA-jfh8-c2jp-5v3q" } ] } ] }"#,
+        )
+        .unwrap();
+        let vulnerable = response
+            .results
+            .iter()
+            .map(|result| !result.vulns.is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(vulnerable, vec![false, true]);
+    }
+
+    #[test_case("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H" => Some(10.0); "log4shell")]
+    #[test_case("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H" => Some(9.8); "critical")]
+    #[test_case("CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:L/I:N/A:N" => Some(3.7); "low")]
+    #[test_case("CVSS:3.0/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:N/A:N" => Some(5.5); "medium")]
+    #[test_case("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N" => Some(0.0); "no impact")]
+    #[test_case("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N" => None; "cvss 4")]
+    fn test_cvss3_score(vector: &str) -> Option<f64> {
+        cvss3_score(vector)
+    }
+
+    #[test]
+    fn test_severity() {
+        let vulnerability = serde_json::from_str::<Vulnerability>(
+            r#"{
+                "id": "GHSA-jfh8-c2jp-5v3q",
+                "severity": [
+                    { "type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:L/I:N/A:N" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(vulnerability.severity(), Some(Severity::Low));
+        assert!(vulnerability.is_at_least(Severity::Low));
+        assert!(!vulnerability.is_at_least(Severity::Medium));
+
+        let vulnerability = serde_json::from_str::<Vulnerability>(
+            r#"{
+                "id": "GHSA-jfh8-c2jp-5v3q",
+                "severity": [
+                    { "type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:L/I:N/A:N" }
+                ],
+                "database_specific": { "severity": "MODERATE" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(vulnerability.severity(), Some(Severity::Medium));
+
+        let unknown = serde_json::from_str::<Vulnerability>(r#"{ "id": "OSV-2020-1" }"#).unwrap();
+        assert_eq!(unknown.severity(), None);
+        assert!(unknown.is_at_least(Severity::Critical));
+    }
+
+    #[test]
+    fn test_query() {
+        let query = query(
+            &Coordinates::new("org.apache.logging.log4j", "log4j-core"),
+            "2.14.1",
+        );
+        assert_eq!(
+            query,
+            json!({
+                "package": { "name": "org.apache.logging.log4j:log4j-core", "ecosystem": "Maven" },
+                "version": "2.14.1"
+            })
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Parses coordinates and version requirements, e.g. `org.neo4j:neo4j:^4` or `[1.0,2.0)`.
+use crate::{
+    regex::Error as RegexError,
+    versions::{PublishedVersion, Requirement},
+    Coordinates, ErrorKind, VersionCheck,
+};
+use console::style;
+use semver::{Error as ReqParseError, VersionReq};
+use std::{fmt::Display, str::FromStr};
+
+/// Coordinates or options that cannot be parsed, which is an [`ErrorKind::InvalidInput`] in the API.
+#[derive(Debug)]
+#[cfg_attr(not(feature = "runtime"), allow(dead_code))] // most are only returned for the options of the binary
+pub(crate) enum Error {
+    EmptyGroupId(String),
+    EmptyArtifact(String),
+    InvalidRange(String, ReqParseError),
+    NoProxy,
+    MissingProxyPassword(String),
+    InvalidHeader(String),
+    InvalidDuration(String),
+    InvalidResolve(String),
+    InvalidSize(String),
+    InvalidVersionOrder(String),
+    InvalidMavenRange(String),
+    InvalidPattern(RegexError),
+    InvalidVersion(String),
+    InvalidReleaseTime(String),
+    InvalidBump(String),
+    InvalidSeverity(String),
+    InvalidJava(String),
+    InvalidLimit(String),
+}
+
+pub(crate) fn parse_coordinates(input: &str) -> Result<VersionCheck, Error> {
+    // the current version is a suffix, e.g. `group:artifact:^1@1.2.3`
+    let (coordinates, current) = match input.split_once('@') {
+        Some((coordinates, current)) => (coordinates, Some(parse_current(current)?)),
+        None => (input, None),
+    };
+    let mut segments = coordinates.split(':').map(str::trim);
+    let first = segments.next().unwrap_or_default();
+    // Clojure style `group/artifact`
+    let (group_id, artifact) = match first.split_once('/') {
+        Some((group_id, artifact)) => (group_id.trim(), Some(artifact.trim())),
+        None => (first, None),
+    };
+    if group_id.is_empty() {
+        return Err(Error::EmptyGroupId(input.into()));
+    }
+    let artifact = match artifact.or_else(|| segments.next()) {
+        Some(artifact_id) if !artifact_id.is_empty() => String::from(artifact_id),
+        Some(_) => return Err(Error::EmptyArtifact(input.into())),
+        // like Leiningen, the groupId defaults to the artifact
+        None => String::from(group_id),
+    };
+    let group_id = String::from(group_id);
+
+    let versions = segments.map(parse_version).collect::<Result<Vec<_>, _>>()?;
+    Ok(VersionCheck {
+        coordinates: Coordinates { group_id, artifact },
+        versions,
+        current,
+        parent: false,
+    })
+}
+
+fn parse_current(input: &str) -> Result<PublishedVersion, Error> {
+    let input = input.trim();
+    PublishedVersion::parse(input).ok_or_else(|| Error::InvalidVersion(input.into()))
+}
+
+pub(crate) fn parse_plain_coordinates(input: &str) -> Result<Coordinates, Error> {
+    parse_coordinates(input).map(|check| check.coordinates)
+}
+
+impl FromStr for VersionCheck {
+    type Err = crate::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(parse_coordinates(input)?)
+    }
+}
+
+impl FromStr for Coordinates {
+    type Err = crate::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(parse_plain_coordinates(input)?)
+    }
+}
+
+impl FromStr for Requirement {
+    type Err = crate::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(parse_version(input)?)
+    }
+}
+
+impl From<Error> for crate::Error {
+    fn from(error: Error) -> Self {
+        ErrorKind::InvalidInput(Box::new(error)).into()
+    }
+}
+fn parse_version(version: &str) -> Result<Requirement, Error> {
+    let mut alternatives = Vec::new();
+    let mut excluded = Vec::new();
+    for alternative in version.split("||") {
+        let (req, exclusions) = parse_range(alternative, version)?;
+        alternatives.extend(req);
+        excluded.extend(exclusions);
+    }
+    if alternatives.is_empty() {
+        alternatives.push(VersionReq::STAR);
+    }
+    Ok(Requirement {
+        alternatives,
+        excluded,
+    })
+}
+
+/// Parses one alternative of the requirement `version`, which is `None` if it only excludes versions.
+fn parse_range(
+    alternative: &str,
+    version: &str,
+) -> Result<(Option<VersionReq>, Vec<PublishedVersion>), Error> {
+    // exclusions like `!=1.4.2` can follow any range, e.g. `^1,!=1.4.2` or `[1.0,2.0),!=1.4.2`
+    let (exclusions, range): (Vec<_>, Vec<_>) = alternative
+        .split(',')
+        .partition(|part| part.trim_start().starts_with("!="));
+    let excluded = exclusions
+        .into_iter()
+        .map(|exclusion| {
+            let excluded = exclusion.trim_start()[2..].trim();
+            PublishedVersion::parse(excluded).ok_or_else(|| Error::InvalidVersion(excluded.into()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let range = range.join(",");
+    let trimmed = range.trim();
+    let req = if trimmed.is_empty() && !excluded.is_empty() {
+        None
+    } else if trimmed.starts_with(['[', '(']) {
+        let range = maven_range(trimmed).ok_or_else(|| Error::InvalidMavenRange(version.into()))?;
+        Some(VersionReq::parse(&range).map_err(|e| Error::InvalidRange(version.into(), e))?)
+    } else {
+        Some(VersionReq::parse(&range).map_err(|e| Error::InvalidRange(version.into(), e))?)
+    };
+    Ok((req, excluded))
+}
+
+/// Translates a Maven range like `[1.0,2.0)` or `(,1.5]` into a requirement like `>=1.0.0, <2.0.0`.
+///
+/// A union of ranges like `[1,2),[3,4)` is not supported.
+fn maven_range(range: &str) -> Option<String> {
+    let lower_inclusive = match range.chars().next()? {
+        '[' => true,
+        '(' => false,
+        _ => return None,
+    };
+    let upper_inclusive = match range.chars().last()? {
+        ']' => true,
+        ')' => false,
+        _ => return None,
+    };
+    let inner = range.get(1..range.len() - 1)?;
+    if inner.contains(['[', ']', '(', ')']) {
+        return None;
+    }
+    let (lower, upper) = match inner.split_once(',') {
+        Some((lower, upper)) if !upper.contains(',') => (lower.trim(), upper.trim()),
+        Some(_) => return None,
+        // `[1.0]` is exactly that version
+        None => {
+            let version = inner.trim();
+            return (lower_inclusive && upper_inclusive && !version.is_empty())
+                .then(|| format!("={}", maven_range_version(version)));
+        }
+    };
+    let mut comparators = Vec::new();
+    if !lower.is_empty() {
+        let op = if lower_inclusive { ">=" } else { ">" };
+        comparators.push(format!("{}{}", op, maven_range_version(lower)));
+    }
+    if !upper.is_empty() {
+        let op = if upper_inclusive { "<=" } else { "<" };
+        comparators.push(format!("{}{}", op, maven_range_version(upper)));
+    }
+    if comparators.is_empty() {
+        return Some(String::from("*"));
+    }
+    Some(comparators.join(", "))
+}
+
+/// Pads the version to three numbers, since `1.5` in Maven is `1.5.0`,
+/// while a semver requirement like `<=1.5` would also match `1.5.9`.
+fn maven_range_version(version: &str) -> String {
+    let (numbers, qualifier) = version.split_at(version.find(['-', '+']).unwrap_or(version.len()));
+    let padding = 3_usize.saturating_sub(numbers.split('.').count());
+    format!("{}{}{}", numbers, ".0".repeat(padding), qualifier)
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::EmptyGroupId(input) => write!(
+                f,
+                "The groupId may not be empty in {}",
+                style(input).red().bold()
+            ),
+            Error::EmptyArtifact(input) => write!(
+                f,
+                "The artifact may not be empty in {}",
+                style(input).red().bold()
+            ),
+            Error::InvalidRange(input, _) => write!(
+                f,
+                "Could not parse {} into a semantic version range. Please provide a valid range according to {}",
+                style(input).red().bold(),
+                style("https://www.npmjs.com/package/semver#advanced-range-syntax").cyan().underlined(),
+            ),
+            Error::InvalidDuration(input) => write!(
+                f,
+                "The duration {} is invalid. Please use seconds or a unit, e.g. {}",
+                style(input).red().bold(),
+                style("500ms, 30s, 2m").cyan(),
+            ),
+            Error::InvalidHeader(input) => write!(
+                f,
+                "The header {} is invalid. Please use the form {}",
+                style(input).red().bold(),
+                style("Name: value").cyan(),
+            ),
+            Error::InvalidSize(input) => write!(
+                f,
+                "The size {} is invalid. Please use bytes or a unit, e.g. {}",
+                style(input).red().bold(),
+                style("512K, 100M, 1G").cyan(),
+            ),
+            Error::InvalidMavenRange(input) => write!(
+                f,
+                "Could not parse {} into a Maven version range. Please provide a single range, e.g. {}",
+                style(input).red().bold(),
+                style("[1.0,2.0), (,1.5], [1.2.3]").cyan(),
+            ),
+            Error::InvalidVersion(input) => write!(
+                f,
+                "Could not parse {} into a version. Please provide a version, e.g. {}",
+                style(input).red().bold(),
+                style("1.2.3").cyan(),
+            ),
+            Error::InvalidBump(input) => write!(
+                f,
+                "The update size {} is unknown. Please use {}",
+                style(input).red().bold(),
+                style("major, minor, patch, pre-release").cyan(),
+            ),
+            Error::InvalidSeverity(input) => write!(
+                f,
+                "The severity {} is unknown. Please use {}",
+                style(input).red().bold(),
+                style("low, medium, high, critical").cyan(),
+            ),
+            Error::InvalidJava(input) => write!(
+                f,
+                "Could not parse {} into a Java version. Please provide a version, e.g. {}",
+                style(input).red().bold(),
+                style("17").cyan(),
+            ),
+            Error::InvalidLimit(input) => write!(
+                f,
+                "Could not parse {} into a limit. Please provide a positive number, e.g. {}",
+                style(input).red().bold(),
+                style("8").cyan(),
+            ),
+            Error::InvalidReleaseTime(input) => write!(
+                f,
+                "Could not parse {} into a point in time. Please use a duration like {} or a date like {}",
+                style(input).red().bold(),
+                style("14d").cyan(),
+                style("2024-01-31").cyan(),
+            ),
+            Error::InvalidPattern(e) => write!(
+                f,
+                "The regular expression is invalid: {}",
+                style(e).red().bold(),
+            ),
+            Error::InvalidVersionOrder(input) => write!(
+                f,
+                "The ordering {} is unknown. Please use {}",
+                style(input).red().bold(),
+                style("semver, maven").cyan(),
+            ),
+            Error::InvalidResolve(input) => write!(
+                f,
+                "The address override {} is invalid. Please use the form {}",
+                style(input).red().bold(),
+                style("host:addr").cyan(),
+            ),
+            Error::NoProxy => write!(
+                f,
+                "A proxy user was given, but there is no proxy. Please provide one with {}",
+                style("--proxy").cyan(),
+            ),
+            Error::MissingProxyPassword(var) => write!(
+                f,
+                "The environment variable {} for the proxy password is not set",
+                style(var).red().bold()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidRange(_, src) => Some(src),
+            Error::InvalidPattern(src) => Some(src),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::EmptyGroupId(lhs), Self::EmptyGroupId(rhs)) => lhs == rhs,
+            (Self::EmptyArtifact(lhs), Self::EmptyArtifact(rhs)) => lhs == rhs,
+            (Self::InvalidRange(lhs, _), Self::InvalidRange(rhs, _)) => lhs == rhs,
+            (Self::InvalidHeader(lhs), Self::InvalidHeader(rhs)) => lhs == rhs,
+            (Self::InvalidDuration(lhs), Self::InvalidDuration(rhs)) => lhs == rhs,
+            (Self::InvalidResolve(lhs), Self::InvalidResolve(rhs)) => lhs == rhs,
+            (Self::InvalidSize(lhs), Self::InvalidSize(rhs)) => lhs == rhs,
+            (Self::InvalidVersionOrder(lhs), Self::InvalidVersionOrder(rhs)) => lhs == rhs,
+            (Self::InvalidMavenRange(lhs), Self::InvalidMavenRange(rhs)) => lhs == rhs,
+            (Self::InvalidVersion(lhs), Self::InvalidVersion(rhs)) => lhs == rhs,
+            (Self::InvalidReleaseTime(lhs), Self::InvalidReleaseTime(rhs)) => lhs == rhs,
+            (Self::InvalidBump(lhs), Self::InvalidBump(rhs)) => lhs == rhs,
+            (Self::InvalidSeverity(lhs), Self::InvalidSeverity(rhs)) => lhs == rhs,
+            (Self::InvalidJava(lhs), Self::InvalidJava(rhs)) => lhs == rhs,
+            (Self::InvalidLimit(lhs), Self::InvalidLimit(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let check = "org.neo4j:neo4j:^4".parse::<VersionCheck>().unwrap();
+        assert_eq!(check, parse_coordinates("org.neo4j:neo4j:^4").unwrap());
+        assert_eq!(check.coordinates().to_string(), "org.neo4j:neo4j");
+        let coordinates = "org.neo4j:neo4j".parse::<Coordinates>().unwrap();
+        assert_eq!(coordinates, Coordinates::new("org.neo4j", "neo4j"));
+        let requirement = "~1.1 || ~1.3".parse::<Requirement>().unwrap();
+        assert_eq!(requirement, parse_version("~1.1 || ~1.3").unwrap());
+        assert!(":neo4j".parse::<VersionCheck>().is_err());
+    }
+}
@@ -0,0 +1,602 @@
+use crate::{xml::Element, Coordinates};
+use console::style;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// The parts of a `pom.xml` that are relevant for checking versions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Pom {
+    /// Dependencies and plugins, including the managed ones.
+    pub(crate) dependencies: Vec<Coordinates>,
+    /// The versions of the dependencies and plugins, as they are declared first.
+    pub(crate) versions: Vec<(Coordinates, String)>,
+    /// Repositories and plugin repositories.
+    pub(crate) repositories: Vec<Repository>,
+    /// The packaging of the project itself, e.g. `pom` for a BOM, `jar` if it has none.
+    pub(crate) packaging: Option<String>,
+    /// What the project itself is, as far as the pom tells.
+    pub(crate) details: Details,
+    /// The coordinates and version of the parent pom.
+    pub(crate) parent: Option<(Coordinates, String)>,
+    /// The dependencies of the project itself, without the managed ones and plugins.
+    pub(crate) declared: Vec<Dependency>,
+    /// The managed dependencies, including the imported BOMs.
+    pub(crate) managed: Vec<Dependency>,
+    /// The properties of the pom, to interpolate what refers to the properties of its parent.
+    pub(crate) properties: Properties,
+}
+
+/// A dependency as it is declared, its version and scope might come from the dependency management.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Dependency {
+    pub(crate) coordinates: Coordinates,
+    pub(crate) version: Option<String>,
+    pub(crate) scope: Option<String>,
+    pub(crate) optional: bool,
+}
+
+/// The description of the project, without what it inherits from its parent.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Details {
+    pub(crate) name: Option<String>,
+    /// The description, with its line breaks and indentation collapsed.
+    pub(crate) description: Option<String>,
+    pub(crate) url: Option<String>,
+    /// The names of the licenses, or their URLs if they have no name.
+    pub(crate) licenses: Vec<String>,
+    /// The URL of the source repository, or its connection if it has no URL.
+    pub(crate) scm: Option<String>,
+    /// The tag of the version in the source repository, often just `HEAD`.
+    pub(crate) scm_tag: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Repository {
+    pub(crate) id: String,
+    pub(crate) url: String,
+}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, xmlparser::Error),
+}
+
+/// The groupId that Maven assumes for plugins without one.
+static DEFAULT_PLUGIN_GROUP: &str = "org.apache.maven.plugins";
+
+static DEPENDENCIES: [&[&str]; 2] = [&["dependencies"], &["dependencyManagement", "dependencies"]];
+
+static PLUGINS: [&[&str]; 2] = [
+    &["build", "plugins"],
+    &["build", "pluginManagement", "plugins"],
+];
+
+impl Pom {
+    pub(crate) fn read(path: &Path) -> Result<Self, Error> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| Error::Read(path.to_path_buf(), e))?;
+        Self::parse(&content).map_err(|e| Error::Parse(path.to_path_buf(), e))
+    }
+
+    pub(crate) fn parse(content: &str) -> Result<Self, xmlparser::Error> {
+        let root = Element::parse(content)?;
+        let properties = Properties::of(&root);
+
+        let dependencies = DEPENDENCIES
+            .iter()
+            .filter_map(|path| root.find(path))
+            .flat_map(|deps| deps.children("dependency"))
+            .filter_map(|dep| Some((properties.coordinates(dep, None)?, dep)));
+        let [declared, managed] = DEPENDENCIES.map(|path| {
+            root.find(path)
+                .into_iter()
+                .flat_map(|deps| deps.children("dependency"))
+                .filter_map(|dep| properties.dependency(dep))
+                .collect()
+        });
+        let parent = root.child("parent").and_then(|parent| {
+            let coordinates = properties.coordinates(parent, None)?;
+            let version = properties.interpolate(parent.child_text("version")?);
+            Some((coordinates, version))
+        });
+
+        let plugins = PLUGINS
+            .iter()
+            .filter_map(|path| root.find(path))
+            .flat_map(|plugins| plugins.children("plugin"))
+            .filter_map(|plugin| {
+                let coordinates = properties.coordinates(plugin, Some(DEFAULT_PLUGIN_GROUP))?;
+                Some((coordinates, plugin))
+            });
+
+        let mut all_dependencies = Vec::new();
+        let mut versions = Vec::new();
+        for (coordinates, element) in dependencies.chain(plugins) {
+            if let Some(version) = element.child_text("version") {
+                if !versions.iter().any(|(c, _)| *c == coordinates) {
+                    versions.push((coordinates.clone(), properties.interpolate(version)));
+                }
+            }
+            if !all_dependencies.contains(&coordinates) {
+                all_dependencies.push(coordinates);
+            }
+        }
+
+        let repositories = [
+            ("repositories", "repository"),
+            ("pluginRepositories", "pluginRepository"),
+        ]
+        .iter()
+        .filter_map(|(parent, child)| Some((root.child(parent)?, *child)))
+        .flat_map(|(repos, child)| repos.children(child))
+        .filter_map(|repo| {
+            Some(Repository {
+                id: properties.interpolate(repo.child_text("id")?),
+                url: properties.interpolate(repo.child_text("url")?),
+            })
+        })
+        .fold(Vec::new(), |mut repos, repo| {
+            if !repos.iter().any(|r: &Repository| r.url == repo.url) {
+                repos.push(repo);
+            }
+            repos
+        });
+
+        Ok(Self {
+            dependencies: all_dependencies,
+            versions,
+            repositories,
+            packaging: root
+                .child_text("packaging")
+                .map(|p| properties.interpolate(p)),
+            details: Details::of(&root, &properties),
+            parent,
+            declared,
+            managed,
+            properties,
+        })
+    }
+
+    /// The extension of the artifact that the packaging produces, `None` if the pom is the only artifact.
+    pub(crate) fn artifact_extension(&self) -> Option<&str> {
+        match self.packaging.as_deref() {
+            None | Some("jar") | Some("bundle") | Some("maven-plugin") | Some("ejb") => Some("jar"),
+            Some("pom") => None,
+            Some(packaging) => Some(packaging),
+        }
+    }
+
+    /// The declared version of the dependency or plugin, if it has one.
+    pub(crate) fn version(&self, coordinates: &Coordinates) -> Option<&str> {
+        self.versions
+            .iter()
+            .find(|(c, _)| c == coordinates)
+            .map(|(_, version)| version.as_str())
+    }
+}
+
+impl Repository {
+    /// Whether the URL is a plain http(s) URL.
+    ///
+    /// The poms of a project are not trusted like the command line, so their repositories cannot use the
+    /// other schemes and presets of `--resolver`, e.g. `exec:`, `local:`, or `file:`.
+    pub(crate) fn is_http(&self) -> bool {
+        Url::parse(&self.url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    }
+}
+
+impl Details {
+    fn of(root: &Element, properties: &Properties) -> Self {
+        let text = |element: Option<&Element>, name: &str| {
+            element
+                .and_then(|e| e.child_text(name))
+                .map(|text| properties.interpolate(text))
+        };
+        let licenses = root
+            .child("licenses")
+            .into_iter()
+            .flat_map(|licenses| licenses.children("license"))
+            .filter_map(|license| {
+                text(Some(license), "name").or_else(|| text(Some(license), "url"))
+            })
+            .collect();
+        let scm = root.child("scm");
+        let connection = text(scm, "connection").map(|connection| {
+            // `scm:git:https://…` names the provider before the URL
+            match connection
+                .strip_prefix("scm:")
+                .and_then(|c| c.split_once(':'))
+            {
+                Some((_, url)) => String::from(url),
+                None => connection,
+            }
+        });
+        Self {
+            name: text(Some(root), "name"),
+            description: text(Some(root), "description")
+                .map(|description| description.split_whitespace().collect::<Vec<_>>().join(" ")),
+            url: text(Some(root), "url"),
+            licenses,
+            scm: text(scm, "url").or(connection),
+            scm_tag: text(scm, "tag"),
+        }
+    }
+}
+
+/// Properties of the pom that can be referenced as `${name}`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Properties(HashMap<String, String>);
+
+impl Properties {
+    fn of(root: &Element) -> Self {
+        let mut properties = root
+            .child("properties")
+            .into_iter()
+            .flat_map(|p| p.children.iter())
+            .filter_map(|p| Some((p.name.clone(), String::from(p.text()?))))
+            .collect::<HashMap<_, _>>();
+
+        let parent = root.child("parent");
+        let group_id = root
+            .child_text("groupId")
+            .or_else(|| parent.and_then(|p| p.child_text("groupId")));
+        let version = root
+            .child_text("version")
+            .or_else(|| parent.and_then(|p| p.child_text("version")));
+        let project = [
+            ("groupId", group_id),
+            ("artifactId", root.child_text("artifactId")),
+            ("version", version),
+        ];
+        for (key, value) in project.iter() {
+            if let Some(value) = value {
+                properties.insert(format!("project.{}", key), String::from(*value));
+                properties.insert(format!("pom.{}", key), String::from(*value));
+            }
+        }
+
+        Self(properties)
+    }
+
+    fn coordinates(&self, element: &Element, default_group: Option<&str>) -> Option<Coordinates> {
+        let group_id = element.child_text("groupId").or(default_group)?;
+        let artifact = element.child_text("artifactId")?;
+        Some(Coordinates {
+            group_id: self.interpolate(group_id),
+            artifact: self.interpolate(artifact),
+        })
+    }
+
+    fn dependency(&self, element: &Element) -> Option<Dependency> {
+        let text = |name| element.child_text(name).map(|text| self.interpolate(text));
+        Some(Dependency {
+            coordinates: self.coordinates(element, None)?,
+            version: text("version"),
+            scope: text("scope"),
+            optional: text("optional").as_deref() == Some("true"),
+        })
+    }
+
+    /// The value of the property, with the properties that it refers to interpolated.
+    pub(crate) fn get(&self, name: &str) -> Option<String> {
+        self.0.get(name).map(|value| self.interpolate(value))
+    }
+
+    /// Adds the properties of a parent, without overriding the own ones.
+    pub(crate) fn inherit(&mut self, parent: &Properties) {
+        for (name, value) in &parent.0 {
+            self.0.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+    }
+
+    /// Replaces `${name}` with the value of the property, unknown properties are kept as-is.
+    pub(crate) fn interpolate(&self, value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+            match rest.find('}') {
+                Some(end) => {
+                    match self.0.get(&rest[2..end]) {
+                        Some(value) => result.push_str(value),
+                        None => result.push_str(&rest[..=end]),
+                    }
+                    rest = &rest[end + 1..];
+                }
+                None => break,
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Read(path, e) => write!(
+                f,
+                "Could not read the pom {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+            Error::Parse(path, e) => write!(
+                f,
+                "Could not parse the pom {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Read(_, src) => Some(src),
+            Error::Parse(_, src) => Some(src),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    static POM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <project xmlns="http://maven.apache.org/POM/4.0.0">
+          <modelVersion>4.0.0</modelVersion>
+          <parent>
+            <groupId>com.mycorp</groupId>
+            <artifactId>parent</artifactId>
+            <version>1.0.0</version>
+          </parent>
+          <artifactId>app</artifactId>
+          <properties>
+            <spring.group>org.springframework</spring.group>
+          </properties>
+          <repositories>
+            <repository>
+              <id>spring-milestones</id>
+              <url>https://repo.spring.io/milestone</url>
+            </repository>
+          </repositories>
+          <pluginRepositories>
+            <pluginRepository>
+              <id>spring-milestones</id>
+              <url>https://repo.spring.io/milestone</url>
+            </pluginRepository>
+            <pluginRepository>
+              <id>internal</id>
+              <url>https://nexus.mycorp.com/plugins</url>
+            </pluginRepository>
+          </pluginRepositories>
+          <dependencyManagement>
+            <dependencies>
+              <dependency>
+                <groupId>${project.groupId}</groupId>
+                <artifactId>lib</artifactId>
+                <version>${project.version}</version>
+              </dependency>
+            </dependencies>
+          </dependencyManagement>
+          <dependencies>
+            <dependency>
+              <groupId>${spring.group}</groupId>
+              <artifactId>spring-core</artifactId>
+            </dependency>
+            <dependency>
+              <groupId>com.mycorp</groupId>
+              <artifactId>lib</artifactId>
+            </dependency>
+          </dependencies>
+          <build>
+            <plugins>
+              <plugin>
+                <artifactId>maven-compiler-plugin</artifactId>
+              </plugin>
+            </plugins>
+          </build>
+        </project>
+    "#;
+
+    #[test]
+    fn test_parse_dependencies() {
+        let pom = Pom::parse(POM).unwrap();
+        assert_eq!(
+            pom.dependencies,
+            vec![
+                Coordinates::new("org.springframework", "spring-core"),
+                Coordinates::new("com.mycorp", "lib"),
+                Coordinates::new("org.apache.maven.plugins", "maven-compiler-plugin"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_versions() {
+        let pom = Pom::parse(POM).unwrap();
+        let lib = Coordinates::new("com.mycorp", "lib");
+        assert_eq!(pom.version(&lib), Some("1.0.0"));
+        let spring = Coordinates::new("org.springframework", "spring-core");
+        assert_eq!(pom.version(&spring), None);
+    }
+
+    #[test]
+    fn test_parse_details() {
+        let pom = Pom::parse(
+            r#"<project>
+              <artifactId>lib</artifactId>
+              <name>${project.artifactId}</name>
+              <description>
+                A library
+                for things.
+              </description>
+              <url>https://lib.example.com</url>
+              <licenses>
+                <license><name>Apache-2.0</name><url>https://www.apache.org/licenses/LICENSE-2.0</url></license>
+                <license><url>https://opensource.org/licenses/MIT</url></license>
+              </licenses>
+              <scm>
+                <connection>scm:git:https://github.com/example/lib.git</connection>
+                <tag>lib-1.0.0</tag>
+              </scm>
+            </project>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            pom.details,
+            Details {
+                name: Some(String::from("lib")),
+                description: Some(String::from("A library for things.")),
+                url: Some(String::from("https://lib.example.com")),
+                licenses: vec![
+                    String::from("Apache-2.0"),
+                    String::from("https://opensource.org/licenses/MIT")
+                ],
+                scm: Some(String::from("https://github.com/example/lib.git")),
+                scm_tag: Some(String::from("lib-1.0.0")),
+            }
+        );
+        assert_eq!(Pom::parse(POM).unwrap().details, Details::default());
+    }
+
+    #[test]
+    fn test_parse_declared_dependencies() {
+        let pom = Pom::parse(
+            r#"<project>
+              <parent>
+                <groupId>com.mycorp</groupId>
+                <artifactId>parent</artifactId>
+                <version>2.0.0</version>
+              </parent>
+              <artifactId>app</artifactId>
+              <dependencyManagement>
+                <dependencies>
+                  <dependency>
+                    <groupId>com.mycorp</groupId>
+                    <artifactId>bom</artifactId>
+                    <version>${project.version}</version>
+                    <type>pom</type>
+                    <scope>import</scope>
+                  </dependency>
+                </dependencies>
+              </dependencyManagement>
+              <dependencies>
+                <dependency>
+                  <groupId>com.mycorp</groupId>
+                  <artifactId>lib</artifactId>
+                  <version>${lib.version}</version>
+                  <optional>true</optional>
+                </dependency>
+                <dependency>
+                  <groupId>junit</groupId>
+                  <artifactId>junit</artifactId>
+                  <scope>test</scope>
+                </dependency>
+              </dependencies>
+            </project>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            pom.parent,
+            Some((
+                Coordinates::new("com.mycorp", "parent"),
+                String::from("2.0.0")
+            ))
+        );
+        assert_eq!(
+            pom.declared,
+            vec![
+                Dependency {
+                    coordinates: Coordinates::new("com.mycorp", "lib"),
+                    version: Some(String::from("${lib.version}")),
+                    scope: None,
+                    optional: true,
+                },
+                Dependency {
+                    coordinates: Coordinates::new("junit", "junit"),
+                    version: None,
+                    scope: Some(String::from("test")),
+                    optional: false,
+                },
+            ]
+        );
+        assert_eq!(
+            pom.managed,
+            vec![Dependency {
+                coordinates: Coordinates::new("com.mycorp", "bom"),
+                version: Some(String::from("2.0.0")),
+                scope: Some(String::from("import")),
+                optional: false,
+            }]
+        );
+
+        let mut properties = pom.properties;
+        let mut parent = Properties::default();
+        parent.0.insert("lib.version".into(), "1.2.3".into());
+        parent.0.insert("project.version".into(), "3.0.0".into());
+        properties.inherit(&parent);
+        assert_eq!(properties.interpolate("${lib.version}"), "1.2.3");
+        assert_eq!(properties.interpolate("${project.version}"), "2.0.0");
+        assert_eq!(properties.get("lib.version").as_deref(), Some("1.2.3"));
+        assert_eq!(properties.get("unknown"), None);
+    }
+
+    #[test]
+    fn test_artifact_extension() {
+        let pom = Pom::parse(POM).unwrap();
+        assert_eq!(pom.artifact_extension(), Some("jar"));
+        let bom = Pom::parse("<project><packaging>pom</packaging></project>").unwrap();
+        assert_eq!(bom.artifact_extension(), None);
+        let war = Pom::parse("<project><packaging>war</packaging></project>").unwrap();
+        assert_eq!(war.artifact_extension(), Some("war"));
+    }
+
+    #[test]
+    fn test_parse_repositories() {
+        let pom = Pom::parse(POM).unwrap();
+        assert_eq!(
+            pom.repositories,
+            vec![
+                Repository {
+                    id: String::from("spring-milestones"),
+                    url: String::from("https://repo.spring.io/milestone"),
+                },
+                Repository {
+                    id: String::from("internal"),
+                    url: String::from("https://nexus.mycorp.com/plugins"),
+                },
+            ]
+        );
+    }
+
+    #[test_case("https://repo.spring.io/milestone" => true)]
+    #[test_case("http://nexus.mycorp.local/maven" => true)]
+    #[test_case("exec:./resolver" => false)]
+    #[test_case("local:/home/user/.m2/repository" => false)]
+    #[test_case("file:///home/user/.m2/repository" => false)]
+    #[test_case("artifactory:https://repo.mycorp.com/artifactory/libs" => false)]
+    #[test_case("gs://bucket/maven" => false)]
+    #[test_case("jitpack" => false)]
+    fn test_repository_is_http(url: &str) -> bool {
+        let repository = Repository {
+            id: String::from("repo"),
+            url: String::from(url),
+        };
+        repository.is_http()
+    }
+
+    #[test]
+    fn test_unknown_property() {
+        let properties = Properties(HashMap::new());
+        assert_eq!(properties.interpolate("${unknown}.foo"), "${unknown}.foo");
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    aws, gcp,
+    http::ApiClient,
+    resolvers::{Auth, LOCAL_PREFIX},
+    Server,
+};
+use console::style;
+use std::{fmt::Display, path::Path, sync::Arc};
+
+static AZURE_PREFIX: &str = "azure:";
+
+/// The resolver name for the local repository, e.g. `~/.m2/repository`.
+static LOCAL: &str = "local";
+
+/// Short names for public repositories.
+static NAMED: [(&str, &str); 2] = [
+    ("clojars", "https://repo.clojars.org"),
+    ("jitpack", "https://jitpack.io"),
+];
+
+static ARTIFACT_REGISTRY_PREFIX: &str = "artifactregistry://";
+
+static S3_PREFIX: &str = "s3://";
+
+static GCS_PREFIX: &str = "gs://";
+
+/// Hosts of Artifact Registry Maven repositories are `{location}-maven.pkg.dev`.
+static ARTIFACT_REGISTRY_HOST_SUFFIX: &str = "-maven.pkg.dev";
+
+/// Environment variables that can hold an Azure DevOps personal access token, in order of preference.
+static AZURE_TOKEN_VARS: [&str; 2] = ["AZURE_DEVOPS_EXT_PAT", "SYSTEM_ACCESSTOKEN"];
+
+/// Azure DevOps ignores the username for PAT authentication, but it must not be empty.
+static AZURE_USER: &str = "AzureDevOps";
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Error {
+    InvalidAzureFeed(String),
+    InvalidS3Bucket(String),
+    InvalidGcsBucket(String),
+    NoLocalRepository,
+}
+
+/// Expands the server if its URL is a preset for a well-known repository,
+/// other servers are returned unchanged.
+///
+/// Credentials for Google Cloud fetch their tokens with the client.
+pub(crate) fn expand(
+    server: Server,
+    local_repository: Option<&Path>,
+    http: &ApiClient,
+) -> Result<Server, Error> {
+    let Server { id, url, auth } = server;
+    let (url, auth) = if url == LOCAL {
+        let path = local_repository.ok_or(Error::NoLocalRepository)?;
+        (format!("{}{}", LOCAL_PREFIX, path.display()), auth)
+    } else if let Some((_, named)) = NAMED.iter().find(|(name, _)| *name == url) {
+        (String::from(*named), auth)
+    } else if let Some(feed) = url.strip_prefix(AZURE_PREFIX) {
+        azure(feed, auth)?
+    } else if let Some(location) = url.strip_prefix(S3_PREFIX) {
+        s3(location, auth)?
+    } else if let Some(location) = url.strip_prefix(GCS_PREFIX) {
+        gcs(location, auth, http)?
+    } else if let Some(repo) = url.strip_prefix(ARTIFACT_REGISTRY_PREFIX) {
+        artifact_registry(format!("https://{}", repo), auth, http)
+    } else if is_artifact_registry(&url) {
+        artifact_registry(url, auth, http)
+    } else {
+        (url, auth)
+    };
+    Ok(Server { id, url, auth })
+}
+
+type Expanded = (String, Option<Auth>);
+
+/// `azure:{organization}/[{project}/]{feed}` for Azure Artifacts feeds.
+///
+/// Without explicit credentials, a personal access token is read from
+/// `AZURE_DEVOPS_EXT_PAT` or `SYSTEM_ACCESSTOKEN` (as provided by Azure Pipelines).
+fn azure(feed: &str, auth: Option<Auth>) -> Result<Expanded, Error> {
+    let segments = feed.split('/').collect::<Vec<_>>();
+    if segments.iter().any(|s| s.trim().is_empty()) {
+        return Err(Error::InvalidAzureFeed(feed.into()));
+    }
+    let url = match segments[..] {
+        [organization, feed] => format!(
+            "https://pkgs.dev.azure.com/{}/_packaging/{}/maven/v1",
+            organization, feed
+        ),
+        [organization, project, feed] => format!(
+            "https://pkgs.dev.azure.com/{}/{}/_packaging/{}/maven/v1",
+            organization, project, feed
+        ),
+        _ => return Err(Error::InvalidAzureFeed(feed.into())),
+    };
+    let auth = auth.or_else(|| {
+        AZURE_TOKEN_VARS
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|t| !t.is_empty()))
+            .map(|token| Auth::basic(AZURE_USER, token))
+    });
+    Ok((url, auth))
+}
+
+/// `artifactregistry://{location}-maven.pkg.dev/{project}/{repository}` or the https URL
+/// for Google Artifact Registry.
+///
+/// Without explicit credentials, OAuth access tokens from the Application Default Credentials are used.
+fn artifact_registry(url: String, auth: Option<Auth>, http: &ApiClient) -> Expanded {
+    let auth = auth.or_else(|| {
+        let credentials = gcp::ApplicationDefaultCredentials::new(http.clone());
+        Some(Auth::Bearer(Arc::new(credentials)))
+    });
+    (url, auth)
+}
+
+/// `s3://{bucket}/{prefix}` for repositories in S3 buckets.
+///
+/// Without explicit credentials, requests are signed with the credentials from the AWS environment.
+fn s3(location: &str, auth: Option<Auth>) -> Result<Expanded, Error> {
+    let (url, region) =
+        aws::s3_url(location).ok_or_else(|| Error::InvalidS3Bucket(location.into()))?;
+    let auth = auth.or_else(|| Some(Auth::Signed(Arc::new(aws::S3Signer::from_env(region)))));
+    Ok((url, auth))
+}
+
+/// `gs://{bucket}/{prefix}` for repositories in Google Cloud Storage buckets.
+///
+/// Without explicit credentials, OAuth access tokens from the Application Default Credentials are used,
+/// and requests are anonymous if there are none, like for public buckets.
+fn gcs(location: &str, auth: Option<Auth>, http: &ApiClient) -> Result<Expanded, Error> {
+    let location = location.trim_end_matches('/');
+    if location.split('/').next().is_none_or(str::is_empty) {
+        return Err(Error::InvalidGcsBucket(location.into()));
+    }
+    let url = format!("https://storage.googleapis.com/{}", location);
+    let auth = auth.or_else(|| {
+        let credentials = gcp::ApplicationDefaultCredentials::new(http.clone());
+        Some(Auth::Signed(Arc::new(gcp::OrAnonymous(credentials))))
+    });
+    Ok((url, auth))
+}
+
+fn is_artifact_registry(url: &str) -> bool {
+    url.strip_prefix("https://")
+        .and_then(|rest| rest.split('/').next())
+        .is_some_and(|host| host.ends_with(ARTIFACT_REGISTRY_HOST_SUFFIX))
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidAzureFeed(feed) => write!(
+                f,
+                "The Azure Artifacts feed {} is invalid. Please use the form {}",
+                style(feed).red().bold(),
+                style("azure:{organization}/[{project}/]{feed}").cyan(),
+            ),
+            Error::InvalidS3Bucket(location) => write!(
+                f,
+                "The S3 location {} is invalid. Please use the form {}",
+                style(location).red().bold(),
+                style("s3://{bucket}/{prefix}").cyan(),
+            ),
+            Error::InvalidGcsBucket(location) => write!(
+                f,
+                "The Cloud Storage location {} is invalid. Please use the form {}",
+                style(location).red().bold(),
+                style("gs://{bucket}/{prefix}").cyan(),
+            ),
+            Error::NoLocalRepository => write!(
+                f,
+                "Could not find the local repository, please use {} or set {} in the Maven settings.",
+                style("local:/path/to/repository").cyan(),
+                style("<localRepository>").cyan(),
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolvers::ClientOptions;
+    use test_case::test_case;
+
+    fn api() -> ApiClient {
+        ApiClient::new(&ClientOptions::default()).unwrap()
+    }
+
+    fn server(url: &str, auth: Option<(&str, &str)>) -> Server {
+        Server {
+            id: None,
+            url: String::from(url),
+            auth: auth.map(|(u, p)| Auth::basic(u, p)),
+        }
+    }
+
+    fn expand_default(server: Server) -> Result<Server, Error> {
+        expand(server, Some(Path::new("/home/user/.m2/repository")), &api())
+    }
+
+    #[test_case("local" => "local:/home/user/.m2/repository"; "local repository")]
+    #[test_case("clojars" => "https://repo.clojars.org"; "clojars")]
+    #[test_case("jitpack" => "https://jitpack.io"; "jitpack")]
+    #[test_case("gs://bucket/maven/" => "https://storage.googleapis.com/bucket/maven"; "cloud storage")]
+    #[test_case("azure:org/feed" => "https://pkgs.dev.azure.com/org/_packaging/feed/maven/v1"; "organization feed")]
+    #[test_case("azure:org/project/feed" => "https://pkgs.dev.azure.com/org/project/_packaging/feed/maven/v1"; "project feed")]
+    #[test_case("artifactregistry://europe-west1-maven.pkg.dev/project/repo" => "https://europe-west1-maven.pkg.dev/project/repo"; "artifact registry")]
+    #[test_case("https://example.org/maven2" => "https://example.org/maven2"; "not a preset")]
+    fn test_expand_url(url: &str) -> String {
+        expand_default(server(url, Some(("user", "pass"))))
+            .unwrap()
+            .url
+    }
+
+    #[test_case("azure:"; "empty")]
+    #[test_case("azure:org"; "missing feed")]
+    #[test_case("azure:org//feed"; "empty project")]
+    #[test_case("azure:a/b/c/d"; "too many segments")]
+    fn test_invalid_azure_feed(url: &str) {
+        let err = expand_default(server(url, None)).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InvalidAzureFeed(String::from(&url[AZURE_PREFIX.len()..]))
+        );
+    }
+
+    #[test_case("artifactregistry://us-maven.pkg.dev/project/repo"; "artifact registry scheme")]
+    #[test_case("https://us-maven.pkg.dev/project/repo"; "artifact registry url")]
+    fn test_artifact_registry_uses_oauth(url: &str) {
+        let expanded = expand_default(server(url, None)).unwrap();
+        assert!(matches!(expanded.auth, Some(Auth::Bearer(_))));
+    }
+
+    #[test]
+    fn test_artifact_registry_keeps_explicit_auth() {
+        let expanded = expand_default(server(
+            "https://us-maven.pkg.dev/project/repo",
+            Some(("oauth2accesstoken", "token")),
+        ))
+        .unwrap();
+        assert_eq!(
+            expanded.auth,
+            Some(Auth::basic("oauth2accesstoken", "token"))
+        );
+    }
+
+    #[test_case("s3://bucket/maven/releases"; "with prefix")]
+    #[test_case("s3://bucket"; "without prefix")]
+    fn test_s3_signs_requests(url: &str) {
+        let expanded = expand_default(server(url, None)).unwrap();
+        assert!(expanded.url.contains("bucket"));
+        assert!(matches!(expanded.auth, Some(Auth::Signed(_))));
+    }
+
+    #[test]
+    fn test_gcs_uses_oauth_or_anonymous() {
+        let expanded = expand_default(server("gs://bucket", None)).unwrap();
+        assert_eq!(expanded.url, "https://storage.googleapis.com/bucket");
+        assert!(matches!(expanded.auth, Some(Auth::Signed(_))));
+    }
+
+    #[test]
+    fn test_invalid_gcs_bucket() {
+        let err = expand_default(server("gs:///prefix", None)).unwrap_err();
+        assert_eq!(err, Error::InvalidGcsBucket(String::from("/prefix")));
+    }
+
+    #[test]
+    fn test_invalid_s3_bucket() {
+        let err = expand_default(server("s3:///prefix", None)).unwrap_err();
+        assert_eq!(err, Error::InvalidS3Bucket(String::from("/prefix")));
+    }
+
+    #[test]
+    fn test_no_local_repository() {
+        let err = expand(server("local", None), None, &api()).unwrap_err();
+        assert_eq!(err, Error::NoLocalRepository);
+    }
+
+    #[test]
+    fn test_azure_keeps_explicit_auth() {
+        let expanded = expand_default(server("azure:org/feed", Some(("alice", "s3cure")))).unwrap();
+        assert_eq!(expanded.auth, Some(Auth::basic("alice", "s3cure")));
+    }
+}
@@ -0,0 +1,27 @@
+use crate::Coordinates;
+use std::{fmt, time::Duration};
+use url::Url;
+
+/// Observes the checks while they run, e.g. for a progress bar.
+///
+/// All methods do nothing by default. The requests and retries are only reported by resolvers
+/// that were given the observer with [`crate::UrlResolver::with_progress`].
+pub trait Progress: Send + Sync {
+    /// The check of the coordinates started.
+    fn on_check_started(&self, _coordinates: &Coordinates) {}
+
+    /// A request is sent, once for every attempt.
+    fn on_request(&self, _url: &Url) {}
+
+    /// The request failed with a transient error or was rate limited, and is sent again after the delay.
+    fn on_retry(&self, _url: &Url, _attempt: u32, _delay: Duration) {}
+
+    /// The check of the coordinates finished, successfully or not.
+    fn on_check_finished(&self, _coordinates: &Coordinates) {}
+}
+
+impl fmt::Debug for dyn Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Progress")
+    }
+}
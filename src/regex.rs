@@ -0,0 +1,77 @@
+use std::fmt::Display;
+
+/// A regular expression for the short strings of versions, like `^2\.17\.0$` or `-(alpha|beta)\d*$`.
+///
+/// Uses the syntax of the regex crate, which matches in linear time, also for patterns from the command line.
+/// A pattern matches anywhere in the text unless it is anchored.
+/// Two expressions are equal if they have the same pattern.
+#[derive(Debug, Clone)]
+pub(crate) struct Regex(::regex::Regex);
+
+#[derive(Debug)]
+pub struct Error(::regex::Error);
+
+impl Regex {
+    #[cfg(feature = "runtime")]
+    pub(crate) fn new(pattern: &str) -> Result<Self, Error> {
+        ::regex::Regex::new(pattern).map(Self).map_err(Error)
+    }
+
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl PartialEq for Regex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for Regex {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(r"^2\.17\.0$", "2.17.0" => true; "exact version")]
+    #[test_case(r"^2\.17\.0$", "2.17.01" => false; "anchored end")]
+    #[test_case(r"^2\.17\.0$", "12.17.0" => false; "anchored start")]
+    #[test_case(r"2\.17\.0", "12.17.01" => true; "unanchored")]
+    fn test_is_match(pattern: &str, text: &str) -> bool {
+        Regex::new(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn test_nested_repetition_in_linear_time() {
+        let text = format!("{}b", "a".repeat(10_000));
+        assert!(!Regex::new(r"^(a*)*$").unwrap().is_match(&text));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = Regex::new(r"(1.0").unwrap_err();
+        assert!(error.to_string().contains("unclosed group"));
+    }
+
+    #[test]
+    fn test_eq_by_pattern() {
+        assert_eq!(
+            Regex::new(r"-rc\d+$").unwrap(),
+            Regex::new(r"-rc\d+$").unwrap()
+        );
+        assert_ne!(
+            Regex::new(r"-rc\d+$").unwrap(),
+            Regex::new(r"-RC\d+$").unwrap()
+        );
+    }
+}
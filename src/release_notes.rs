@@ -0,0 +1,156 @@
+use crate::pom::Details;
+use url::Url;
+
+/// The links to the release of a version and to its changes, on GitHub or GitLab.
+///
+/// The links are guessed from the source repository or project URL of the pom, and from the tag
+/// that the pom names for its version. Without a tag, or with `HEAD`, the tag is assumed to be `v{version}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ReleaseNotes {
+    /// The release page of the tag of the version.
+    pub(crate) release: String,
+    /// The comparison of the tags of the current version and the version.
+    pub(crate) compare: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Forge {
+    GitHub,
+    GitLab,
+}
+
+impl ReleaseNotes {
+    pub(crate) fn guess(details: &Details, version: &str, current: Option<&str>) -> Option<Self> {
+        let (forge, base) = details
+            .scm
+            .iter()
+            .chain(details.url.iter())
+            .find_map(|url| repository(url))?;
+        let (prefix, suffix) = details
+            .scm_tag
+            .as_deref()
+            .and_then(|tag| tag.split_once(version))
+            .unwrap_or(("v", ""));
+        let tag = |version: &str| format!("{}{}{}", prefix, version, suffix);
+        let (release, compare) = match forge {
+            Forge::GitHub => ("releases/tag", "compare"),
+            Forge::GitLab => ("-/releases", "-/compare"),
+        };
+        Some(Self {
+            release: format!("{}/{}/{}", base, release, tag(version)),
+            compare: current
+                .map(|current| format!("{}/{}/{}...{}", base, compare, tag(current), tag(version))),
+        })
+    }
+}
+
+/// The forge and the web URL of a repository, e.g. `https://github.com/owner/repo` for `git@github.com:owner/repo.git`.
+fn repository(url: &str) -> Option<(Forge, String)> {
+    // scp-like URLs have no scheme, e.g. `git@github.com:owner/repo.git`
+    let url = match url.split_once("@") {
+        Some((user, rest)) if !user.contains("://") => match rest.split_once(':') {
+            Some((host, path)) => format!("https://{}/{}", host, path),
+            None => return None,
+        },
+        _ => String::from(url),
+    };
+    let url = Url::parse(&url).ok()?;
+    let host = url.host_str()?;
+    let forge = if host.contains("github") {
+        Forge::GitHub
+    } else if host.contains("gitlab") {
+        Forge::GitLab
+    } else {
+        return None;
+    };
+    let segments = url.path_segments()?.filter(|s| !s.is_empty());
+    let segments = match forge {
+        // anything after the owner and the repository is a path in it, e.g. `tree/main`
+        Forge::GitHub => segments.take(2).collect::<Vec<_>>(),
+        // groups can be nested, and paths in the repository start with `-`
+        Forge::GitLab => segments.take_while(|s| *s != "-").collect(),
+    };
+    if segments.len() < 2 {
+        return None;
+    }
+    let path = segments.join("/");
+    let path = path.strip_suffix(".git").unwrap_or(&path);
+    Some((forge, format!("https://{}/{}", host, path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("https://github.com/owner/repo" => Some((Forge::GitHub, String::from("https://github.com/owner/repo"))); "github")]
+    #[test_case("https://github.com/owner/repo/tree/main/lib" => Some((Forge::GitHub, String::from("https://github.com/owner/repo"))); "github path")]
+    #[test_case("git://github.com/owner/repo.git" => Some((Forge::GitHub, String::from("https://github.com/owner/repo"))); "git protocol")]
+    #[test_case("git@github.com:owner/repo.git" => Some((Forge::GitHub, String::from("https://github.com/owner/repo"))); "scp like")]
+    #[test_case("ssh://git@github.com/owner/repo.git" => Some((Forge::GitHub, String::from("https://github.com/owner/repo"))); "ssh")]
+    #[test_case("https://gitlab.com/group/sub/repo/-/tree/main" => Some((Forge::GitLab, String::from("https://gitlab.com/group/sub/repo"))); "gitlab subgroup")]
+    #[test_case("https://github.com/owner" => None; "no repository")]
+    #[test_case("https://example.com/owner/repo" => None; "unknown forge")]
+    fn test_repository(url: &str) -> Option<(Forge, String)> {
+        repository(url)
+    }
+
+    fn details(scm: &str, tag: Option<&str>) -> Details {
+        Details {
+            scm: Some(String::from(scm)),
+            scm_tag: tag.map(String::from),
+            ..Details::default()
+        }
+    }
+
+    #[test]
+    fn test_github_release_notes() {
+        let notes = ReleaseNotes::guess(
+            &details("https://github.com/owner/repo", Some("HEAD")),
+            "1.2.3",
+            Some("1.2.2"),
+        );
+        assert_eq!(
+            notes,
+            Some(ReleaseNotes {
+                release: String::from("https://github.com/owner/repo/releases/tag/v1.2.3"),
+                compare: Some(String::from(
+                    "https://github.com/owner/repo/compare/v1.2.2...v1.2.3"
+                )),
+            })
+        );
+    }
+
+    #[test]
+    fn test_release_notes_with_tag() {
+        let notes = ReleaseNotes::guess(
+            &details(
+                "https://gitlab.com/group/repo.git",
+                Some("repo-parent-1.2.3"),
+            ),
+            "1.2.3",
+            None,
+        );
+        assert_eq!(
+            notes,
+            Some(ReleaseNotes {
+                release: String::from("https://gitlab.com/group/repo/-/releases/repo-parent-1.2.3"),
+                compare: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_release_notes_from_project_url() {
+        let details = Details {
+            scm: Some(String::from("https://svn.example.com/repo")),
+            url: Some(String::from("https://github.com/owner/repo")),
+            ..Details::default()
+        };
+        let notes = ReleaseNotes::guess(&details, "1.0", None).unwrap();
+        assert_eq!(
+            notes.release,
+            "https://github.com/owner/repo/releases/tag/v1.0"
+        );
+    }
+}
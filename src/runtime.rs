@@ -0,0 +1,112 @@
+//! Blocking IO and timers for the code that runs with any executor, e.g. the resolvers and the clients.
+//!
+//! They use the tokio runtime if there is one. Otherwise, like with [`crate::check_latest_blocking`]
+//! or without the `runtime` feature, IO blocks the calling thread, while the timers do not.
+use std::{fs, io, path::PathBuf, time::Duration};
+use url::Url;
+
+/// Runs blocking IO on the blocking threads of the tokio runtime, or on the calling thread if there is none.
+///
+/// The error is only returned when the runtime shuts down before the IO is done, panics are resumed.
+pub(crate) async fn unblock<T, F>(f: F) -> io::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    #[cfg(feature = "runtime")]
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        return match handle.spawn_blocking(f).await {
+            Ok(result) => Ok(result),
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(io::Error::from(e)),
+        };
+    }
+    Ok(f())
+}
+
+/// Reads the whole file with [`unblock`].
+pub(crate) async fn read(path: PathBuf) -> io::Result<Vec<u8>> {
+    unblock(move || fs::read(path)).await?
+}
+
+/// Reads the whole file as UTF-8 with [`unblock`].
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+pub(crate) async fn read_to_string(path: PathBuf) -> io::Result<String> {
+    unblock(move || fs::read_to_string(path)).await?
+}
+
+#[cfg(any(feature = "runtime", feature = "blocking"))]
+pub(crate) async fn metadata(path: PathBuf) -> io::Result<fs::Metadata> {
+    unblock(move || fs::metadata(path)).await?
+}
+
+/// The names of the directories in the directory, in the order of the file system.
+pub(crate) async fn subdirectories(path: PathBuf) -> io::Result<Vec<String>> {
+    unblock(move || {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                if let Some(name) = entry.file_name().to_str() {
+                    names.push(String::from(name));
+                }
+            }
+        }
+        Ok(names)
+    })
+    .await?
+}
+
+/// The path of a `file:` URL, there is none on `wasm32-unknown-unknown`, which has no file system.
+pub(crate) fn file_path(url: &Url) -> Option<PathBuf> {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    return url.to_file_path().ok();
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        let _ = url;
+        None
+    }
+}
+
+/// Waits before a retry, with the timer of the tokio runtime, or with a timer that works with any executor if there is none.
+///
+/// The thread of the executor is not blocked, and `wasm32-unknown-unknown` waits with the timers of the browser.
+pub(crate) async fn sleep(delay: Duration) {
+    #[cfg(feature = "runtime")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return tokio::time::sleep(delay).await;
+    }
+    futures_timer::Delay::new(delay).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+    use std::time::Instant;
+
+    #[test]
+    fn test_sleep_without_runtime() {
+        let start = Instant::now();
+        let mut delay = Box::pin(sleep(Duration::from_millis(20)));
+        // the first poll returns instead of blocking the thread of the executor
+        assert_eq!(delay.as_mut().now_or_never(), None);
+        futures_executor::block_on(delay);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_unblock_without_runtime() {
+        let thread = std::thread::current().id();
+        let result = unblock(move || std::thread::current().id()).now_or_never();
+        assert_eq!(result.unwrap().unwrap(), thread);
+    }
+
+    #[cfg(feature = "runtime")]
+    #[tokio::test]
+    async fn test_unblock_with_runtime() {
+        let thread = std::thread::current().id();
+        let result = unblock(move || std::thread::current().id()).await;
+        assert_ne!(result.unwrap(), thread);
+    }
+}
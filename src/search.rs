@@ -0,0 +1,333 @@
+use crate::Coordinates;
+use serde::Deserialize;
+use url::Url;
+
+/// The hosts of Maven Central, whose search is at [`CENTRAL_SEARCH`].
+static CENTRAL_HOSTS: [&str; 2] = ["repo.maven.apache.org", "repo1.maven.org"];
+
+static CENTRAL_SEARCH: &str = "https://search.maven.org/solrsearch/select";
+
+/// What to search for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// A part of the artifact or group, or the name of a class, simple or fully qualified.
+    pub(crate) name: String,
+    pub(crate) class: bool,
+    /// How many results to ask for.
+    pub(crate) limit: usize,
+}
+
+impl Query {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the name is the one of a class instead of a part of the coordinates.
+    pub fn is_class(&self) -> bool {
+        self.class
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+/// The most suggestions for coordinates that could not be found.
+#[cfg(feature = "runtime")]
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The response of the Maven Central search.
+#[derive(Debug, Deserialize)]
+struct CentralResponse {
+    response: CentralResults,
+}
+
+#[derive(Debug, Deserialize)]
+struct CentralResults {
+    #[serde(default)]
+    docs: Vec<CentralDoc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CentralDoc {
+    g: String,
+    a: String,
+}
+
+/// The response of Artifactory's `/api/search/artifact` and `/api/search/archive`.
+#[derive(Debug, Deserialize)]
+struct ArtifactoryResponse {
+    #[serde(default)]
+    results: Vec<ArtifactoryResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactoryResult {
+    /// The storage URI of a found file.
+    uri: Option<String>,
+    /// The URIs of the archives that contain a found class.
+    #[serde(default)]
+    archive_uris: Vec<String>,
+}
+
+/// Whether the server is Maven Central, which has its own search.
+pub(crate) fn is_central(server: &Url) -> bool {
+    server
+        .host_str()
+        .is_some_and(|host| CENTRAL_HOSTS.contains(&host))
+}
+
+/// The URL of the Maven Central search for the query.
+pub(crate) fn central_url(query: &Query) -> Url {
+    let q = match (query.class, query.name.contains('.')) {
+        (true, true) => format!("fc:\"{}\"", query.name),
+        (true, false) => format!("c:\"{}\"", query.name),
+        (false, _) => query.name.clone(),
+    };
+    let mut url = Url::parse(CENTRAL_SEARCH).unwrap(); // it is a valid URL
+    url.query_pairs_mut()
+        .append_pair("q", &q)
+        .append_pair("rows", &query.limit.to_string())
+        .append_pair("wt", "json");
+    url
+}
+
+/// The URL of Artifactory's search for the query, restricted to the repository.
+///
+/// Artifacts are found by the name of their pom, and classes by the name of their class file.
+pub(crate) fn artifactory_url(server: &Url, repo: &str, query: &Query) -> Url {
+    let mut url = server.clone();
+    let (api, name) = if query.class {
+        let class = query.name.rsplit('.').next().unwrap_or(&query.name);
+        ("archive", format!("{}.class", class))
+    } else {
+        ("artifact", format!("*{}*.pom", query.name))
+    };
+    url.path_segments_mut()
+        .unwrap() // resolvers are checked during construction
+        .pop_if_empty()
+        .pop()
+        .extend(&["api", "search", api]);
+    url.query_pairs_mut()
+        .clear()
+        .append_pair("name", &name)
+        .append_pair("repos", repo);
+    url
+}
+
+/// Parses the coordinates from a Maven Central search response, in the order they were found.
+pub(crate) fn parse_central(input: &str) -> Result<Vec<Coordinates>, serde_json::Error> {
+    let response = serde_json::from_str::<CentralResponse>(input)?;
+    let found = response.response.docs.into_iter().map(|doc| Coordinates {
+        group_id: doc.g,
+        artifact: doc.a,
+    });
+    Ok(distinct(found))
+}
+
+/// Parses the coordinates from the paths of the files that an Artifactory search found in the repository.
+pub(crate) fn parse_artifactory(
+    input: &str,
+    repo: &str,
+) -> Result<Vec<Coordinates>, serde_json::Error> {
+    let response = serde_json::from_str::<ArtifactoryResponse>(input)?;
+    let found = response
+        .results
+        .into_iter()
+        .flat_map(|result| result.uri.into_iter().chain(result.archive_uris))
+        .filter_map(|uri| coordinates_in(&uri, repo));
+    Ok(distinct(found))
+}
+
+/// The coordinates of a file like `…/{repo}/com/foo/bar/1.0.0/bar-1.0.0.pom`.
+fn coordinates_in(uri: &str, repo: &str) -> Option<Coordinates> {
+    let url = Url::parse(uri).ok()?;
+    let segments = url.path_segments()?.collect::<Vec<_>>();
+    let start = segments.iter().position(|segment| *segment == repo)? + 1;
+    // the last two segments are the version and the file
+    let path = segments.get(start..segments.len().checked_sub(2)?)?;
+    let (artifact, group) = path.split_last()?;
+    if group.is_empty() {
+        return None;
+    }
+    Some(Coordinates {
+        group_id: group.join("."),
+        artifact: String::from(*artifact),
+    })
+}
+
+/// The candidates that look like a typo of the missing coordinates, the closest first.
+///
+/// Candidates are close if they have the same artifact in any group,
+/// or if both their group and artifact are a few edits away from the missing ones.
+/// A group is one edit away from its parent or child groups, e.g. `io.projectreactor` from `io.projectreactor.netty`.
+#[cfg(feature = "runtime")]
+pub(crate) fn close_matches(
+    missing: &Coordinates,
+    candidates: impl IntoIterator<Item = Coordinates>,
+) -> Vec<Coordinates> {
+    let mut close = candidates
+        .into_iter()
+        .filter(|candidate| candidate != missing)
+        .filter_map(|candidate| {
+            let artifact = distance(&missing.artifact, &candidate.artifact);
+            let group = group_distance(&missing.group_id, &candidate.group_id);
+            let is_close = artifact == 0
+                || (artifact <= max_edits(&missing.artifact)
+                    && group <= max_edits(&missing.group_id));
+            is_close.then_some((artifact + group, candidate))
+        })
+        .collect::<Vec<_>>();
+    close.sort_by_key(|(distance, _)| *distance);
+    let mut close = distinct(close.into_iter().map(|(_, candidate)| candidate));
+    close.truncate(MAX_SUGGESTIONS);
+    close
+}
+
+/// How many edits a name can be away to still be a typo of it.
+#[cfg(feature = "runtime")]
+fn max_edits(name: &str) -> usize {
+    (name.chars().count() / 4).max(1)
+}
+
+#[cfg(feature = "runtime")]
+fn group_distance(lhs: &str, rhs: &str) -> usize {
+    let is_parent = |parent: &str, child: &str| {
+        child
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('.'))
+    };
+    if is_parent(lhs, rhs) || is_parent(rhs, lhs) {
+        1
+    } else {
+        distance(lhs, rhs)
+    }
+}
+
+/// The Levenshtein distance, the fewest insertions, deletions, and substitutions that turn one into the other.
+#[cfg(feature = "runtime")]
+fn distance(lhs: &str, rhs: &str) -> usize {
+    let rhs = rhs.chars().collect::<Vec<_>>();
+    let mut previous = (0..=rhs.len()).collect::<Vec<_>>();
+    for (i, l) in lhs.chars().enumerate() {
+        let mut current = vec![i + 1; rhs.len() + 1];
+        for (j, r) in rhs.iter().enumerate() {
+            let substitution = previous[j] + usize::from(l != *r);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[rhs.len()]
+}
+
+fn distinct(found: impl Iterator<Item = Coordinates>) -> Vec<Coordinates> {
+    found.fold(Vec::new(), |mut distinct, coordinates| {
+        if !distinct.contains(&coordinates) {
+            distinct.push(coordinates);
+        }
+        distinct
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn query(name: &str, class: bool) -> Query {
+        Query {
+            name: String::from(name),
+            class,
+            limit: 20,
+        }
+    }
+
+    #[test_case("guava", false => "q=guava&rows=20&wt=json"; "artifact")]
+    #[test_case("ImmutableList", true => "q=c%3A%22ImmutableList%22&rows=20&wt=json"; "class")]
+    #[test_case("com.google.common.collect.ImmutableList", true => "q=fc%3A%22com.google.common.collect.ImmutableList%22&rows=20&wt=json"; "qualified class")]
+    fn test_central_url(name: &str, class: bool) -> String {
+        String::from(central_url(&query(name, class)).query().unwrap())
+    }
+
+    #[test_case("guava", false => "https://repo.example.com/artifactory/api/search/artifact?name=*guava*.pom&repos=libs-release"; "artifact")]
+    #[test_case("com.google.common.collect.ImmutableList", true => "https://repo.example.com/artifactory/api/search/archive?name=ImmutableList.class&repos=libs-release"; "class")]
+    fn test_artifactory_url(name: &str, class: bool) -> String {
+        let server = Url::parse("https://repo.example.com/artifactory/libs-release/").unwrap();
+        artifactory_url(&server, "libs-release", &query(name, class))
+            .as_str()
+            .replace("%2A", "*")
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case("reactor-netty", "reactor-netty" => 0; "same")]
+    #[test_case("reactor-nety", "reactor-netty" => 1; "insertion")]
+    #[test_case("reactor-netty", "raector-netty" => 2; "swap")]
+    #[test_case("", "netty" => 5; "empty")]
+    fn test_distance(lhs: &str, rhs: &str) -> usize {
+        distance(lhs, rhs)
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_close_matches() {
+        let missing = Coordinates::new("io.projectreactor", "reactor-netty");
+        let candidates = vec![
+            Coordinates::new("io.projectreactor", "reactor-core"),
+            Coordinates::new("io.projectreactor", "reactor-netty"),
+            Coordinates::new("com.example.fork", "reactor-netty"),
+            Coordinates::new("io.projectreactor.netty", "reactor-netty-http"),
+            Coordinates::new("io.projectreactor.netty", "reactor-netty"),
+            Coordinates::new("io.projectreactor", "reactor-nettyy"),
+        ];
+        assert_eq!(
+            close_matches(&missing, candidates),
+            vec![
+                Coordinates::new("io.projectreactor.netty", "reactor-netty"),
+                Coordinates::new("io.projectreactor", "reactor-nettyy"),
+                Coordinates::new("com.example.fork", "reactor-netty"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_central() {
+        let input = r#"{
+          "responseHeader": { "status": 0 },
+          "response": {
+            "numFound": 3,
+            "docs": [
+              { "id": "com.google.guava:guava", "g": "com.google.guava", "a": "guava", "latestVersion": "33.0.0-jre" },
+              { "id": "com.google.guava:guava:32.0.0-jre", "g": "com.google.guava", "a": "guava", "v": "32.0.0-jre" },
+              { "id": "com.google.guava:guava-testlib", "g": "com.google.guava", "a": "guava-testlib" }
+            ]
+          }
+        }"#;
+        assert_eq!(
+            parse_central(input).unwrap(),
+            vec![
+                Coordinates::new("com.google.guava", "guava"),
+                Coordinates::new("com.google.guava", "guava-testlib"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_artifactory() {
+        let input = r#"{
+          "results": [
+            { "uri": "https://repo.example.com/artifactory/api/storage/libs-release/com/foo/bar/1.0.0/bar-1.0.0.pom" },
+            { "uri": "https://repo.example.com/artifactory/api/storage/libs-release/com/foo/bar/1.1.0/bar-1.1.0.pom" },
+            { "entry": "com/foo/Baz.class", "archiveUris": [ "https://repo.example.com/artifactory/libs-release/com/foo/baz/2.0/baz-2.0.jar" ] },
+            { "uri": "https://repo.example.com/artifactory/api/storage/libs-release/bar-1.0.0.pom" }
+          ]
+        }"#;
+        assert_eq!(
+            parse_artifactory(input, "libs-release").unwrap(),
+            vec![
+                Coordinates::new("com.foo", "bar"),
+                Coordinates::new("com.foo", "baz"),
+            ]
+        );
+    }
+}
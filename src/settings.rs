@@ -0,0 +1,536 @@
+use crate::{
+    resolvers::{Auth, Proxy},
+    xml::Element,
+    Server,
+};
+use console::style;
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// The parts of a Maven `settings.xml` that are relevant for resolving versions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Settings {
+    servers: Vec<ServerCredentials>,
+    mirrors: Vec<Mirror>,
+    proxies: Vec<ProxySettings>,
+    local_repository: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ServerCredentials {
+    id: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Mirror {
+    id: String,
+    url: String,
+    mirror_of: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProxySettings {
+    active: bool,
+    protocol: String,
+    host: String,
+    port: u16,
+    username: Option<String>,
+    password: Option<String>,
+    non_proxy_hosts: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, xmlparser::Error),
+}
+
+/// The repository id Maven uses for Maven Central.
+static CENTRAL_ID: &str = "central";
+
+impl Settings {
+    pub(crate) fn read(path: &Path) -> Result<Self, Error> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| Error::Read(path.to_path_buf(), e))?;
+        let mut settings =
+            Self::parse(&content).map_err(|e| Error::Parse(path.to_path_buf(), e))?;
+        for name in settings.remove_encrypted_passwords() {
+            eprintln!(
+                "{} The password of {} in the Maven settings is encrypted, which is not supported, so its credentials are not used. Please use {} with the plain password in an environment variable instead.",
+                style("Warning:").yellow().bold(),
+                style(name).cyan(),
+                style("${env.NAME}").cyan(),
+            );
+        }
+        Ok(settings)
+    }
+
+    fn parse(content: &str) -> Result<Self, xmlparser::Error> {
+        let root = Element::parse(content)?;
+
+        let servers = root
+            .find(&["servers"])
+            .into_iter()
+            .flat_map(|s| s.children("server"))
+            .filter_map(|server| {
+                Some(ServerCredentials {
+                    id: interpolate(server.child_text("id")?),
+                    username: server.child_text("username").map(interpolate),
+                    password: server.child_text("password").map(interpolate),
+                })
+            })
+            .collect();
+
+        let mirrors = root
+            .find(&["mirrors"])
+            .into_iter()
+            .flat_map(|m| m.children("mirror"))
+            .filter_map(|mirror| {
+                Some(Mirror {
+                    id: interpolate(mirror.child_text("id")?),
+                    url: interpolate(mirror.child_text("url")?),
+                    mirror_of: interpolate(mirror.child_text("mirrorOf")?),
+                })
+            })
+            .collect();
+
+        let proxies = root
+            .find(&["proxies"])
+            .into_iter()
+            .flat_map(|p| p.children("proxy"))
+            .filter_map(|proxy| {
+                Some(ProxySettings {
+                    active: proxy.child_text("active").is_none_or(|a| a == "true"),
+                    protocol: proxy
+                        .child_text("protocol")
+                        .map_or_else(|| String::from("http"), interpolate),
+                    host: interpolate(proxy.child_text("host")?),
+                    port: proxy
+                        .child_text("port")
+                        .map_or(Some(8080), |p| interpolate(p).parse().ok())?,
+                    username: proxy.child_text("username").map(interpolate),
+                    password: proxy.child_text("password").map(interpolate),
+                    non_proxy_hosts: proxy.child_text("nonProxyHosts").map(interpolate),
+                })
+            })
+            .collect();
+
+        let local_repository = root.child_text("localRepository").map(interpolate);
+
+        Ok(Self {
+            servers,
+            mirrors,
+            proxies,
+            local_repository,
+        })
+    }
+
+    /// Removes the credentials whose password is encrypted with the master password of `settings-security.xml`,
+    /// instead of sending the encrypted value, and returns the servers and proxies they belong to.
+    fn remove_encrypted_passwords(&mut self) -> Vec<String> {
+        let mut removed = Vec::new();
+        for server in &mut self.servers {
+            if server.password.as_deref().is_some_and(is_encrypted) {
+                server.username = None;
+                server.password = None;
+                removed.push(format!("the server {}", server.id));
+            }
+        }
+        for proxy in &mut self.proxies {
+            if proxy.password.as_deref().is_some_and(is_encrypted) {
+                proxy.username = None;
+                proxy.password = None;
+                removed.push(format!("the proxy {}", proxy.host));
+            }
+        }
+        removed
+    }
+
+    /// The local repository from `<localRepository>`, or `~/.m2/repository`.
+    pub(crate) fn local_repository(&self) -> Option<PathBuf> {
+        match &self.local_repository {
+            Some(path) => match (path.strip_prefix("${user.home}"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => Some(home.join(rest.trim_start_matches(['/', '\\']))),
+                _ => Some(PathBuf::from(path)),
+            },
+            None => {
+                let mut path = dirs::home_dir()?;
+                path.push(".m2");
+                path.push("repository");
+                Some(path)
+            }
+        }
+    }
+
+    /// Returns the credentials of the `<server>` with the given id.
+    pub(crate) fn credentials(&self, id: &str) -> Option<Auth> {
+        let server = self.servers.iter().find(|s| s.id == id)?;
+        Some(Auth::Basic(
+            server.username.clone()?,
+            server.password.clone().unwrap_or_default(),
+        ))
+    }
+
+    /// Replaces the server with a matching mirror, if there is one,
+    /// and adds the credentials of the `<server>` with the same id.
+    ///
+    /// Maven Central is also identified by its URL, since servers given on the command line do not have an id.
+    /// Existing credentials of the server are kept.
+    pub(crate) fn apply(&self, server: Server, central: &str) -> Server {
+        let id = server.id.as_deref().or_else(|| {
+            (server.url.trim_end_matches('/') == central.trim_end_matches('/'))
+                .then_some(CENTRAL_ID)
+        });
+        let mirror = Url::parse(&server.url)
+            .ok()
+            .and_then(|url| self.mirrors.iter().find(|m| m.matches(id, &url)));
+        match mirror {
+            Some(mirror) => Server {
+                id: Some(mirror.id.clone()),
+                url: mirror.url.clone(),
+                auth: server.auth.or_else(|| self.credentials(&mirror.id)),
+            },
+            None => {
+                let auth = match server.auth {
+                    Some(auth) => Some(auth),
+                    None => server.id.as_deref().and_then(|id| self.credentials(id)),
+                };
+                Server { auth, ..server }
+            }
+        }
+    }
+
+    /// Returns the first active proxy.
+    pub(crate) fn proxy(&self) -> Option<Proxy> {
+        self.proxies.iter().find(|p| p.active).and_then(|p| {
+            let url = Url::parse(&format!("{}://{}:{}", p.protocol, p.host, p.port)).ok()?;
+            let auth = p
+                .username
+                .clone()
+                .map(|user| (user, p.password.clone().unwrap_or_default()));
+            let non_proxy_hosts = p
+                .non_proxy_hosts
+                .iter()
+                .flat_map(|hosts| hosts.split(['|', ',']))
+                .map(str::trim)
+                .filter(|host| !host.is_empty())
+                .map(String::from)
+                .collect();
+            Some(Proxy {
+                url,
+                auth,
+                non_proxy_hosts,
+            })
+        })
+    }
+}
+
+impl Mirror {
+    /// Implements the `mirrorOf` syntax: `*`, `external:*`, `id`, `!id`, separated by commas.
+    fn matches(&self, id: Option<&str>, url: &Url) -> bool {
+        let mut matched = false;
+        for pattern in self.mirror_of.split(',').map(str::trim) {
+            if let Some(excluded) = pattern.strip_prefix('!') {
+                if Some(excluded) == id {
+                    return false;
+                }
+            } else if pattern == "*"
+                || (pattern == "external:*" && !is_local(url))
+                || Some(pattern) == id
+            {
+                matched = true;
+            }
+        }
+        matched
+    }
+}
+
+fn is_local(url: &Url) -> bool {
+    url.scheme() == "file"
+        || matches!(
+            url.host_str(),
+            Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+        )
+}
+
+/// Whether the value is encrypted like `{COQLCE6DU6GtcS5P=}`, which can also be surrounded by a comment.
+fn is_encrypted(value: &str) -> bool {
+    value.match_indices('{').any(|(start, _)| {
+        !value[..start].ends_with('$')
+            && value[start + 1..]
+                .split_once('}')
+                .is_some_and(|(encrypted, _)| {
+                    !encrypted.is_empty()
+                        && encrypted
+                            .bytes()
+                            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='))
+                })
+    })
+}
+
+/// Replaces `${env.NAME}` with the value of the environment variable `NAME`.
+fn interpolate(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${env.") {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest.find('}') {
+            Some(end) => {
+                let name = &rest["${env.".len()..end];
+                match std::env::var(name) {
+                    Ok(value) => result.push_str(&value),
+                    Err(_) => result.push_str(&rest[..=end]),
+                }
+                rest = &rest[end + 1..];
+            }
+            None => break,
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+pub(crate) fn default_path() -> Option<PathBuf> {
+    let mut path = dirs::home_dir()?;
+    path.push(".m2");
+    path.push("settings.xml");
+    Some(path)
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Read(path, e) => write!(
+                f,
+                "Could not read the Maven settings {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+            Error::Parse(path, e) => write!(
+                f,
+                "Could not parse the Maven settings {}: {}",
+                style(path.display()).red().bold(),
+                e
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Read(_, src) => Some(src),
+            Error::Parse(_, src) => Some(src),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    static CENTRAL: &str = "https://repo.maven.apache.org/maven2";
+
+    static SETTINGS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <settings>
+          <servers>
+            <server>
+              <id>nexus</id>
+              <username>alice</username>
+              <password>s3cure</password>
+            </server>
+          </servers>
+          <mirrors>
+            <mirror>
+              <id>nexus</id>
+              <mirrorOf>central</mirrorOf>
+              <url>https://nexus.mycorp.com/repository/maven-public</url>
+            </mirror>
+          </mirrors>
+          <proxies>
+            <proxy>
+              <active>false</active>
+              <host>inactive.mycorp.com</host>
+            </proxy>
+            <proxy>
+              <protocol>http</protocol>
+              <host>proxy.mycorp.com</host>
+              <port>3128</port>
+              <username>bob</username>
+              <password>pr0xy</password>
+              <nonProxyHosts>localhost|*.mycorp.com</nonProxyHosts>
+            </proxy>
+          </proxies>
+        </settings>
+    "#;
+
+    fn server(url: &str) -> Server {
+        Server {
+            id: None,
+            url: String::from(url),
+            auth: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings = Settings::parse(SETTINGS).unwrap();
+        assert_eq!(
+            settings.credentials("nexus"),
+            Some(Auth::basic("alice", "s3cure"))
+        );
+        assert_eq!(settings.credentials("other"), None);
+        assert_eq!(settings.mirrors.len(), 1);
+        assert_eq!(settings.proxies.len(), 2);
+    }
+
+    #[test]
+    fn test_local_repository() {
+        let settings =
+            Settings::parse("<settings><localRepository>/opt/m2</localRepository></settings>")
+                .unwrap();
+        assert_eq!(settings.local_repository(), Some(PathBuf::from("/opt/m2")));
+
+        let settings = Settings::parse(
+            "<settings><localRepository>${user.home}/m2</localRepository></settings>",
+        )
+        .unwrap();
+        assert_eq!(
+            settings.local_repository(),
+            dirs::home_dir().map(|home| home.join("m2"))
+        );
+    }
+
+    #[test]
+    fn test_mirror_replaces_central() {
+        let settings = Settings::parse(SETTINGS).unwrap();
+        let server = settings.apply(server(CENTRAL), CENTRAL);
+        assert_eq!(
+            server.url,
+            "https://nexus.mycorp.com/repository/maven-public"
+        );
+        assert_eq!(server.auth, Some(Auth::basic("alice", "s3cure")));
+    }
+
+    #[test]
+    fn test_mirror_keeps_other_servers() {
+        let settings = Settings::parse(SETTINGS).unwrap();
+        let server = settings.apply(server("https://example.org/maven2"), CENTRAL);
+        assert_eq!(server.url, "https://example.org/maven2");
+        assert_eq!(server.auth, None);
+    }
+
+    #[test]
+    fn test_credentials_by_id() {
+        let settings = Settings::parse(SETTINGS).unwrap();
+        let server = Server {
+            id: Some(String::from("nexus")),
+            ..server("https://nexus.mycorp.com/repository/maven-releases")
+        };
+        let server = settings.apply(server, CENTRAL);
+        assert_eq!(
+            server.url,
+            "https://nexus.mycorp.com/repository/maven-releases"
+        );
+        assert_eq!(server.auth, Some(Auth::basic("alice", "s3cure")));
+    }
+
+    #[test_case("*", None, "https://example.org" => true; "star")]
+    #[test_case("central", Some("central"), CENTRAL => true; "by id")]
+    #[test_case("central", None, "https://example.org" => false; "other id")]
+    #[test_case("*,!central", Some("central"), CENTRAL => false; "excluded")]
+    #[test_case("*,!central", None, "https://example.org" => true; "not excluded")]
+    #[test_case("external:*", None, "https://example.org" => true; "external")]
+    #[test_case("external:*", None, "http://localhost:8081" => false; "external localhost")]
+    #[test_case("external:*", None, "file:///tmp/repo" => false; "external file")]
+    fn test_mirror_of(mirror_of: &str, id: Option<&str>, url: &str) -> bool {
+        let mirror = Mirror {
+            id: String::from("mirror"),
+            url: String::from("https://mirror.example.org"),
+            mirror_of: String::from(mirror_of),
+        };
+        mirror.matches(id, &Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_proxy() {
+        let settings = Settings::parse(SETTINGS).unwrap();
+        let proxy = settings.proxy().unwrap();
+        assert_eq!(proxy.url.as_str(), "http://proxy.mycorp.com:3128/");
+        assert_eq!(
+            proxy.auth,
+            Some((String::from("bob"), String::from("pr0xy")))
+        );
+        assert_eq!(proxy.non_proxy_hosts, vec!["localhost", "*.mycorp.com"]);
+    }
+
+    #[test_case("{COQLCE6DU6GtcS5P=}" => true; "encrypted")]
+    #[test_case("expires in May {COQLCE6DU6GtcS5P=}" => true; "with comment")]
+    #[test_case("s3cure" => false; "plain")]
+    #[test_case("${env.PASSWORD}" => false; "environment variable")]
+    #[test_case("{not encrypted}" => false; "braces")]
+    #[test_case("{}" => false; "empty braces")]
+    fn test_is_encrypted(password: &str) -> bool {
+        is_encrypted(password)
+    }
+
+    #[test]
+    fn test_remove_encrypted_passwords() {
+        let mut settings = Settings::parse(
+            r#"<settings>
+              <servers>
+                <server>
+                  <id>encrypted</id>
+                  <username>alice</username>
+                  <password>{COQLCE6DU6GtcS5P=}</password>
+                </server>
+                <server>
+                  <id>plain</id>
+                  <username>bob</username>
+                  <password>s3cure</password>
+                </server>
+              </servers>
+              <proxies>
+                <proxy>
+                  <host>proxy.mycorp.com</host>
+                  <username>carol</username>
+                  <password>{jSMOWnoPFgsHVpMvz5VrIt5kRbzGpI8u+9EF1iFQyJQ=}</password>
+                </proxy>
+              </proxies>
+            </settings>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.remove_encrypted_passwords(),
+            vec!["the server encrypted", "the proxy proxy.mycorp.com"]
+        );
+        assert_eq!(settings.credentials("encrypted"), None);
+        assert_eq!(
+            settings.credentials("plain"),
+            Some(Auth::basic("bob", "s3cure"))
+        );
+        assert_eq!(settings.proxy().unwrap().auth, None);
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        std::env::set_var("LMV_TEST_SETTINGS_PASSWORD", "from-env");
+        assert_eq!(interpolate("${env.LMV_TEST_SETTINGS_PASSWORD}"), "from-env");
+        assert_eq!(
+            interpolate("pre-${env.LMV_TEST_SETTINGS_PASSWORD}-post"),
+            "pre-from-env-post"
+        );
+        assert_eq!(
+            interpolate("${env.LMV_TEST_SETTINGS_UNDEFINED}"),
+            "${env.LMV_TEST_SETTINGS_UNDEFINED}"
+        );
+        assert_eq!(interpolate("${env.UNTERMINATED"), "${env.UNTERMINATED");
+    }
+}
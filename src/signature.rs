@@ -0,0 +1,337 @@
+use crate::{
+    temp::{private_dir, write_new},
+    Coordinates,
+};
+use console::style;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    io,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// The name of the file in the data directory that remembers the signers of the coordinates.
+const SIGNERS_FILE: &str = "signers";
+
+/// Verifies the `.asc` signatures of artifacts with the `gpg` of the system.
+///
+/// With a keyring, only its keys are used. Otherwise, missing keys are fetched from the keyserver of
+/// the `gpg` configuration, and the signers are trusted on first use, see [`Signers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Gpg {
+    keyring: Option<PathBuf>,
+}
+
+/// The key that made a good signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Signer {
+    /// The fingerprint of the primary key.
+    pub(crate) fingerprint: String,
+    /// The user id of the key, e.g. `Jane Doe <jane@example.com>`.
+    pub(crate) user_id: String,
+}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// `gpg` could not be run.
+    NotStarted(io::Error),
+    /// The signature does not match the file.
+    BadSignature(String),
+    /// The signature is good, but the key expired or was revoked.
+    InvalidKey(String),
+    /// The key of the signature is not in the keyring and could not be fetched.
+    MissingKey(String),
+    /// Any other failure, with the output of `gpg`.
+    Failed(String),
+}
+
+impl Gpg {
+    pub(crate) fn new(keyring: Option<PathBuf>) -> Self {
+        Self { keyring }
+    }
+
+    /// Whether the signers are trusted on first use, as the keys are fetched automatically.
+    pub(crate) fn trusts_on_first_use(&self) -> bool {
+        self.keyring.is_none()
+    }
+
+    /// Verifies the armored signature of the content.
+    pub(crate) async fn verify(&self, content: &[u8], signature: &str) -> Result<Signer, Error> {
+        // gpg reads the signature from a file and the signed data from stdin,
+        // other users must not be able to swap the file before gpg reads it
+        let dir = private_dir().await.map_err(Error::NotStarted)?;
+        let signature_file = dir.join("artifact.asc");
+        let output = match write_new(&signature_file, signature.as_bytes()).await {
+            Ok(()) => self.run(&signature_file, content).await,
+            Err(e) => Err(e),
+        };
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        let output = output.map_err(Error::NotStarted)?;
+
+        let stderr = || String::from_utf8_lossy(&output.stderr).into_owned();
+        let signer =
+            parse_status(&String::from_utf8_lossy(&output.stdout)).map_err(|e| match e {
+                Error::Failed(_) => Error::Failed(stderr()),
+                e => e,
+            })?;
+        // a good signature in the status lines is not enough if gpg failed otherwise
+        if !output.status.success() {
+            return Err(Error::Failed(stderr()));
+        }
+        Ok(signer)
+    }
+
+    async fn run(&self, signature_file: &Path, content: &[u8]) -> io::Result<std::process::Output> {
+        let mut command = Command::new("gpg");
+        command.args(["--batch", "--no-tty", "--status-fd", "1"]);
+        match &self.keyring {
+            Some(keyring) => {
+                command
+                    .arg("--no-default-keyring")
+                    .arg("--keyring")
+                    .arg(keyring);
+            }
+            None => {
+                command.args(["--auto-key-retrieve"]);
+            }
+        }
+        let mut child = command
+            .arg("--verify")
+            .arg(signature_file)
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // gpg stops reading early if the signature is broken
+            let _ = stdin.write_all(content).await;
+        }
+        child.wait_with_output().await
+    }
+}
+
+/// Reads the result from the machine readable status lines of `gpg --status-fd`.
+fn parse_status(status: &str) -> Result<Signer, Error> {
+    let mut user_id = None;
+    let mut fingerprint = None;
+    for line in status.lines() {
+        let mut fields = match line.strip_prefix("[GNUPG:] ") {
+            Some(line) => line.splitn(3, ' '),
+            None => continue,
+        };
+        let keyword = fields.next().unwrap_or_default();
+        let key_id = fields.next().unwrap_or_default();
+        let rest = fields.next().unwrap_or_default();
+        match keyword {
+            "GOODSIG" => user_id = Some(String::from(rest)),
+            // the primary key fingerprint is the last field
+            "VALIDSIG" => {
+                let primary = rest.rsplit(' ').next().filter(|f| f.len() >= 32);
+                fingerprint = Some(String::from(primary.unwrap_or(key_id)));
+            }
+            "BADSIG" => return Err(Error::BadSignature(String::from(rest))),
+            "EXPKEYSIG" | "REVKEYSIG" => return Err(Error::InvalidKey(String::from(rest))),
+            "NO_PUBKEY" => return Err(Error::MissingKey(String::from(key_id))),
+            _ => {}
+        }
+    }
+    match (fingerprint, user_id) {
+        (Some(fingerprint), Some(user_id)) => Ok(Signer {
+            fingerprint,
+            user_id,
+        }),
+        _ => Err(Error::Failed(String::new())),
+    }
+}
+
+/// The fingerprints that signed the coordinates before, so that a different signer is noticed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Signers {
+    path: Option<PathBuf>,
+    known: BTreeMap<String, String>,
+}
+
+/// How a signer relates to the signers that were seen before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Trust {
+    /// The coordinates were not signed before, the signer is remembered.
+    FirstUse,
+    /// The coordinates were signed by the same key before.
+    Known,
+    /// The coordinates were signed by the key with this fingerprint before.
+    Changed(String),
+}
+
+impl Signers {
+    /// The signers in the user's data directory, if there is one.
+    pub(crate) fn in_default_dir() -> io::Result<Self> {
+        let path = match dirs::data_dir() {
+            Some(dir) => dir.join(env!("CARGO_PKG_NAME")).join(SIGNERS_FILE),
+            None => return Ok(Self::default()),
+        };
+        let known = match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            path: Some(path),
+            known,
+        })
+    }
+
+    /// Each line has the coordinates and the fingerprint, separated by whitespace.
+    fn parse(content: &str) -> BTreeMap<String, String> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let (coordinates, fingerprint) = line.trim().split_once(char::is_whitespace)?;
+                Some((String::from(coordinates), String::from(fingerprint.trim())))
+            })
+            .collect()
+    }
+
+    /// Checks the signer against the one that was seen before, and remembers a new one.
+    ///
+    /// A changed signer is not remembered, so that it is reported until the file is edited.
+    pub(crate) fn trust(&mut self, coordinates: &Coordinates, signer: &Signer) -> Trust {
+        let key = format!("{}:{}", coordinates.group_id, coordinates.artifact);
+        match self.known.get(&key) {
+            Some(known) if *known == signer.fingerprint => Trust::Known,
+            Some(known) => Trust::Changed(known.clone()),
+            None => {
+                self.known.insert(key, signer.fingerprint.clone());
+                Trust::FirstUse
+            }
+        }
+    }
+
+    pub(crate) fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = self
+            .known
+            .iter()
+            .map(|(coordinates, fingerprint)| format!("{} {}\n", coordinates, fingerprint))
+            .collect::<String>();
+        std::fs::write(path, content)
+    }
+
+    pub(crate) fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotStarted(e) => write!(
+                f,
+                "Could not run {} to verify the signature: {}",
+                style("gpg").cyan(),
+                e
+            ),
+            Error::BadSignature(user_id) => write!(
+                f,
+                "The signature by {} does not match the file, it might have been tampered with",
+                style(user_id).red().bold()
+            ),
+            Error::InvalidKey(user_id) => write!(
+                f,
+                "The key of {} expired or was revoked",
+                style(user_id).red().bold()
+            ),
+            Error::MissingKey(key_id) => write!(
+                f,
+                "The key {} of the signature is not in the keyring and could not be fetched",
+                style(key_id).red().bold()
+            ),
+            Error::Failed(output) => {
+                write!(f, "The signature could not be verified: {}", output.trim())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static GOOD: &str = "\
+[GNUPG:] NEWSIG
+[GNUPG:] KEY_CONSIDERED 0123456789ABCDEF0123456789ABCDEF01234567 0
+[GNUPG:] SIG_ID abcdef 2020-08-27 1598542637
+[GNUPG:] GOODSIG 89ABCDEF01234567 Jane Doe <jane@example.com>
+[GNUPG:] VALIDSIG FEDCBA9876543210FEDCBA9876543210FEDCBA98 2020-08-27 1598542637 0 4 0 1 10 00 0123456789ABCDEF0123456789ABCDEF01234567
+[GNUPG:] TRUST_UNDEFINED 0 pgp
+";
+
+    fn signer(fingerprint: &str) -> Signer {
+        Signer {
+            fingerprint: String::from(fingerprint),
+            user_id: String::from("Jane Doe <jane@example.com>"),
+        }
+    }
+
+    #[test]
+    fn test_good_signature() {
+        assert_eq!(
+            parse_status(GOOD).unwrap(),
+            signer("0123456789ABCDEF0123456789ABCDEF01234567")
+        );
+    }
+
+    #[test]
+    fn test_bad_signature() {
+        let status =
+            "[GNUPG:] NEWSIG\n[GNUPG:] BADSIG 89ABCDEF01234567 Jane Doe <jane@example.com>\n";
+        let err = parse_status(status).unwrap_err();
+        assert!(matches!(err, Error::BadSignature(user) if user == "Jane Doe <jane@example.com>"));
+    }
+
+    #[test]
+    fn test_missing_key() {
+        let status = "[GNUPG:] ERRSIG 89ABCDEF01234567 1 10 00 1598542637 9 -\n[GNUPG:] NO_PUBKEY 89ABCDEF01234567\n";
+        let err = parse_status(status).unwrap_err();
+        assert!(matches!(err, Error::MissingKey(key) if key == "89ABCDEF01234567"));
+    }
+
+    #[test]
+    fn test_no_signature() {
+        let err = parse_status("[GNUPG:] NODATA 1\n").unwrap_err();
+        assert!(matches!(err, Error::Failed(_)));
+    }
+
+    #[test]
+    fn test_trust_on_first_use() {
+        let mut signers = Signers {
+            path: None,
+            known: Signers::parse("com.foo:baz AAAA\n"),
+        };
+        let coordinates = Coordinates::new("com.foo", "bar");
+        assert_eq!(
+            signers.trust(&coordinates, &signer("BBBB")),
+            Trust::FirstUse
+        );
+        assert_eq!(signers.trust(&coordinates, &signer("BBBB")), Trust::Known);
+        assert_eq!(
+            signers.trust(&coordinates, &signer("CCCC")),
+            Trust::Changed(String::from("BBBB"))
+        );
+        assert_eq!(
+            signers.trust(&Coordinates::new("com.foo", "baz"), &signer("AAAA")),
+            Trust::Known
+        );
+    }
+}
@@ -0,0 +1,501 @@
+use crate::{
+    checksum::hex,
+    temp::{private_dir, write_new},
+};
+use ring::digest;
+use serde::Deserialize;
+use std::{
+    fmt::{self, Display},
+    io,
+    path::Path,
+};
+use tokio::process::Command;
+
+/// The extensions of the bundles next to an artifact, in the order they are looked up.
+pub(crate) static BUNDLE_EXTENSIONS: [&str; 2] = ["sigstore.json", "sigstore"];
+
+/// The payload type of in-toto statements in DSSE envelopes.
+static IN_TOTO: &str = "application/vnd.in-toto+json";
+
+/// The DER encoding of the OID of the subject alternative name extension, 2.5.29.17.
+static SUBJECT_ALT_NAME: [u8; 3] = [0x55, 0x1d, 0x11];
+
+/// The DER encoding of the OID of Fulcio's issuer extension, 1.3.6.1.4.1.57264.1.8, whose value is a UTF8String.
+static FULCIO_ISSUER: [u8; 10] = [0x2b, 0x06, 0x01, 0x04, 0x01, 0x83, 0xbf, 0x30, 0x01, 0x08];
+
+/// The DER encoding of the OID of Fulcio's deprecated issuer extension, 1.3.6.1.4.1.57264.1.1, whose value is raw.
+static FULCIO_ISSUER_V1: [u8; 10] = [0x2b, 0x06, 0x01, 0x04, 0x01, 0x83, 0xbf, 0x30, 0x01, 0x01];
+
+/// Verifies the Sigstore bundles of artifacts with the `cosign` of the system.
+///
+/// `cosign` checks the signature, the certificate chain up to Fulcio, and the entry in the Rekor transparency log
+/// against the trusted root that it keeps up to date. The identity that signed is read from the certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Cosign {
+    /// A regular expression that the identity of the certificate must match, any identity without it.
+    identity: Option<String>,
+    /// A regular expression that the OIDC issuer of the certificate must match, any issuer without it.
+    issuer: Option<String>,
+}
+
+/// What a verified bundle tells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Attested {
+    /// Who signed, the email or the URI of the certificate, e.g. the workflow that built the artifact.
+    pub(crate) identity: String,
+    /// The OIDC issuer that vouched for the identity, e.g. `https://token.actions.githubusercontent.com`.
+    pub(crate) issuer: Option<String>,
+    /// The provenance of the artifact, if the bundle is an attestation instead of a signature.
+    pub(crate) provenance: Option<Provenance>,
+}
+
+/// An in-toto attestation about the artifact, e.g. SLSA provenance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Provenance {
+    /// The type of the predicate, e.g. `https://slsa.dev/provenance/v1`.
+    pub(crate) predicate_type: String,
+    /// The builder that the provenance names, if it names one.
+    pub(crate) builder: Option<String>,
+}
+
+/// The outcome of verifying the bundle of a version.
+#[derive(Debug)]
+pub(crate) enum Status {
+    Verified(Attested),
+    /// The version has no bundle.
+    Unavailable,
+    /// The bundle cannot be verified, because it is malformed, for a different file, or rejected by `cosign`.
+    Invalid(Error),
+}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// `cosign` could not be run.
+    NotStarted(io::Error),
+    /// The bundle cannot be parsed.
+    Malformed(String),
+    /// The bundle is for a different file.
+    Mismatch,
+    /// `cosign` rejected the bundle, with its output.
+    Rejected(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Bundle {
+    verification_material: VerificationMaterial,
+    message_signature: Option<MessageSignature>,
+    dsse_envelope: Option<Envelope>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerificationMaterial {
+    /// The certificate of bundles since v0.3.
+    certificate: Option<RawBytes>,
+    /// The certificate chain of older bundles, which starts with the certificate that signed.
+    x509_certificate_chain: Option<Chain>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chain {
+    certificates: Vec<RawBytes>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawBytes {
+    raw_bytes: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageSignature {
+    message_digest: Option<MessageDigest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageDigest {
+    algorithm: String,
+    digest: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    payload: String,
+    payload_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Statement {
+    predicate_type: String,
+    #[serde(default)]
+    subject: Vec<Subject>,
+    #[serde(default)]
+    predicate: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct Subject {
+    #[serde(default)]
+    digest: std::collections::BTreeMap<String, String>,
+}
+
+impl Cosign {
+    pub(crate) fn new(identity: Option<String>, issuer: Option<String>) -> Self {
+        Self { identity, issuer }
+    }
+
+    /// Verifies the bundle of the content, which is a signature or an attestation.
+    pub(crate) async fn verify(&self, content: &[u8], bundle: &str) -> Result<Attested, Error> {
+        let parsed =
+            serde_json::from_str::<Bundle>(bundle).map_err(|e| Error::Malformed(e.to_string()))?;
+        // cosign checks the digest as well, but this tells a bundle of another file apart from a forged one
+        let provenance = check_digest(&parsed, content)?;
+        let certificate = parsed
+            .verification_material
+            .certificate
+            .as_ref()
+            .or_else(|| {
+                let chain = parsed.verification_material.x509_certificate_chain.as_ref();
+                chain?.certificates.first()
+            })
+            .ok_or_else(|| Error::Malformed(String::from("the bundle has no certificate")))?;
+        let certificate =
+            base64::decode(&certificate.raw_bytes).map_err(|e| Error::Malformed(e.to_string()))?;
+        let (identity, issuer) = identity(&certificate)
+            .ok_or_else(|| Error::Malformed(String::from("the certificate names no identity")))?;
+
+        // other users must not be able to swap the files before cosign reads them
+        let dir = private_dir().await.map_err(Error::NotStarted)?;
+        let content_file = dir.join("artifact.blob");
+        let bundle_file = dir.join("artifact.sigstore.json");
+        let written = match write_new(&content_file, content).await {
+            Ok(()) => write_new(&bundle_file, bundle.as_bytes()).await,
+            Err(e) => Err(e),
+        };
+        let output = match written {
+            Ok(()) => {
+                let predicate_type = provenance.as_ref().map(|p| p.predicate_type.as_str());
+                self.run(&content_file, &bundle_file, predicate_type).await
+            }
+            Err(e) => Err(e),
+        };
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        let output = output.map_err(Error::NotStarted)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Rejected(String::from(stderr.trim())));
+        }
+        Ok(Attested {
+            identity,
+            issuer,
+            provenance,
+        })
+    }
+
+    /// The options that were not given, so that any identity or issuer is accepted, e.g. `--sigstore-issuer`.
+    pub(crate) fn unchecked(&self) -> Vec<(&'static str, &'static str)> {
+        let mut unchecked = Vec::new();
+        if self.identity.is_none() {
+            unchecked.push(("identity", "--sigstore-identity"));
+        }
+        if self.issuer.is_none() {
+            unchecked.push(("issuer", "--sigstore-issuer"));
+        }
+        unchecked
+    }
+
+    async fn run(
+        &self,
+        content_file: &Path,
+        bundle_file: &Path,
+        predicate_type: Option<&str>,
+    ) -> io::Result<std::process::Output> {
+        let mut command = Command::new("cosign");
+        match predicate_type {
+            Some(predicate_type) => command
+                .arg("verify-blob-attestation")
+                .arg("--type")
+                .arg(predicate_type),
+            None => command.arg("verify-blob"),
+        };
+        command
+            .arg("--new-bundle-format")
+            .arg("--bundle")
+            .arg(bundle_file)
+            .arg("--certificate-identity-regexp")
+            .arg(self.identity.as_deref().unwrap_or(".*"))
+            .arg("--certificate-oidc-issuer-regexp")
+            .arg(self.issuer.as_deref().unwrap_or(".*"))
+            .arg(content_file)
+            .kill_on_drop(true)
+            .output()
+            .await
+    }
+}
+
+/// Checks that the bundle is for the content, and reads the provenance if it is an attestation.
+fn check_digest(bundle: &Bundle, content: &[u8]) -> Result<Option<Provenance>, Error> {
+    let sha256 = digest::digest(&digest::SHA256, content);
+    if let Some(envelope) = &bundle.dsse_envelope {
+        if envelope.payload_type != IN_TOTO {
+            return Err(Error::Malformed(format!(
+                "the payload type {} is not supported",
+                envelope.payload_type
+            )));
+        }
+        let payload =
+            base64::decode(&envelope.payload).map_err(|e| Error::Malformed(e.to_string()))?;
+        let statement = serde_json::from_slice::<Statement>(&payload)
+            .map_err(|e| Error::Malformed(e.to_string()))?;
+        let sha256 = hex(sha256.as_ref());
+        if !statement
+            .subject
+            .iter()
+            .any(|subject| subject.digest.get("sha256") == Some(&sha256))
+        {
+            return Err(Error::Mismatch);
+        }
+        return Ok(Some(Provenance {
+            builder: builder(&statement.predicate),
+            predicate_type: statement.predicate_type,
+        }));
+    }
+    let digest = bundle
+        .message_signature
+        .as_ref()
+        .and_then(|signature| signature.message_digest.as_ref())
+        .ok_or_else(|| Error::Malformed(String::from("the bundle has no signature")))?;
+    if digest.algorithm != "SHA2_256" {
+        return Err(Error::Malformed(format!(
+            "the digest algorithm {} is not supported",
+            digest.algorithm
+        )));
+    }
+    match base64::decode(&digest.digest) {
+        Ok(expected) if expected == sha256.as_ref() => Ok(None),
+        Ok(_) => Err(Error::Mismatch),
+        Err(e) => Err(Error::Malformed(e.to_string())),
+    }
+}
+
+/// The builder of SLSA provenance, `runDetails.builder.id` since v1 and `builder.id` before.
+fn builder(predicate: &serde_json::Value) -> Option<String> {
+    predicate
+        .pointer("/runDetails/builder/id")
+        .or_else(|| predicate.pointer("/builder/id"))?
+        .as_str()
+        .map(String::from)
+}
+
+/// The subject alternative name and the Fulcio issuer of a DER encoded certificate.
+fn identity(certificate: &[u8]) -> Option<(String, Option<String>)> {
+    let (_, certificate, _) = tlv(certificate)?;
+    let (_, mut tbs, _) = tlv(certificate)?;
+    // the extensions are the element of the to-be-signed certificate that is tagged with [3]
+    let extensions = loop {
+        let (tag, value, rest) = tlv(tbs)?;
+        if tag == 0xa3 {
+            break tlv(value)?.1;
+        }
+        tbs = rest;
+    };
+    let mut name = None;
+    let mut issuer = None;
+    for (oid, value) in sequence(extensions).filter_map(extension) {
+        if oid == SUBJECT_ALT_NAME {
+            name = sequence(tlv(value)?.1)
+                .find(|(tag, _)| *tag == 0x81 || *tag == 0x86)
+                .map(|(_, name)| String::from_utf8_lossy(name).into_owned());
+        } else if oid == FULCIO_ISSUER {
+            issuer = Some(String::from_utf8_lossy(tlv(value)?.1).into_owned());
+        } else if oid == FULCIO_ISSUER_V1 && issuer.is_none() {
+            issuer = Some(String::from_utf8_lossy(value).into_owned());
+        }
+    }
+    Some((name?, issuer))
+}
+
+/// The OID and the value of an extension, which is a sequence of the OID, maybe whether it is critical,
+/// and the value in an octet string.
+fn extension((_, extension): (u8, &[u8])) -> Option<(&[u8], &[u8])> {
+    let mut fields = sequence(extension);
+    let (_, oid) = fields.next()?;
+    let (_, value) = fields.find(|(tag, _)| *tag == 0x04)?;
+    Some((oid, value))
+}
+
+/// The tags and values of the elements of a DER sequence.
+fn sequence(mut input: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    std::iter::from_fn(move || {
+        let (tag, value, rest) = tlv(input)?;
+        input = rest;
+        Some((tag, value))
+    })
+}
+
+/// Reads the tag, the value, and what follows of a DER element.
+fn tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&length, mut input) = input.split_first()?;
+    let length = if length & 0x80 == 0 {
+        usize::from(length)
+    } else {
+        let octets = usize::from(length & 0x7f);
+        if octets > std::mem::size_of::<usize>() || octets > input.len() {
+            return None;
+        }
+        let (length, rest) = input.split_at(octets);
+        input = rest;
+        length
+            .iter()
+            .fold(0_usize, |length, &octet| (length << 8) | usize::from(octet))
+    };
+    if length > input.len() {
+        return None;
+    }
+    let (value, rest) = input.split_at(length);
+    Some((tag, value, rest))
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotStarted(e) => write!(f, "cosign could not be run: {}", e),
+            Error::Malformed(e) => write!(f, "The bundle is malformed: {}", e),
+            Error::Mismatch => f.write_str("The bundle is for a different file"),
+            Error::Rejected(output) => write!(f, "cosign rejected the bundle: {}", output),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::NotStarted(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    /// Encodes a DER element of less than 64KiB.
+    fn der(tag: u8, value: &[u8]) -> Vec<u8> {
+        let length = value.len();
+        let mut element = match length {
+            0..=0x7f => vec![tag, length as u8],
+            0x80..=0xff => vec![tag, 0x81, length as u8],
+            _ => vec![tag, 0x82, (length >> 8) as u8, length as u8],
+        };
+        element.extend_from_slice(value);
+        element
+    }
+
+    fn certificate(name: &[u8], issuer: &str) -> Vec<u8> {
+        let san = der(0x30, &der(0x86, name));
+        let san = [der(0x06, &SUBJECT_ALT_NAME), der(0x04, &san)].concat();
+        let fulcio = der(0x0c, issuer.as_bytes());
+        let fulcio = [der(0x06, &FULCIO_ISSUER), der(0x04, &fulcio)].concat();
+        let extensions = [der(0x30, &san), der(0x30, &fulcio)].concat();
+        let tbs = [
+            der(0xa0, &der(0x02, &[2])),
+            der(0x02, &[1]),
+            der(0xa3, &der(0x30, &extensions)),
+        ]
+        .concat();
+        der(0x30, &der(0x30, &tbs))
+    }
+
+    #[test_case(None, None => vec!["identity", "issuer"]; "neither")]
+    #[test_case(Some("^https://github.com/neo4j/"), None => vec!["issuer"]; "identity")]
+    #[test_case(Some("^https://github.com/neo4j/"), Some("^https://token.actions.githubusercontent.com$") => Vec::<&str>::new(); "both")]
+    fn test_unchecked(identity: Option<&str>, issuer: Option<&str>) -> Vec<&'static str> {
+        let cosign = Cosign::new(identity.map(String::from), issuer.map(String::from));
+        cosign
+            .unchecked()
+            .into_iter()
+            .map(|(what, _)| what)
+            .collect()
+    }
+
+    #[test]
+    fn test_identity() {
+        let workflow =
+            b"https://github.com/example/lib/.github/workflows/release.yml@refs/tags/v1.0";
+        let certificate = certificate(workflow, "https://token.actions.githubusercontent.com");
+        assert_eq!(
+            identity(&certificate),
+            Some((
+                String::from_utf8(workflow.to_vec()).unwrap(),
+                Some(String::from("https://token.actions.githubusercontent.com"))
+            ))
+        );
+    }
+
+    #[test]
+    fn test_tlv_long_length() {
+        let value = vec![7; 300];
+        let element = [&[0x04, 0x82, 0x01, 0x2c][..], &value, &[0x05, 0x00]].concat();
+        let (tag, read, rest) = tlv(&element).unwrap();
+        assert_eq!((tag, read.len(), rest), (0x04, 300, &[0x05, 0x00][..]));
+        assert_eq!(tlv(&[0x04, 0x82, 0x01]), None);
+    }
+
+    #[test]
+    fn test_check_digest() {
+        let content = b"hello";
+        let sha256 = digest::digest(&digest::SHA256, content);
+        let bundle = serde_json::from_value::<Bundle>(serde_json::json!({
+            "mediaType": "application/vnd.dev.sigstore.bundle.v0.3+json",
+            "verificationMaterial": { "certificate": { "rawBytes": "" } },
+            "messageSignature": {
+                "messageDigest": { "algorithm": "SHA2_256", "digest": base64::encode(sha256) },
+                "signature": ""
+            }
+        }))
+        .unwrap();
+        assert!(matches!(check_digest(&bundle, content), Ok(None)));
+        assert!(matches!(
+            check_digest(&bundle, b"other"),
+            Err(Error::Mismatch)
+        ));
+    }
+
+    #[test]
+    fn test_check_attestation() {
+        let content = b"hello";
+        let statement = serde_json::json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [ { "name": "lib-1.0.jar", "digest": { "sha256": hex(digest::digest(&digest::SHA256, content).as_ref()) } } ],
+            "predicateType": "https://slsa.dev/provenance/v1",
+            "predicate": { "runDetails": { "builder": { "id": "https://github.com/actions/runner" } } }
+        });
+        let bundle = serde_json::from_value::<Bundle>(serde_json::json!({
+            "verificationMaterial": { "certificate": { "rawBytes": "" } },
+            "dsseEnvelope": {
+                "payload": base64::encode(statement.to_string()),
+                "payloadType": "application/vnd.in-toto+json",
+                "signatures": []
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            check_digest(&bundle, content).unwrap(),
+            Some(Provenance {
+                predicate_type: String::from("https://slsa.dev/provenance/v1"),
+                builder: Some(String::from("https://github.com/actions/runner")),
+            })
+        );
+        assert!(matches!(
+            check_digest(&bundle, b"other"),
+            Err(Error::Mismatch)
+        ));
+    }
+}
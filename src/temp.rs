@@ -0,0 +1,56 @@
+use crate::checksum::hex;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use tokio::io::AsyncWriteExt;
+
+/// A new directory with a random name in the temp directory, that only the current user can access.
+///
+/// Files for other programs go in here, so that other users cannot swap them before they are read.
+pub(crate) async fn private_dir() -> io::Result<PathBuf> {
+    let mut random = [0; 16];
+    SystemRandom::new()
+        .fill(&mut random)
+        .map_err(|_| io::Error::other("no random name"))?;
+    let dir = std::env::temp_dir().join(format!("{}-{}", env!("CARGO_PKG_NAME"), hex(&random)));
+    let mut builder = tokio::fs::DirBuilder::new();
+    #[cfg(unix)]
+    builder.mode(0o700);
+    // fails if the directory exists already
+    builder.create(&dir).await?;
+    Ok(dir)
+}
+
+/// Writes a file that must not exist yet.
+pub(crate) async fn write_new(path: &Path, content: &[u8]) -> io::Result<()> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .await?;
+    file.write_all(content).await?;
+    file.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_private_dir() {
+        let dir = private_dir().await.unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        let file = dir.join("artifact.blob");
+        write_new(&file, b"content").await.unwrap();
+        assert!(write_new(&file, b"swapped").await.is_err());
+        assert_eq!(std::fs::read(&file).unwrap(), b"content");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,415 @@
+use crate::{
+    pom::{Dependency, Pom, Properties},
+    resolvers::{Client, Error, Resolver},
+    Coordinates,
+};
+use console::style;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::{self, Display},
+};
+
+/// How many parents, and how many imported BOMs, are followed for one pom, which also stops at cycles.
+const MAX_INHERITANCE: usize = 16;
+
+/// The scopes that put a dependency on the classpath of its dependents.
+static TRANSITIVE_SCOPES: [&str; 2] = ["compile", "runtime"];
+
+/// The dependencies of a version and their dependencies, as far as their poms tell.
+///
+/// Only dependencies that end up on the classpath of the dependents are followed, i.e. not the optional ones
+/// and not the `test` or `provided` ones. Versions come from the pom itself, its parents, or its imported BOMs.
+/// Like Maven, a dependency that was already listed closer to the root is not listed again.
+#[derive(Debug)]
+pub(crate) struct Tree {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug)]
+struct Node {
+    coordinates: Coordinates,
+    version: Option<String>,
+    scope: Option<String>,
+    state: State,
+    children: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// The dependencies of the node are listed, unless it is too deep.
+    Listed,
+    /// The node was already listed closer to the root.
+    Repeated,
+    /// The version is not declared or refers to an unknown property.
+    UnknownVersion,
+    /// The resolver has no pom for the version, or it cannot be parsed.
+    MissingPom,
+}
+
+/// The poms that were fetched, as the same parents are needed over and over.
+pub(crate) struct Poms<'a, R, C> {
+    resolver: &'a R,
+    client: &'a C,
+    fetched: HashMap<(Coordinates, String), Option<Pom>>,
+}
+
+/// The dependencies of a pom, with what its parents and imported BOMs add to them.
+pub(crate) struct Effective {
+    dependencies: Vec<Dependency>,
+    managed: Vec<Dependency>,
+    /// The properties of the pom and its parents.
+    pub(crate) properties: Properties,
+    /// The licenses of the pom, or of the closest parent that has any.
+    pub(crate) licenses: Vec<String>,
+}
+
+impl Tree {
+    /// Resolves the dependencies of the version, and their dependencies up to the depth.
+    pub(crate) async fn resolve<R, C>(
+        resolver: &R,
+        client: &C,
+        coordinates: &Coordinates,
+        version: &str,
+        depth: usize,
+    ) -> Result<Self, Error>
+    where
+        R: Resolver,
+        C: Client,
+    {
+        let mut poms = Poms::new(resolver, client);
+        let mut nodes = vec![Node {
+            coordinates: coordinates.clone(),
+            version: Some(String::from(version)),
+            scope: None,
+            state: State::Listed,
+            children: Vec::new(),
+        }];
+        let mut seen = HashSet::from([coordinates.clone()]);
+        // breadth first, so that the dependency closest to the root is the listed one
+        let mut level = vec![0];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for parent in level {
+                let node = &nodes[parent];
+                let version = match &node.version {
+                    Some(version) => version.clone(),
+                    None => continue,
+                };
+                let effective = match poms.effective(&node.coordinates, &version).await? {
+                    Some(effective) => effective,
+                    None => {
+                        nodes[parent].state = State::MissingPom;
+                        continue;
+                    }
+                };
+                for dependency in effective.dependencies() {
+                    let state = if dependency.version.is_none() {
+                        State::UnknownVersion
+                    } else if !seen.insert(dependency.coordinates.clone()) {
+                        State::Repeated
+                    } else {
+                        next.push(nodes.len());
+                        State::Listed
+                    };
+                    let child = nodes.len();
+                    nodes[parent].children.push(child);
+                    nodes.push(Node {
+                        coordinates: dependency.coordinates,
+                        version: dependency.version,
+                        scope: dependency.scope,
+                        state,
+                        children: Vec::new(),
+                    });
+                }
+            }
+            level = next;
+        }
+        Ok(Self { nodes })
+    }
+
+    fn fmt_children(&self, f: &mut fmt::Formatter<'_>, node: usize, indent: &str) -> fmt::Result {
+        let children = &self.nodes[node].children;
+        for (index, child) in children.iter().enumerate() {
+            let last = index + 1 == children.len();
+            let (branch, continuation) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            writeln!(f, "{}{}{}", indent, branch, self.nodes[*child])?;
+            self.fmt_children(f, *child, &format!("{}{}", indent, continuation))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, R: Resolver, C: Client> Poms<'a, R, C> {
+    pub(crate) fn new(resolver: &'a R, client: &'a C) -> Self {
+        Self {
+            resolver,
+            client,
+            fetched: HashMap::new(),
+        }
+    }
+
+    async fn get(
+        &mut self,
+        coordinates: &Coordinates,
+        version: &str,
+    ) -> Result<Option<&Pom>, Error> {
+        let key = (coordinates.clone(), String::from(version));
+        if !self.fetched.contains_key(&key) {
+            let file = self
+                .resolver
+                .version_file(coordinates, version, "pom", self.client)
+                .await?;
+            let pom = file.and_then(|file| Pom::parse(&file).ok());
+            self.fetched.insert(key.clone(), pom);
+        }
+        Ok(self.fetched[&key].as_ref())
+    }
+
+    /// The dependencies of the pom and its parents, with the versions from their dependency management.
+    pub(crate) async fn effective(
+        &mut self,
+        coordinates: &Coordinates,
+        version: &str,
+    ) -> Result<Option<Effective>, Error> {
+        let mut effective = match self.inherited(coordinates, version).await? {
+            Some(effective) => effective,
+            None => return Ok(None),
+        };
+        let mut imports = effective.imports().collect::<VecDeque<_>>();
+        let mut followed = 0;
+        while let Some((coordinates, version)) = imports.pop_front() {
+            if followed == MAX_INHERITANCE {
+                break;
+            }
+            followed += 1;
+            if let Some(bom) = self.inherited(&coordinates, &version).await? {
+                imports.extend(bom.imports());
+                add_missing(&mut effective.managed, bom.managed);
+            }
+        }
+        Ok(Some(effective))
+    }
+
+    /// The dependencies of the pom and its parents, with their properties interpolated, but without the BOMs.
+    pub(crate) async fn inherited(
+        &mut self,
+        coordinates: &Coordinates,
+        version: &str,
+    ) -> Result<Option<Effective>, Error> {
+        let pom = match self.get(coordinates, version).await? {
+            Some(pom) => pom,
+            None => return Ok(None),
+        };
+        let mut properties = pom.properties.clone();
+        let mut dependencies = pom.declared.clone();
+        let mut managed = pom.managed.clone();
+        let mut licenses = pom.details.licenses.clone();
+        let mut parent = pom.parent.clone();
+        for _ in 0..MAX_INHERITANCE {
+            let pom = match &parent {
+                Some((coordinates, version)) => match self.get(coordinates, version).await? {
+                    Some(pom) => pom,
+                    None => break,
+                },
+                None => break,
+            };
+            properties.inherit(&pom.properties);
+            if licenses.is_empty() {
+                licenses = pom.details.licenses.clone();
+            }
+            add_missing(&mut dependencies, pom.declared.clone());
+            add_missing(&mut managed, pom.managed.clone());
+            parent = pom.parent.clone();
+        }
+        for dependency in dependencies.iter_mut().chain(managed.iter_mut()) {
+            let interpolate = |value: &mut Option<String>| {
+                *value = value.as_deref().map(|value| properties.interpolate(value));
+            };
+            interpolate(&mut dependency.version);
+            interpolate(&mut dependency.scope);
+        }
+        Ok(Some(Effective {
+            dependencies,
+            managed,
+            properties,
+            licenses,
+        }))
+    }
+}
+
+/// Adds the dependencies that are not there yet, the ones that are there take precedence.
+fn add_missing(dependencies: &mut Vec<Dependency>, other: Vec<Dependency>) {
+    for dependency in other {
+        if !dependencies
+            .iter()
+            .any(|d| d.coordinates == dependency.coordinates)
+        {
+            dependencies.push(dependency);
+        }
+    }
+}
+
+impl Effective {
+    /// The BOMs that the dependency management imports.
+    fn imports(&self) -> impl Iterator<Item = (Coordinates, String)> + '_ {
+        self.managed
+            .iter()
+            .filter(|d| d.scope.as_deref() == Some("import"))
+            .filter_map(|d| Some((d.coordinates.clone(), d.version.clone()?)))
+    }
+
+    /// The dependencies that end up on the classpath of the dependents, with their managed version and scope.
+    ///
+    /// A version that still refers to a property is unknown.
+    pub(crate) fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies
+            .iter()
+            .filter(|dependency| !dependency.optional)
+            .map(|dependency| {
+                let mut dependency = dependency.clone();
+                let managed = self
+                    .managed
+                    .iter()
+                    .find(|m| m.coordinates == dependency.coordinates);
+                if dependency.version.is_none() {
+                    dependency.version = managed.and_then(|m| m.version.clone());
+                }
+                if dependency.scope.is_none() {
+                    dependency.scope = managed.and_then(|m| m.scope.clone());
+                }
+                dependency.version = dependency.version.filter(|v| !v.contains("${"));
+                dependency
+            })
+            .filter(|dependency| {
+                let scope = dependency.scope.as_deref().unwrap_or("compile");
+                TRANSITIVE_SCOPES.contains(&scope)
+            })
+            .collect()
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}",
+            style(&self.coordinates.group_id).magenta(),
+            style(&self.coordinates.artifact).blue()
+        )?;
+        if let Some(version) = &self.version {
+            write!(f, ":{}", style(version).green())?;
+        }
+        if let Some(scope) = self.scope.as_deref().filter(|scope| *scope != "compile") {
+            write!(f, " ({})", scope)?;
+        }
+        match self.state {
+            State::Listed => Ok(()),
+            State::Repeated => write!(f, " {}", style("(already listed)").dim()),
+            State::UnknownVersion => write!(f, " {}", style("(unknown version)").yellow()),
+            State::MissingPom => write!(f, " {}", style("(no pom)").yellow()),
+        }
+    }
+}
+
+impl Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let root = &self.nodes[0];
+        if root.children.is_empty() && root.state == State::Listed {
+            return writeln!(f, "{} has no dependencies", root);
+        }
+        writeln!(f, "Dependencies of {}:", root)?;
+        self.fmt_children(f, 0, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolvers::{ErrorKind, RequestOptions, UrlResolver};
+    use async_trait::async_trait;
+    use url::Url;
+
+    /// Serves the poms from memory, everything else is not found.
+    struct PomClient(Vec<(&'static str, String)>);
+
+    #[async_trait]
+    impl Client for PomClient {
+        async fn request(
+            &self,
+            url: &Url,
+            _options: &RequestOptions,
+            coordinates: &Coordinates,
+        ) -> Result<String, ErrorKind> {
+            match self.0.iter().find(|(file, _)| url.path().ends_with(file)) {
+                Some((_, pom)) => Ok(pom.clone()),
+                None => Err(ErrorKind::CoordinatesNotFound(coordinates.clone())),
+            }
+        }
+    }
+
+    fn dependency(group_id: &str, artifact: &str, version: &str, scope: &str) -> String {
+        format!(
+            "<dependency><groupId>{}</groupId><artifactId>{}</artifactId><version>{}</version><scope>{}</scope></dependency>",
+            group_id, artifact, version, scope
+        )
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let app = format!(
+            "<project><parent><groupId>com.foo</groupId><artifactId>parent</artifactId><version>1</version></parent>
+             <dependencies>{}{}{}<dependency><groupId>com.foo</groupId><artifactId>managed</artifactId></dependency></dependencies></project>",
+            dependency("com.foo", "lib", "${lib.version}", "compile"),
+            dependency("com.foo", "runtime", "2.0", "runtime"),
+            dependency("junit", "junit", "4.13", "test"),
+        );
+        let parent = format!(
+            "<project><properties><lib.version>1.0</lib.version></properties>
+             <dependencyManagement><dependencies>{}</dependencies></dependencyManagement></project>",
+            dependency("com.foo", "bom", "3", "import")
+        );
+        let bom = format!(
+            "<project><dependencyManagement><dependencies>{}</dependencies></dependencyManagement></project>",
+            dependency("com.foo", "managed", "3.0", "compile")
+        );
+        let lib = format!(
+            "<project><dependencies>{}{}</dependencies></project>",
+            dependency("com.foo", "runtime", "2.1", "compile"),
+            dependency("com.foo", "deep", "1.0", "compile"),
+        );
+        let client = PomClient(vec![
+            ("/app-1.0.pom", app),
+            ("/parent-1.pom", parent),
+            ("/bom-3.pom", bom),
+            ("/lib-1.0.pom", lib),
+        ]);
+        let resolver = UrlResolver::new("http://releases", None).unwrap();
+        let coordinates = Coordinates::new("com.foo", "app");
+
+        let tree = Tree::resolve(&resolver, &client, &coordinates, "1.0", 2)
+            .await
+            .unwrap();
+        console::set_colors_enabled(false);
+        assert_eq!(
+            tree.to_string(),
+            "\
+Dependencies of com.foo:app:1.0:
+├── com.foo:lib:1.0
+│   ├── com.foo:runtime:2.1 (already listed)
+│   └── com.foo:deep:1.0
+├── com.foo:runtime:2.0 (runtime) (no pom)
+└── com.foo:managed:3.0 (no pom)
+"
+        );
+
+        let tree = Tree::resolve(&resolver, &client, &coordinates, "1.0", 1)
+            .await
+            .unwrap();
+        assert_eq!(tree.nodes[0].children.len(), 3);
+        assert!(tree.nodes.iter().all(|node| node.state == State::Listed));
+    }
+}
@@ -0,0 +1,491 @@
+use super::{
+    send_with_challenge, Auth, Client as CrateClient, ClientOptions, Error, ErrorKind, IpFamily,
+    Proxy, RequestOptions,
+};
+use crate::{
+    http::{
+        content_type_charset, forward_auth, retry_after, user_agent, DEFAULT_MAX_RESPONSE_SIZE,
+        DEFAULT_TIMEOUT,
+    },
+    metadata::{Metadata, StreamParser},
+    runtime::unblock,
+    Coordinates,
+};
+use async_trait::async_trait;
+use console::style;
+use encoding_rs::{Encoding, UTF_8};
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    net::{SocketAddr, ToSocketAddrs},
+    sync::Arc,
+    time::SystemTime,
+};
+use ureq::{Agent, AgentBuilder, Request, Response};
+use url::Url;
+
+/// Sends the requests with ureq, on the blocking threads of the tokio runtime if there is one.
+///
+/// Without a runtime, the requests are sent on the calling thread, and the futures of this client
+/// complete when they are first polled, e.g. by [`futures_executor::block_on`].
+pub(super) struct UreqClient {
+    agents: Agents,
+    /// The headers of `--header`, which are only sent to the origin of the resolver, like the credentials.
+    headers: Vec<(String, String)>,
+    forward_auth_cross_origin: bool,
+    /// Redirects are followed manually, so that credentials are only forwarded to the same origin.
+    max_redirects: usize,
+    max_response_size: u64,
+    verbose: bool,
+}
+
+/// The agents with the options that all requests share, e.g. the proxy, the timeouts, and the lookup.
+#[derive(Debug, Clone)]
+pub(crate) struct Agents {
+    agent: Agent,
+    /// The agent for the hosts that bypass the proxy, if there is a proxy.
+    direct: Option<Agent>,
+    proxy: Option<Proxy>,
+}
+
+impl UreqClient {
+    /// Extra root certificates and HTTP/2 are not supported, the idle timeout and TCP keepalive are ignored.
+    pub(super) fn new(options: ClientOptions) -> Result<Self, Error> {
+        Ok(Self {
+            // redirects are followed manually
+            agents: Agents::new(&options, 0)?,
+            headers: options.headers.clone(),
+            forward_auth_cross_origin: options.forward_auth_cross_origin,
+            max_redirects: options.max_redirects,
+            max_response_size: options
+                .max_response_size
+                .unwrap_or(DEFAULT_MAX_RESPONSE_SIZE),
+            verbose: options.verbose,
+        })
+    }
+}
+
+impl Agents {
+    /// Agents that follow up to `redirects` redirects themselves.
+    pub(crate) fn new(options: &ClientOptions, redirects: u32) -> Result<Self, Error> {
+        if !options.ca_certs.is_empty() {
+            return Err(ErrorKind::Unsupported("--ca-cert").into());
+        }
+        if options.http2_prior_knowledge {
+            return Err(ErrorKind::Unsupported("--http2-prior-knowledge").into());
+        }
+        let lookup = Arc::new(Lookup {
+            overrides: options.resolve.iter().cloned().collect(),
+            ip_family: options.ip_family,
+        });
+        let builder = || {
+            let lookup = Arc::clone(&lookup);
+            let builder = AgentBuilder::new()
+                .user_agent(&user_agent(options.user_agent.as_deref()))
+                .timeout(options.timeout.unwrap_or(DEFAULT_TIMEOUT))
+                .resolver(move |netloc: &str| lookup.resolve(netloc))
+                .redirects(redirects);
+            let builder = match options.pool_max_idle_per_host {
+                Some(max) => builder.max_idle_connections_per_host(max),
+                None => builder,
+            };
+            match options.connect_timeout {
+                Some(timeout) => builder.timeout_connect(timeout),
+                None => builder,
+            }
+        };
+        let direct = options.proxy.as_ref().map(|_| builder().build());
+        let mut builder = builder();
+        if let Some(proxy) = &options.proxy {
+            let mut url = proxy.url.clone();
+            if let Some((user, pass)) = &proxy.auth {
+                // cannot fail, as proxy URLs have a host
+                let _ = url.set_username(user);
+                let _ = url.set_password(Some(pass));
+            }
+            let proxy =
+                ureq::Proxy::new(url.as_str()).map_err(|e| ErrorKind::BuildClient(Box::new(e)))?;
+            builder = builder.proxy(proxy);
+        }
+        Ok(Self {
+            agent: builder.build(),
+            direct,
+            proxy: options.proxy.clone(),
+        })
+    }
+
+    /// A request through the proxy, unless the host bypasses it.
+    pub(crate) fn request(&self, method: &str, url: &Url) -> Request {
+        let agent = match (&self.proxy, &self.direct) {
+            (Some(proxy), Some(direct)) if !proxy.intercepts(url) => direct,
+            _ => &self.agent,
+        };
+        agent.request_url(method, url)
+    }
+}
+
+#[async_trait]
+impl CrateClient for UreqClient {
+    async fn request(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        let response = self.response(url, options, "GET", coordinates).await?;
+        let limit = self.max_response_size;
+        unblock(move || read_body(response, limit))
+            .await
+            .map_err(|e| ErrorKind::TransportError(Box::new(e)))?
+    }
+
+    async fn request_metadata(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Metadata, ErrorKind> {
+        let response = self.response(url, options, "GET", coordinates).await?;
+        let limit = self.max_response_size;
+        unblock(move || {
+            let mut parser = StreamParser::with_charset(charset(&response));
+            read_chunks(response, limit, |chunk| {
+                parser.feed(chunk).map_err(ErrorKind::parse_body)?;
+                Ok(!parser.is_done())
+            })?;
+            parser.finish().map_err(ErrorKind::parse_body)
+        })
+        .await
+        .map_err(|e| ErrorKind::TransportError(Box::new(e)))?
+    }
+
+    async fn request_bytes(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        let response = self.response(url, options, "GET", coordinates).await?;
+        let limit = options.body_limit(self.max_response_size);
+        unblock(move || {
+            let mut body = Vec::new();
+            read_chunks(response, limit, |chunk| {
+                body.extend_from_slice(chunk);
+                Ok(true)
+            })?;
+            Ok(body)
+        })
+        .await
+        .map_err(|e| ErrorKind::TransportError(Box::new(e)))?
+    }
+
+    /// Sends a `HEAD` request, and falls back to `GET` for servers that do not allow it.
+    async fn request_exists(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<(), ErrorKind> {
+        match self.response(url, options, "HEAD", coordinates).await {
+            Err(ErrorKind::ClientError(405, _)) => self
+                .response(url, options, "GET", coordinates)
+                .await
+                .map(|_| ()),
+            result => result.map(|_| ()),
+        }
+    }
+}
+
+impl UreqClient {
+    /// The successful response, after the anonymous request was challenged if credentials are not preemptive.
+    async fn response(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        method: &str,
+        coordinates: &Coordinates,
+    ) -> Result<Response, ErrorKind> {
+        send_with_challenge(options, |credentials| {
+            self.follow(url, options, method, credentials, coordinates)
+        })
+        .await
+    }
+
+    /// Sends the request and follows its redirects, with credentials only if `credentials` is set.
+    async fn follow(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        method: &str,
+        credentials: bool,
+        coordinates: &Coordinates,
+    ) -> Result<Response, ErrorKind> {
+        let mut target = url.clone();
+        for _ in 0..=self.max_redirects {
+            let same_origin = forward_auth(url, &target, self.forward_auth_cross_origin);
+            let response = self
+                .send(
+                    &target,
+                    options,
+                    method,
+                    same_origin,
+                    credentials && same_origin,
+                )
+                .await?;
+            let location = response
+                .header("location")
+                .and_then(|location| target.join(location).ok());
+            match location {
+                Some(location) if (300..400).contains(&response.status()) => target = location,
+                _ => {
+                    if self.verbose && target != *url {
+                        eprintln!("{} {} -> {}", style("Redirect:").cyan().bold(), url, target);
+                    }
+                    let coordinates = coordinates.clone();
+                    let limit = self.max_response_size;
+                    return unblock(move || check_status(response, &coordinates, limit))
+                        .await
+                        .map_err(|e| ErrorKind::TransportError(Box::new(e)))?;
+                }
+            }
+        }
+        Err(ErrorKind::TooManyRedirects)
+    }
+
+    /// Sends the request, with the headers of `--header` only if `headers` is set,
+    /// and with the credentials and custom headers of the resolver only if `credentials` is set.
+    async fn send(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        method: &str,
+        headers: bool,
+        credentials: bool,
+    ) -> Result<Response, ErrorKind> {
+        let mut request = self.agents.request(method, url);
+        if let Some(timeout) = options.timeout {
+            request = request.timeout(timeout);
+        }
+
+        if headers {
+            for (name, value) in &self.headers {
+                request = request.set(name, value);
+            }
+        }
+
+        if credentials {
+            for (name, value) in &options.headers {
+                request = request.set(name, value);
+            }
+            match &options.auth {
+                Some(Auth::Basic(user, pass)) => {
+                    let credentials = base64::encode(format!("{}:{}", user, pass));
+                    request = request.set("Authorization", &format!("Basic {}", credentials));
+                }
+                Some(Auth::Bearer(source)) => {
+                    let token = source
+                        .token()
+                        .await
+                        .map_err(ErrorKind::AuthenticationFailed)?;
+                    request = request.set("Authorization", &format!("Bearer {}", token));
+                }
+                Some(Auth::Signed(signer)) => {
+                    let headers = signer
+                        .sign(url)
+                        .await
+                        .map_err(ErrorKind::AuthenticationFailed)?;
+                    for (name, value) in headers {
+                        request = request.set(&name, &value);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        unblock(move || call(request))
+            .await
+            .map_err(|e| ErrorKind::TransportError(Box::new(e)))?
+    }
+}
+
+fn call(request: Request) -> Result<Response, ErrorKind> {
+    match request.call() {
+        Ok(response) => Ok(response),
+        // error statuses are checked like the ones of the reqwest client
+        Err(ureq::Error::Status(_, response)) => Ok(response),
+        Err(ureq::Error::Transport(transport)) => Err(transport_error(transport)),
+    }
+}
+
+/// Looks up the addresses of a `host:port`, with the overrides of `--resolve`, and with the system resolver otherwise.
+///
+/// It does not cache anything itself: the DNS cache is [`ClientOptions::cache_dns`], which adds the addresses
+/// of the resolver hosts to the overrides before the client is built.
+/// Only the addresses of the IP family are used, if there is one.
+struct Lookup {
+    overrides: HashMap<String, Vec<SocketAddr>>,
+    ip_family: Option<IpFamily>,
+}
+
+impl Lookup {
+    fn resolve(&self, netloc: &str) -> io::Result<Vec<SocketAddr>> {
+        let overrides = netloc.rsplit_once(':').and_then(|(host, port)| {
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            Some((self.overrides.get(host)?, port.parse::<u16>().ok()?))
+        });
+        let addrs = match overrides {
+            // the overrides have no port, like the ones of the reqwest client
+            Some((addrs, port)) => addrs
+                .iter()
+                .map(|addr| SocketAddr::new(addr.ip(), port))
+                .collect::<Vec<_>>(),
+            None => netloc.to_socket_addrs()?.collect(),
+        };
+        Ok(addrs
+            .into_iter()
+            .filter(|addr| match self.ip_family {
+                Some(IpFamily::V4) => addr.is_ipv4(),
+                Some(IpFamily::V6) => addr.is_ipv6(),
+                None => true,
+            })
+            .collect())
+    }
+}
+
+fn transport_error(transport: ureq::Transport) -> ErrorKind {
+    match transport.kind() {
+        ureq::ErrorKind::InvalidUrl | ureq::ErrorKind::UnknownScheme => {
+            ErrorKind::InvalidRequest(Box::new(transport))
+        }
+        ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed => ErrorKind::ServerNotFound,
+        ureq::ErrorKind::Io if is_timeout(&transport) => ErrorKind::ServerNotAvailable,
+        _ => ErrorKind::TransportError(Box::new(transport)),
+    }
+}
+
+fn is_timeout(transport: &ureq::Transport) -> bool {
+    std::error::Error::source(transport)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .is_some_and(|e| {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            )
+        })
+}
+
+/// Turns error responses into errors, the body of successful responses is read by the caller.
+fn check_status(
+    response: Response,
+    coordinates: &Coordinates,
+    limit: u64,
+) -> Result<Response, ErrorKind> {
+    let status = response.status();
+    if status == 404 {
+        return Err(ErrorKind::CoordinatesNotFound(coordinates.clone()));
+    }
+    if status == 429 {
+        let retry_after = response
+            .header("retry-after")
+            .and_then(|value| retry_after(value, SystemTime::now()));
+        return Err(ErrorKind::TooManyRequests(retry_after));
+    }
+    if (400..500).contains(&status) {
+        return Err(ErrorKind::ClientError(status, read_body(response, limit)?));
+    }
+    if status >= 500 {
+        return Err(ErrorKind::ServerError(status, read_body(response, limit)?));
+    }
+    Ok(response)
+}
+
+/// Decodes the body with the charset of the `Content-Type`, or as UTF-8.
+fn read_body(response: Response, limit: u64) -> Result<String, ErrorKind> {
+    let encoding = charset(&response)
+        .and_then(|charset| Encoding::for_label(charset.as_bytes()))
+        .unwrap_or(UTF_8);
+    let mut body = Vec::new();
+    read_chunks(response, limit, |chunk| {
+        body.extend_from_slice(chunk);
+        Ok(true)
+    })?;
+    Ok(encoding.decode(&body).0.into_owned())
+}
+
+/// Reads the body in chunks, so that a huge response fails before it is buffered completely.
+///
+/// Reading stops early when `consume` returns `false`.
+fn read_chunks(
+    response: Response,
+    limit: u64,
+    mut consume: impl FnMut(&[u8]) -> Result<bool, ErrorKind>,
+) -> Result<(), ErrorKind> {
+    if response
+        .header("content-length")
+        .and_then(|length| length.parse::<u64>().ok())
+        .is_some_and(|length| length > limit)
+    {
+        return Err(ErrorKind::ResponseTooLarge(limit));
+    }
+    let status = response.status();
+    let mut reader = response.into_reader();
+    let mut chunk = [0; 16 * 1024];
+    let mut read = 0_u64;
+    loop {
+        let len = reader
+            .read(&mut chunk)
+            .map_err(|error| ErrorKind::ReadBodyError(status, Box::new(error)))?;
+        if len == 0 {
+            return Ok(());
+        }
+        read += len as u64;
+        if read > limit {
+            return Err(ErrorKind::ResponseTooLarge(limit));
+        }
+        if !consume(&chunk[..len])? {
+            return Ok(());
+        }
+    }
+}
+
+/// The `charset` parameter of the `Content-Type`, e.g. `text/xml; charset=ISO-8859-1`.
+fn charset(response: &Response) -> Option<&str> {
+    content_type_charset(response.header("content-type")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use test_case::test_case;
+
+    #[test_case("repo.example.com:443", None => vec!["10.0.0.1:443", "[fd00::1]:443"]; "override address")]
+    #[test_case("repo.example.com:8081", None => vec!["10.0.0.1:8081", "[fd00::1]:8081"]; "override on another port")]
+    #[test_case("repo.example.com:8443", Some(IpFamily::V4) => vec!["10.0.0.1:8443"]; "override with ipv4")]
+    #[test_case("repo.example.com:443", Some(IpFamily::V6) => vec!["[fd00::1]:443"]; "override with ipv6")]
+    #[test_case("127.0.0.1:80", None => vec!["127.0.0.1:80"]; "no override")]
+    #[test_case("127.0.0.1:80", Some(IpFamily::V6) => Vec::<String>::new(); "no address of the family")]
+    fn test_lookup(netloc: &str, ip_family: Option<IpFamily>) -> Vec<String> {
+        let lookup = Lookup {
+            overrides: HashMap::from([(
+                String::from("repo.example.com"),
+                vec![
+                    SocketAddr::new(Ipv4Addr::new(10, 0, 0, 1).into(), 0),
+                    SocketAddr::new(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1).into(), 0),
+                ],
+            )]),
+            ip_family,
+        };
+        let addrs = lookup.resolve(netloc).unwrap();
+        addrs.iter().map(ToString::to_string).collect()
+    }
+
+    #[test_case(ClientOptions { ca_certs: vec!["ca.pem".into()], ..ClientOptions::default() } => "--ca-cert"; "ca certs")]
+    #[test_case(ClientOptions { http2_prior_knowledge: true, ..ClientOptions::default() } => "--http2-prior-knowledge"; "http2")]
+    fn test_unsupported(options: ClientOptions) -> &'static str {
+        match UreqClient::new(options).map_err(|e| e.error) {
+            Err(ErrorKind::Unsupported(option)) => option,
+            _ => panic!("the option is supported"),
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{
+    config::{ConfigFile, ResolverAlias},
+    http::ApiClient,
+    presets,
+    regex::Regex,
+    resolvers::EXEC_PREFIX,
+    Server, UrlResolver, VersionCheck,
+};
+use console::style;
+use std::{fmt::Display, path::Path};
+use toml::Value;
+
+/// A problem of the config file, at the line of the value that causes it, if it can be found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Problem {
+    /// The line, starting at 1.
+    pub(crate) line: Option<usize>,
+    pub(crate) message: String,
+}
+
+/// Finds the problems of the config file that would otherwise only fail a run, or go unnoticed.
+///
+/// The values that the config file parser rejects one at a time are checked first, so that all of them are reported.
+/// Then the resolvers are expanded like for a run, and their credential sources are looked up.
+/// No requests are sent, the client is only given to the credentials of the presets.
+pub(crate) fn validate(content: &str, http: &ApiClient) -> Vec<Problem> {
+    let value = match content.parse::<Value>() {
+        Ok(value) => value,
+        Err(e) => return vec![parse_problem(&e)],
+    };
+    let lines = Lines(content);
+    let mut problems = Vec::new();
+    raw_values(&value, &lines, &mut problems);
+
+    let config = match ConfigFile::parse(content) {
+        Ok(config) => config,
+        Err(e) => {
+            // the rejected value is most likely one of the reported ones
+            if problems.is_empty() {
+                let mut problem = parse_problem(&e);
+                // the parser reports unknown keys at their table
+                problem.line = unknown_key(&e)
+                    .and_then(|key| lines.key(&key))
+                    .or(problem.line);
+                problems.push(problem);
+            }
+            return problems;
+        }
+    };
+
+    for (name, alias) in &config.resolvers {
+        let server = Server {
+            id: None,
+            url: name.clone(),
+            auth: None,
+        };
+        if let Some(snapshots) = alias.apply_snapshots(&server) {
+            check_url(&snapshots, http, &lines, &mut problems);
+        }
+        check_url(&alias.apply(server), http, &lines, &mut problems);
+        credentials(name, alias, &lines, &mut problems);
+    }
+
+    for (name, profile) in &config.profiles {
+        if let Err(e) = config.profile(name) {
+            let line = profile
+                .extends
+                .as_deref()
+                .and_then(|extends| lines.line(extends));
+            problems.push(Problem {
+                line,
+                message: e.to_string(),
+            });
+        }
+    }
+
+    let references = config
+        .run
+        .resolvers
+        .iter()
+        .chain(
+            config
+                .profiles
+                .values()
+                .filter_map(|p| p.resolvers.as_ref())
+                .flatten(),
+        )
+        .chain(config.routes.values())
+        .chain(
+            config
+                .coordinates
+                .iter()
+                .filter_map(|(_, c)| c.resolver.as_ref()),
+        );
+    for reference in references {
+        if !config.resolvers.contains_key(reference) {
+            let server = Server {
+                id: None,
+                url: reference.clone(),
+                auth: None,
+            };
+            check_url(&server, http, &lines, &mut problems);
+        }
+    }
+
+    problems.sort_by_key(|p| p.line);
+    problems
+}
+
+/// The checks, patterns, and versions, which the parser would reject at the first invalid one.
+fn raw_values(value: &Value, lines: &Lines<'_>, problems: &mut Vec<Problem>) {
+    let profiles = value
+        .get("profiles")
+        .and_then(Value::as_table)
+        .into_iter()
+        .flat_map(|table| table.values());
+    let checks = value
+        .get("run")
+        .into_iter()
+        .chain(profiles)
+        .flat_map(|table| strings(table.get("checks")));
+    for check in checks {
+        if let Err(e) = check.parse::<VersionCheck>() {
+            problems.push(lines.problem(check, e));
+        }
+    }
+
+    let coordinates = value
+        .get("coordinates")
+        .and_then(Value::as_table)
+        .into_iter()
+        .flat_map(|table| table.values())
+        .collect::<Vec<_>>();
+    let patterns = value
+        .get("versions")
+        .into_iter()
+        .chain(coordinates.iter().copied())
+        .flat_map(|table| strings(table.get("ignore_versions")));
+    for pattern in patterns {
+        if let Err(e) = Regex::new(pattern) {
+            problems.push(lines.problem(pattern, format!("Invalid pattern `{}`: {}", pattern, e)));
+        }
+    }
+
+    let min_versions = coordinates
+        .iter()
+        .filter_map(|table| table.get("min_version")?.as_str());
+    for version in min_versions {
+        if lenient_semver::parse(version).is_err() {
+            problems.push(lines.problem(version, format!("Invalid min_version `{}`", version)));
+        }
+    }
+}
+
+/// The resolver is expanded and created like for a run, without a local repository.
+fn check_url(server: &Server, http: &ApiClient, lines: &Lines<'_>, problems: &mut Vec<Problem>) {
+    let needle = server
+        .url
+        .strip_prefix("artifactory:")
+        .unwrap_or(&server.url);
+    if let Some(program) = server.url.strip_prefix(EXEC_PREFIX) {
+        if let Err(e) = UrlResolver::exec(program, None) {
+            problems.push(lines.problem(needle, e));
+        }
+        return;
+    }
+    match presets::expand(server.clone(), Some(Path::new("/")), http) {
+        Ok(server) => {
+            if let Err(e) = UrlResolver::new(server.url, None) {
+                problems.push(lines.problem(needle, e));
+            }
+        }
+        Err(e) => problems.push(lines.problem(needle, e)),
+    }
+}
+
+fn credentials(name: &str, alias: &ResolverAlias, lines: &Lines<'_>, problems: &mut Vec<Problem>) {
+    if let Some(var) = alias.password_env() {
+        if std::env::var_os(var).is_none() {
+            problems.push(lines.problem(
+                var,
+                format!(
+                    "The password of {} is read from {}, which is not set",
+                    style(name).cyan(),
+                    style(var).red().bold()
+                ),
+            ));
+        }
+    }
+    if alias.negotiate() && !cfg!(feature = "kerberos") {
+        problems.push(lines.problem(
+            "negotiate",
+            format!(
+                "{} authenticates with Kerberos, which needs the `kerberos` feature",
+                style(name).cyan()
+            ),
+        ));
+    }
+}
+
+fn strings(value: Option<&Value>) -> impl Iterator<Item = &str> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+}
+
+fn unknown_key(e: &toml::de::Error) -> Option<String> {
+    let message = e.to_string();
+    let key = message.strip_prefix("unknown field `")?.split('`').next()?;
+    Some(String::from(key))
+}
+
+fn parse_problem(e: &toml::de::Error) -> Problem {
+    Problem {
+        line: e.line_col().map(|(line, _)| line + 1),
+        message: e.to_string(),
+    }
+}
+
+/// Finds the line of a value in the content of the file.
+struct Lines<'a>(&'a str);
+
+impl Lines<'_> {
+    /// The first line that contains the quoted value, or else the value itself.
+    fn line(&self, value: &str) -> Option<usize> {
+        let quoted = [format!("\"{}\"", value), format!("'{}'", value)];
+        let find = |needle: &str| self.0.lines().position(|line| line.contains(needle));
+        quoted
+            .iter()
+            .find_map(|quoted| find(quoted))
+            .or_else(|| find(value))
+            .map(|line| line + 1)
+    }
+
+    /// The first line that assigns the key.
+    fn key(&self, key: &str) -> Option<usize> {
+        self.0
+            .lines()
+            .position(|line| {
+                line.trim_start()
+                    .strip_prefix(key)
+                    .is_some_and(|rest| rest.trim_start().starts_with('='))
+            })
+            .map(|line| line + 1)
+    }
+
+    fn problem(&self, value: &str, message: impl Display) -> Problem {
+        Problem {
+            line: self.line(value),
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolvers::ClientOptions;
+    use test_case::test_case;
+
+    fn validate(content: &str) -> Vec<Problem> {
+        super::validate(content, &ApiClient::new(&ClientOptions::default()).unwrap())
+    }
+
+    fn lines(content: &str) -> Vec<Option<usize>> {
+        console::set_colors_enabled(false);
+        validate(content).into_iter().map(|p| p.line).collect()
+    }
+
+    #[test]
+    fn test_valid() {
+        let problems = validate(
+            r#"
+            [run]
+            resolvers = ["internal", "clojars"]
+            checks = ["org.neo4j:neo4j:^4", "com.mycorp:app"]
+
+            [routes]
+            "com.mycorp" = "internal"
+
+            [resolvers.internal]
+            url = "https://nexus.mycorp.com/repository/maven"
+            type = "artifactory"
+            username = "alice"
+            password = "s3cure"
+            "#,
+        );
+        assert_eq!(problems, Vec::new());
+    }
+
+    #[test]
+    fn test_all_invalid_values() {
+        let content = r#"
+            [run]
+            checks = ["org.neo4j:", "com.mycorp:app", ":app"]
+
+            [versions]
+            ignore_versions = ["("]
+
+            [coordinates."foo:bar"]
+            min_version = "latest"
+            "#;
+        assert_eq!(lines(content), vec![Some(3), Some(3), Some(6), Some(9)]);
+    }
+
+    #[test]
+    fn test_resolvers() {
+        let content = r#"
+            [run]
+            resolvers = ["internal", "s3://"]
+
+            [routes]
+            "com.mycorp" = "not a url"
+
+            [resolvers.internal]
+            url = "https://nexus.mycorp.com/repository/maven"
+            username = "alice"
+            password_env = "LMV_TEST_VALIDATE_UNSET"
+            "#;
+        let problems = validate(content);
+        assert_eq!(
+            problems.iter().map(|p| p.line).collect::<Vec<_>>(),
+            vec![Some(3), Some(6), Some(11)]
+        );
+        assert!(problems[2].message.contains("LMV_TEST_VALIDATE_UNSET"));
+    }
+
+    #[test]
+    fn test_profiles() {
+        let content = r#"
+            [profiles.base]
+            resolvers = ["internal", "not a url"]
+
+            [profiles.releases]
+            extends = "base"
+            checks = ["org.neo4j:neo4j"]
+
+            [profiles.nightly]
+            extends = "snapshots"
+
+            [resolvers.internal]
+            url = "https://nexus.mycorp.com/repository/maven"
+            "#;
+        assert_eq!(lines(content), vec![Some(3), Some(10)]);
+    }
+
+    #[test_case("[run\nchecks = []" => vec![Some(1)]; "syntax")]
+    #[test_case("\n\nfoo = 42" => vec![Some(3)]; "unknown key")]
+    #[test_case("[run]\nchecks = 'foo:bar'" => vec![Some(2)]; "wrong type")]
+    fn test_parse_errors(content: &str) -> Vec<Option<usize>> {
+        lines(content)
+    }
+}
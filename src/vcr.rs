@@ -0,0 +1,302 @@
+use crate::{
+    metadata::{self, Metadata},
+    resolvers::{Client, ErrorKind, RequestOptions},
+    Coordinates,
+};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// Records the responses of the repositories to fixture files, or replays them without network access.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Vcr {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// What was requested, the same URL is recorded separately for each of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Text,
+    Bytes,
+    Exists,
+}
+
+/// Records or replays the HTTP requests of the wrapped client.
+///
+/// Every fixture is a JSON file that is named after the hash of the kind and the URL of the request,
+/// with the status and the body of the response. Not found and other error responses are recorded as well,
+/// connection errors and timeouts are not. Local files are always read again.
+pub(crate) struct VcrClient<C> {
+    client: C,
+    vcr: Option<Vcr>,
+}
+
+impl<C> VcrClient<C> {
+    pub(crate) fn new(client: C, vcr: Option<Vcr>) -> Self {
+        Self { client, vcr }
+    }
+}
+
+impl<C: Client> VcrClient<C> {
+    async fn send(
+        &self,
+        kind: Kind,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        let vcr = match &self.vcr {
+            Some(vcr) if matches!(url.scheme(), "http" | "https") => vcr,
+            _ => return self.forward(kind, url, options, coordinates).await,
+        };
+        match vcr {
+            Vcr::Record(dir) => {
+                let result = self.forward(kind, url, options, coordinates).await;
+                if let Some(fixture) = fixture(kind, url, result.as_ref().map(Vec::as_slice)) {
+                    // a failure to record the response does not fail the request
+                    let _ = write(&path(dir, kind, url), &fixture).await;
+                }
+                result
+            }
+            Vcr::Replay(dir) => {
+                let path = path(dir, kind, url);
+                let fixture = read(&path).await.map_err(|e| {
+                    ErrorKind::TransportError(
+                        format!("No recorded response in {}: {}", path.display(), e).into(),
+                    )
+                })?;
+                replay(kind, &fixture, coordinates)
+            }
+        }
+    }
+
+    /// Sends the request of this kind with the wrapped client.
+    async fn forward(
+        &self,
+        kind: Kind,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        match kind {
+            Kind::Text => self
+                .client
+                .request(url, options, coordinates)
+                .await
+                .map(String::into_bytes),
+            Kind::Bytes => self.client.request_bytes(url, options, coordinates).await,
+            Kind::Exists => self
+                .client
+                .request_exists(url, options, coordinates)
+                .await
+                .map(|()| Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: Client> Client for VcrClient<C> {
+    async fn request(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<String, ErrorKind> {
+        let body = self.send(Kind::Text, url, options, coordinates).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Streamed metadata is recorded as a minimal metadata document, like for the cache.
+    async fn request_metadata(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Metadata, ErrorKind> {
+        match &self.vcr {
+            Some(Vcr::Record(dir)) if matches!(url.scheme(), "http" | "https") => {
+                let result = self
+                    .client
+                    .request_metadata(url, options, coordinates)
+                    .await;
+                let document = result.as_ref().map(metadata::document);
+                let recorded = match &document {
+                    Ok(document) => Ok(document.as_bytes()),
+                    Err(e) => Err(*e),
+                };
+                if let Some(fixture) = fixture(Kind::Text, url, recorded) {
+                    let _ = write(&path(dir, Kind::Text, url), &fixture).await;
+                }
+                result
+            }
+            Some(Vcr::Replay(_)) if matches!(url.scheme(), "http" | "https") => {
+                let body = self.send(Kind::Text, url, options, coordinates).await?;
+                metadata::parse_bytes(&body).map_err(ErrorKind::parse_body)
+            }
+            _ => {
+                self.client
+                    .request_metadata(url, options, coordinates)
+                    .await
+            }
+        }
+    }
+
+    async fn request_bytes(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        self.send(Kind::Bytes, url, options, coordinates).await
+    }
+
+    async fn request_exists(
+        &self,
+        url: &Url,
+        options: &RequestOptions,
+        coordinates: &Coordinates,
+    ) -> Result<(), ErrorKind> {
+        self.send(Kind::Exists, url, options, coordinates)
+            .await
+            .map(|_| ())
+    }
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Text => "text",
+            Kind::Bytes => "bytes",
+            Kind::Exists => "exists",
+        }
+    }
+}
+
+fn path(dir: &Path, kind: Kind, url: &Url) -> PathBuf {
+    let key = format!("{} {}", kind.name(), url);
+    let digest = ring::digest::digest(&ring::digest::SHA256, key.as_bytes());
+    let name = digest
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    dir.join(name).with_extension("json")
+}
+
+/// The fixture of a response, unless the request failed before there was one.
+///
+/// Binary bodies are base64 encoded.
+fn fixture(kind: Kind, url: &Url, result: Result<&[u8], &ErrorKind>) -> Option<Value> {
+    let (status, body) = match result {
+        Ok(body) if kind == Kind::Bytes => {
+            return Some(json!({
+                "url": url.as_str(),
+                "status": 200,
+                "body": base64::encode(body),
+                "encoding": "base64",
+            }))
+        }
+        Ok(body) => (200, String::from_utf8_lossy(body).into_owned()),
+        Err(ErrorKind::CoordinatesNotFound(_)) => (404, String::new()),
+        Err(ErrorKind::TooManyRequests(_)) => (429, String::new()),
+        Err(ErrorKind::ClientError(status, body)) | Err(ErrorKind::ServerError(status, body)) => {
+            (*status, body.clone())
+        }
+        Err(_) => return None,
+    };
+    Some(json!({ "url": url.as_str(), "status": status, "body": body }))
+}
+
+/// The recorded response, with error statuses turned into the errors of the clients.
+fn replay(kind: Kind, fixture: &Value, coordinates: &Coordinates) -> Result<Vec<u8>, ErrorKind> {
+    let status = fixture["status"].as_u64().unwrap_or(200) as u16;
+    let body = fixture["body"].as_str().unwrap_or_default();
+    match status {
+        404 => Err(ErrorKind::CoordinatesNotFound(coordinates.clone())),
+        429 => Err(ErrorKind::TooManyRequests(None)),
+        400..=499 => Err(ErrorKind::ClientError(status, String::from(body))),
+        500..=599 => Err(ErrorKind::ServerError(status, String::from(body))),
+        _ if kind == Kind::Bytes && fixture["encoding"] == "base64" => {
+            base64::decode(body).map_err(|e| ErrorKind::ReadBodyError(status, Box::new(e)))
+        }
+        _ => Ok(body.as_bytes().to_vec()),
+    }
+}
+
+async fn read(path: &Path) -> io::Result<Value> {
+    let content = tokio::fs::read(path).await?;
+    Ok(serde_json::from_slice(&content)?)
+}
+
+async fn write(path: &Path, fixture: &Value) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let content = serde_json::to_vec_pretty(fixture)?;
+    tokio::fs::write(path, content).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{request, CountingClient};
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let dir = crate::temp::private_dir().await.unwrap();
+        let record = VcrClient::new(
+            CountingClient::missing("missing"),
+            Some(Vcr::Record(dir.clone())),
+        );
+        assert_eq!(
+            request(&record, "https://repo/a").await,
+            Ok(String::from("https://repo/a #1"))
+        );
+        assert_eq!(
+            request(&record, "https://repo/missing").await,
+            Err(String::from("not found"))
+        );
+
+        let client = CountingClient::default();
+        let replay = VcrClient::new(&client, Some(Vcr::Replay(dir.clone())));
+        assert_eq!(
+            request(&replay, "https://repo/a").await,
+            Ok(String::from("https://repo/a #1"))
+        );
+        assert_eq!(
+            request(&replay, "https://repo/missing").await,
+            Err(String::from("not found"))
+        );
+        assert!(request(&replay, "https://repo/b")
+            .await
+            .unwrap_err()
+            .contains("No recorded response"));
+        assert_eq!(client.requests(), 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_bytes() {
+        let dir = crate::temp::private_dir().await.unwrap();
+        let url = Url::parse("https://repo/a.jar").unwrap();
+        let options = RequestOptions::default();
+        let coordinates = Coordinates::new("com.foo", "bar");
+        let record = VcrClient::new(CountingClient::default(), Some(Vcr::Record(dir.clone())));
+        let recorded = record
+            .request_bytes(&url, &options, &coordinates)
+            .await
+            .unwrap();
+        let replay = VcrClient::new(CountingClient::default(), Some(Vcr::Replay(dir.clone())));
+        let replayed = replay
+            .request_bytes(&url, &options, &coordinates)
+            .await
+            .unwrap();
+        assert_eq!(replayed, recorded);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
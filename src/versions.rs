@@ -1,70 +1,562 @@
+use crate::{date::utc_time, maven_version::MavenVersion, metadata::Metadata, regex::Regex};
 use itertools::Itertools;
-use semver::{Version, VersionReq};
-use std::iter::FromIterator;
+use semver::{Prerelease, Version, VersionReq};
+use serde::Deserialize;
+#[cfg(feature = "runtime")]
+use std::time::Duration;
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{self, Display},
+    iter::FromIterator,
+    time::SystemTime,
+};
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
-pub(crate) struct Versions {
+pub struct Versions {
     version: Vec<String>,
+    tags: Tags,
+    /// When the versions were published, if the resolver knows it.
+    published: HashMap<String, SystemTime>,
+}
+
+/// How `-SNAPSHOT` versions are matched.
+///
+/// Maven treats snapshots as builds in progress rather than as releases,
+/// so they can be matched independently of other pre releases.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Snapshots {
+    /// Snapshots are matched like any other pre release.
+    #[default]
+    AsPreRelease,
+    /// Snapshots are matched, even when pre releases are not.
+    // only set by the options of the binary
+    #[cfg_attr(not(feature = "runtime"), allow(dead_code))]
+    Include,
+    /// Snapshots are never matched, even when pre releases are.
+    Exclude,
+}
+
+impl Snapshots {
+    /// Whether snapshots can be matched at all.
+    pub(crate) fn are_matched(self, allow_pre_release: bool) -> bool {
+        match self {
+            Snapshots::AsPreRelease => allow_pre_release,
+            Snapshots::Include => true,
+            Snapshots::Exclude => false,
+        }
+    }
+}
+
+/// How versions are ordered to find the latest one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum VersionOrder {
+    /// Semantic versioning, where any qualifier is a pre release.
+    #[default]
+    Semver,
+    /// Maven's ordering, where e.g. `1.0.0.Final` is a release and `1.0.0.CR1` is not.
+    Maven,
+}
+
+/// Which versions are considered, and how they are ordered.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Selection {
+    pub(crate) include_pre_releases: bool,
+    pub(crate) snapshots: Snapshots,
+    pub(crate) order: VersionOrder,
+    /// Versions with any of these qualifiers are never considered, ignoring case.
+    pub(crate) exclude_qualifiers: Vec<String>,
+    /// Versions that match any of these patterns are never considered, e.g. withdrawn releases.
+    pub(crate) ignore_versions: Vec<Regex>,
+    /// Versions that are older than this version are never considered.
+    pub(crate) min_version: Option<String>,
+    /// Only versions that were published after this time are considered.
+    pub(crate) released_after: Option<SystemTime>,
+    /// Only versions that were published before this time are considered.
+    pub(crate) released_before: Option<SystemTime>,
+}
+
+impl Selection {
+    /// Whether the version has an excluded qualifier, e.g. `alpha` for `1.0.0-alpha01` or `1.0.0.Alpha2`,
+    /// or matches an ignored pattern.
+    fn is_excluded(&self, version: &str) -> bool {
+        let excluded_qualifier = qualifiers(version).any(|qualifier| {
+            self.exclude_qualifiers
+                .iter()
+                .any(|excluded| excluded.eq_ignore_ascii_case(qualifier))
+        });
+        excluded_qualifier
+            || self
+                .ignore_versions
+                .iter()
+                .any(|regex| regex.is_match(version))
+    }
+
+    /// Whether the version is older than the minimum version, in the order of the selection.
+    fn is_below_min_version(&self, version: &Version, text: &str) -> bool {
+        let min_version = match self.min_version.as_deref() {
+            Some(min_version) => min_version,
+            None => return false,
+        };
+        match self.order {
+            VersionOrder::Semver => lenient_semver::parse(min_version)
+                .is_ok_and(|min| cmp_versions(version, &min) == Ordering::Less),
+            VersionOrder::Maven => MavenVersion::from(text) < MavenVersion::from(min_version),
+        }
+    }
+
+    /// Whether the selection filters by publication dates, which have to be requested separately.
+    pub(crate) fn needs_publication_dates(&self) -> bool {
+        self.released_after.is_some() || self.released_before.is_some()
+    }
+
+    /// Whether the version was published in the window of the selection.
+    ///
+    /// Versions without a known date are outside of any window.
+    fn is_published_in_window(&self, published: Option<&SystemTime>) -> bool {
+        if !self.needs_publication_dates() {
+            return true;
+        }
+        published.is_some_and(|published| {
+            self.released_after.is_none_or(|after| *published >= after)
+                && self
+                    .released_before
+                    .is_none_or(|before| *published <= before)
+        })
+    }
+}
+
+/// The `<latest>`, `<release>`, and `<lastUpdated>` tags of the metadata, if the resolver has them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Tags {
+    pub(crate) latest: Option<String>,
+    pub(crate) release: Option<String>,
+    pub(crate) last_updated: Option<String>,
+}
+
+/// A tag of the metadata, and the newest version that it should name.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TagCheck {
+    pub(crate) tag: &'static str,
+    pub(crate) value: String,
+    pub(crate) newest: Option<PublishedVersion>,
+}
+
+/// A requirement for the versions, e.g. `^1` or `~1.1 || ~1.3`, and the versions that it excludes, e.g. `!=1.4.2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    /// A version matches if it matches any of the alternatives.
+    pub(crate) alternatives: Vec<VersionReq>,
+    /// These versions never match, whichever alternative they match.
+    pub(crate) excluded: Vec<PublishedVersion>,
+}
+
+impl Requirement {
+    pub(crate) fn star() -> Self {
+        Self::from(VersionReq::STAR)
+    }
+
+    /// Whether the version matches and is not excluded.
+    ///
+    /// If pre releases are allowed, a pre release is matched like its release, e.g. `1.1.0-alpha01` by `^1.1`.
+    fn matches(&self, version: &Version, allow_pre_release: bool) -> bool {
+        let release;
+        let matched = if allow_pre_release {
+            release = Version::new(version.major, version.minor, version.patch);
+            &release
+        } else {
+            version
+        };
+        self.alternatives.iter().any(|req| req.matches(matched))
+            && !self
+                .excluded
+                .iter()
+                .any(|excluded| excluded.version == *version)
+    }
+}
+
+impl From<VersionReq> for Requirement {
+    fn from(req: VersionReq) -> Self {
+        Self {
+            alternatives: vec![req],
+            excluded: Vec::new(),
+        }
+    }
+}
+
+impl Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, req) in self.alternatives.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(" || ")?;
+            }
+            write!(f, "{}", req)?;
+        }
+        for excluded in &self.excluded {
+            write!(f, ", !={}", excluded)?;
+        }
+        Ok(())
+    }
+}
+
+/// A version as it is published, e.g. `2.4.0.Final`, and its lenient semver form to compare it.
+///
+/// The published version is printed, so that it can be used in a pom as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishedVersion {
+    pub(crate) version: Version,
+    pub(crate) original: String,
+}
+
+impl PublishedVersion {
+    pub(crate) fn parse(original: &str) -> Option<Self> {
+        let version = lenient_semver::parse(original).ok()?;
+        Some(Self {
+            version,
+            original: String::from(original),
+        })
+    }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn is_snapshot(&self) -> bool {
+        is_snapshot(&self.original)
+    }
+
+    /// How big the update to the other version is, or `None` if it is not newer in the given order.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn bump_to(&self, newer: &PublishedVersion, order: VersionOrder) -> Option<Bump> {
+        if newer.cmp_in(self, order) != Ordering::Greater {
+            None
+        } else if newer.version.major != self.version.major {
+            Some(Bump::Major)
+        } else if newer.version.minor != self.version.minor {
+            Some(Bump::Minor)
+        } else if newer.version.patch != self.version.patch {
+            Some(Bump::Patch)
+        } else {
+            Some(Bump::PreRelease)
+        }
+    }
+
+    /// Whether the version is a pre release in the given order, e.g. `1.0.0-rc1`, or `1.0.0.CR1` in the Maven order.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn is_pre_release(&self, order: VersionOrder) -> bool {
+        match order {
+            VersionOrder::Semver => !self.version.pre.is_empty(),
+            VersionOrder::Maven => MavenVersion::from(self.original.as_str()).is_pre_release(),
+        }
+    }
+
+    #[cfg(feature = "runtime")]
+    fn cmp_in(&self, other: &PublishedVersion, order: VersionOrder) -> Ordering {
+        match order {
+            VersionOrder::Semver => cmp_versions(&self.version, &other.version),
+            VersionOrder::Maven => MavenVersion::from(self.original.as_str())
+                .cmp(&MavenVersion::from(other.original.as_str())),
+        }
+    }
+}
+
+/// How big an update is, ordered from the smallest to the biggest.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Bump {
+    /// Only the pre release or qualifier changes, e.g. from `1.0.0-rc1` to `1.0.0`.
+    PreRelease,
+    Patch,
+    Minor,
+    Major,
+}
+
+#[cfg(feature = "runtime")]
+impl Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Bump::PreRelease => "pre-release",
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        })
+    }
+}
+
+impl Display for PublishedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.original)
+    }
 }
 
 impl FromIterator<String> for Versions {
     fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
         let version = iter.into_iter().collect();
-        Versions { version }
+        Versions {
+            version,
+            tags: Tags::default(),
+            published: HashMap::new(),
+        }
     }
 }
 
 impl<'a> FromIterator<&'a str> for Versions {
     fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
         let version = iter.into_iter().map(String::from).collect();
-        Versions { version }
+        Versions {
+            version,
+            tags: Tags::default(),
+            published: HashMap::new(),
+        }
+    }
+}
+
+impl From<Metadata> for Versions {
+    fn from(metadata: Metadata) -> Self {
+        Versions {
+            version: metadata.versions,
+            tags: Tags {
+                latest: metadata.latest,
+                release: metadata.release,
+                last_updated: metadata.last_updated,
+            },
+            published: HashMap::new(),
+        }
     }
 }
 
 impl Versions {
+    /// Combines the versions of both sides, dropping duplicates.
+    pub(crate) fn union(mut self, other: Versions) -> Self {
+        for version in other.version {
+            if !self.version.contains(&version) {
+                self.version.push(version);
+            }
+        }
+        self.tags = self.tags.union(other.tags);
+        for (version, published) in other.published {
+            self.published.entry(version).or_insert(published);
+        }
+        self
+    }
+
+    /// When the version was published, if the resolver knows it.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn published(&self, version: &str) -> Option<SystemTime> {
+        self.published.get(version).copied()
+    }
+
+    /// Adds when the versions were published, replacing already known dates.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn with_publication_dates(mut self, dates: HashMap<String, SystemTime>) -> Self {
+        self.published.extend(dates);
+        self
+    }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn tags(&self) -> &Tags {
+        &self.tags
+    }
+
+    /// Compares the `<latest>` and `<release>` tags to the newest versions,
+    /// with and without snapshots respectively.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn check_tags(&self) -> Vec<TagCheck> {
+        let checks = [
+            ("latest", &self.tags.latest, true),
+            ("release", &self.tags.release, false),
+        ];
+        checks
+            .iter()
+            .filter_map(|&(tag, value, include_snapshots)| {
+                Some(TagCheck {
+                    tag,
+                    value: value.clone()?,
+                    newest: self.newest(include_snapshots),
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "runtime")]
+    fn newest(&self, include_snapshots: bool) -> Option<PublishedVersion> {
+        self.version
+            .iter()
+            .filter(|v| include_snapshots || !is_snapshot(v))
+            .filter_map(|v| PublishedVersion::parse(v))
+            .max_by(|lhs, rhs| cmp_versions(&lhs.version, &rhs.version))
+    }
+
+    /// The versions that cannot be parsed and are never matched, unless they are excluded anyway.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn unparseable(&self, selection: &Selection) -> Vec<&str> {
+        self.version
+            .iter()
+            .filter(|v| !selection.is_excluded(v) && lenient_semver::parse(v.as_str()).is_err())
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.version.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.version.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.version.iter().map(String::as_str)
+    }
+
+    pub fn contains(&self, version: &str) -> bool {
+        self.version.iter().any(|v| v == version)
+    }
+
     pub(crate) fn latest_versions(
         &self,
-        allow_pre_release: bool,
-        mut requirements: Vec<VersionReq>,
-    ) -> Vec<(VersionReq, Option<Version>)> {
+        selection: &Selection,
+        mut requirements: Vec<Requirement>,
+    ) -> Vec<(Requirement, Option<PublishedVersion>)> {
         if requirements.is_empty() {
-            requirements.push(VersionReq::STAR);
+            requirements.push(Requirement::star());
         }
-        let latest = self.find_latest_versions(&requirements[..], allow_pre_release);
-        requirements.into_iter().zip(latest.into_iter()).collect()
+        let latest = self.find_latest_versions(&requirements[..], selection);
+        requirements.into_iter().zip(latest).collect()
+    }
+
+    /// The newest version that the selection considers, regardless of requirements and the publication window,
+    /// e.g. to tell whether the artifact is still maintained.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn newest_selected(&self, selection: &Selection) -> Option<PublishedVersion> {
+        let selection = Selection {
+            released_after: None,
+            released_before: None,
+            ..selection.clone()
+        };
+        self.find_latest_versions(&[Requirement::star()], &selection)
+            .remove(0)
+    }
+
+    /// All versions that match any of the requirements, or all versions without requirements, newest first.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn matching(
+        &self,
+        requirements: &[Requirement],
+        selection: &Selection,
+    ) -> Vec<PublishedVersion> {
+        let star = [Requirement::star()];
+        let requirements = if requirements.is_empty() {
+            &star[..]
+        } else {
+            requirements
+        };
+        let mut matching = self
+            .candidates(selection)
+            .filter(|(version, _, allow_pre_release)| {
+                requirements
+                    .iter()
+                    .any(|r| r.matches(version, *allow_pre_release))
+            })
+            .map(|(version, original, _)| PublishedVersion {
+                version,
+                original: String::from(original),
+            })
+            .collect::<Vec<_>>();
+        matching.sort_by(|lhs, rhs| rhs.cmp_in(lhs, selection.order));
+        matching
+    }
+
+    /// The versions that are newer than the current and older than the latest version, oldest first.
+    ///
+    /// Only versions that the selection considers are included, e.g. pre releases only if they are included.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn between(
+        &self,
+        current: &PublishedVersion,
+        latest: &PublishedVersion,
+        selection: &Selection,
+    ) -> Vec<PublishedVersion> {
+        let mut between = self
+            .candidates(selection)
+            .filter(|(version, _, allow_pre_release)| *allow_pre_release || version.pre.is_empty())
+            .map(|(version, original, _)| PublishedVersion {
+                version,
+                original: String::from(original),
+            })
+            .filter(|version| {
+                version.cmp_in(current, selection.order) == Ordering::Greater
+                    && version.cmp_in(latest, selection.order) == Ordering::Less
+            })
+            .collect::<Vec<_>>();
+        between.sort_by(|lhs, rhs| lhs.cmp_in(rhs, selection.order));
+        between
+    }
+
+    /// The versions that the selection considers, and whether they can match as pre releases.
+    fn candidates<'a>(
+        &'a self,
+        selection: &'a Selection,
+    ) -> impl Iterator<Item = (Version, &'a str, bool)> + 'a {
+        self.version.iter().filter_map(move |text| {
+            if selection.is_excluded(text)
+                || !selection.is_published_in_window(self.published.get(text))
+            {
+                return None;
+            }
+            let is_pre_release = match selection.order {
+                VersionOrder::Semver => None,
+                VersionOrder::Maven => Some(MavenVersion::from(text.as_str()).is_pre_release()),
+            };
+            let allow_pre_release = if is_snapshot(text) {
+                if selection.snapshots == Snapshots::Exclude {
+                    return None;
+                }
+                selection
+                    .snapshots
+                    .are_matched(selection.include_pre_releases)
+            } else {
+                // Maven releases can have qualifiers, e.g. `1.0.0.Final` or `31.1-jre`
+                selection.include_pre_releases || is_pre_release == Some(false)
+            };
+            let v = lenient_semver::parse(text.as_str()).ok()?;
+            if selection.is_below_min_version(&v, text) {
+                return None;
+            }
+            Some((v, text.as_str(), allow_pre_release))
+        })
     }
 
     fn find_latest_versions(
         &self,
-        requirements: &[VersionReq],
-        allow_pre_release: bool,
-    ) -> Vec<Option<Version>> {
-        let versions_by_req = self
-            .version
-            .iter()
-            .filter_map(|v| lenient_semver::parse(v.as_str()).ok())
-            .filter_map(|v| {
-                if allow_pre_release {
-                    let version = Version::new(v.major, v.minor, v.patch);
-                    requirements
-                        .iter()
-                        .position(|r| r.matches(&version))
-                        .map(|p| (p, v))
-                } else {
-                    requirements
-                        .iter()
-                        .position(|r| r.matches(&v))
-                        .map(|p| (p, v))
+        requirements: &[Requirement],
+        selection: &Selection,
+    ) -> Vec<Option<PublishedVersion>> {
+        let cmp =
+            |(lhs, lhs_text): &(Version, &str), (rhs, rhs_text): &(Version, &str)| match selection
+                .order
+            {
+                VersionOrder::Semver => cmp_versions(lhs, rhs),
+                VersionOrder::Maven => {
+                    MavenVersion::from(*lhs_text).cmp(&MavenVersion::from(*rhs_text))
                 }
+            };
+
+        let versions_by_req = self
+            .candidates(selection)
+            .filter_map(|(v, text, allow_pre_release)| {
+                let position = requirements
+                    .iter()
+                    .position(|r| r.matches(&v, allow_pre_release));
+                position.map(|p| (p, (v, text)))
             })
             .group_by(|(idx, _)| *idx);
 
         let mut latest = vec![None; requirements.len()];
         for (pos, versions) in &versions_by_req {
-            let new = versions.map(|(_, vs)| vs).max();
+            let new = versions.map(|(_, vs)| vs).max_by(cmp);
             match &mut latest[pos] {
                 Some(v1) => match new {
-                    Some(v2) if v2 > *v1 => {
+                    Some(v2) if cmp(&v2, v1) == Ordering::Greater => {
                         *v1 = v2;
                     }
                     _ => {}
@@ -74,6 +566,112 @@ impl Versions {
         }
 
         latest
+            .into_iter()
+            .map(|latest| {
+                latest.map(|(version, original)| PublishedVersion {
+                    version,
+                    original: String::from(original),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Tags {
+    /// How long ago the metadata was last updated.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn age(&self, now: SystemTime) -> Option<Duration> {
+        let last_updated = parse_timestamp(self.last_updated.as_deref()?)?;
+        Some(now.duration_since(last_updated).unwrap_or_default())
+    }
+
+    /// Keeps the newer tags of both sides.
+    fn union(self, other: Tags) -> Self {
+        fn newer(lhs: Option<String>, rhs: Option<String>) -> Option<String> {
+            match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => {
+                    let parse = |v: &str| lenient_semver::parse(v).ok();
+                    match (parse(&lhs), parse(&rhs)) {
+                        (Some(l), Some(r)) if cmp_versions(&r, &l) == Ordering::Greater => {
+                            Some(rhs)
+                        }
+                        (None, Some(_)) => Some(rhs),
+                        _ => Some(lhs),
+                    }
+                }
+                (lhs, rhs) => lhs.or(rhs),
+            }
+        }
+
+        Tags {
+            latest: newer(self.latest, other.latest),
+            release: newer(self.release, other.release),
+            // timestamps like `20200827153717` order like numbers
+            last_updated: self.last_updated.max(other.last_updated),
+        }
+    }
+}
+
+/// Parses a `<lastUpdated>` timestamp like `20200827153717`, which is in UTC.
+pub(crate) fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    if timestamp.len() != 14 || !timestamp.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let part = |range: std::ops::Range<usize>| timestamp[range].parse::<i64>().ok();
+    let (year, month, day) = (part(0..4)?, part(4..6)?, part(6..8)?);
+    let (hour, minute, second) = (part(8..10)?, part(10..12)?, part(12..14)?);
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+    utc_time((year, month, day), (hour, minute, second))
+}
+
+#[cfg(feature = "runtime")]
+impl TagCheck {
+    /// Whether the tag names a different version than the newest one.
+    pub(crate) fn is_outdated(&self) -> bool {
+        match (&self.newest, lenient_semver::parse(&self.value)) {
+            (Some(newest), Ok(value)) => cmp_versions(&newest.version, &value) != Ordering::Equal,
+            (Some(_), Err(_)) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// The words of the version, e.g. `alpha` and `SNAPSHOT` of `1.0-alpha-2-SNAPSHOT`.
+fn qualifiers(version: &str) -> impl Iterator<Item = &str> {
+    version
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+}
+
+fn is_snapshot(version: &str) -> bool {
+    version.to_ascii_uppercase().ends_with("-SNAPSHOT")
+}
+
+/// Orders like semver, but compares the pre-release case-insensitively,
+/// so that `1.0.0-RC1` is newer than `1.0.0-alpha1`.
+fn cmp_versions(lhs: &Version, rhs: &Version) -> Ordering {
+    fn pre(version: &Version) -> Prerelease {
+        Prerelease::new(&version.pre.as_str().to_ascii_lowercase())
+            .unwrap_or_else(|_| version.pre.clone())
+    }
+    (lhs.major, lhs.minor, lhs.patch)
+        .cmp(&(rhs.major, rhs.minor, rhs.patch))
+        .then_with(|| pre(lhs).cmp(&pre(rhs)))
+        .then_with(|| lhs.cmp(rhs))
+}
+
+#[cfg(test)]
+impl Versions {
+    fn with_tags(mut self, tags: Tags) -> Self {
+        self.tags = tags;
+        self
     }
 }
 
@@ -81,7 +679,11 @@ impl Versions {
 impl From<&str> for Versions {
     fn from(version: &str) -> Self {
         let version = vec![version.to_string()];
-        Self { version }
+        Self {
+            version,
+            tags: Tags::default(),
+            published: HashMap::new(),
+        }
     }
 }
 
@@ -92,7 +694,11 @@ where
 {
     fn from(items: &[T]) -> Self {
         let version = items.iter().map(|x| x.to_string()).collect_vec();
-        Self { version }
+        Self {
+            version,
+            tags: Tags::default(),
+            published: HashMap::new(),
+        }
     }
 }
 
@@ -103,25 +709,152 @@ where
 {
     fn from(items: Vec<T>) -> Self {
         let version = items.into_iter().map(Into::into).collect_vec();
-        Self { version }
+        Self {
+            version,
+            tags: Tags::default(),
+            published: HashMap::new(),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::UNIX_EPOCH;
+    use test_case::test_case;
+
+    fn req(req: &str) -> Requirement {
+        Requirement::from(VersionReq::parse(req).unwrap())
+    }
+
+    fn published(version: &str) -> Option<PublishedVersion> {
+        PublishedVersion::parse(version)
+    }
+
+    const PRE_RELEASES: Selection = Selection {
+        include_pre_releases: true,
+        snapshots: Snapshots::AsPreRelease,
+        order: VersionOrder::Semver,
+        exclude_qualifiers: Vec::new(),
+        ignore_versions: Vec::new(),
+        min_version: None,
+        released_after: None,
+        released_before: None,
+    };
+
+    #[test]
+    fn test_union() {
+        let left = Versions::from(["1.0.0", "1.1.0"].as_ref());
+        let right = Versions::from(["1.1.0", "2.0.0-SNAPSHOT"].as_ref());
+        assert_eq!(
+            left.union(right),
+            Versions::from(["1.0.0", "1.1.0", "2.0.0-SNAPSHOT"].as_ref())
+        );
+    }
+
+    fn tags(latest: Option<&str>, release: Option<&str>, last_updated: Option<&str>) -> Tags {
+        Tags {
+            latest: latest.map(String::from),
+            release: release.map(String::from),
+            last_updated: last_updated.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_union_tags() {
+        let left = Versions::from(Metadata {
+            versions: vec![String::from("1.1.0")],
+            ..Metadata::default()
+        })
+        .with_tags(tags(Some("1.1.0"), Some("1.1.0"), Some("20200101000000")));
+        let right = Versions::from("2.0.0-SNAPSHOT").with_tags(tags(
+            Some("2.0.0-SNAPSHOT"),
+            None,
+            Some("20200202000000"),
+        ));
+        assert_eq!(
+            left.union(right).tags,
+            tags(
+                Some("2.0.0-SNAPSHOT"),
+                Some("1.1.0"),
+                Some("20200202000000")
+            )
+        );
+    }
+
+    #[test_case("20200827153717" => Some(1598542637); "timestamp")]
+    #[test_case("19700101000000" => Some(0); "epoch")]
+    #[test_case("20000229235960" => Some(951868800); "leap day and leap second")]
+    #[test_case("20201327153717" => None; "invalid month")]
+    #[test_case("2020-08-27" => None; "other format")]
+    #[test_case("19691231235959" => None; "before the epoch")]
+    fn test_parse_timestamp(timestamp: &str) -> Option<u64> {
+        let time = parse_timestamp(timestamp)?;
+        Some(time.duration_since(UNIX_EPOCH).unwrap().as_secs())
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_age() {
+        let now = UNIX_EPOCH + Duration::from_secs(1598542637 + 3 * 86400);
+        let tags = tags(None, None, Some("20200827153717"));
+        assert_eq!(tags.age(now), Some(Duration::from_secs(3 * 86400)));
+        let future = UNIX_EPOCH + Duration::from_secs(1598542637 - 60);
+        assert_eq!(tags.age(future), Some(Duration::ZERO));
+        assert_eq!(Tags::default().age(now), None);
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_check_tags() {
+        let versions = Versions::from(["1.0.0", "1.1.0", "1.2.0-SNAPSHOT"].as_ref());
+        let checks = versions
+            .clone()
+            .with_tags(tags(Some("1.2.0-SNAPSHOT"), Some("1.0.0"), None))
+            .check_tags();
+        assert_eq!(
+            checks,
+            vec![
+                TagCheck {
+                    tag: "latest",
+                    value: String::from("1.2.0-SNAPSHOT"),
+                    newest: published("1.2.0-SNAPSHOT"),
+                },
+                TagCheck {
+                    tag: "release",
+                    value: String::from("1.0.0"),
+                    newest: published("1.1.0"),
+                },
+            ]
+        );
+        assert!(!checks[0].is_outdated());
+        assert!(checks[1].is_outdated());
+        assert_eq!(versions.check_tags(), vec![]);
+    }
+
+    #[test]
+    fn test_pre_release_ignores_case() {
+        let versions = Versions::from(["1.0.0-alpha1", "1.0.0-RC1", "1.0.0-beta2"].as_ref());
+        assert_eq!(
+            versions.find_latest_versions(&[Requirement::star()], &PRE_RELEASES),
+            vec![published("1.0.0-RC1")]
+        );
+    }
 
     #[test]
     fn test_empty_reqs() {
         let versions = Versions::from("1.0.0");
-        assert_eq!(versions.find_latest_versions(&[], false), vec![]);
+        assert_eq!(
+            versions.find_latest_versions(&[], &Selection::default()),
+            vec![]
+        );
     }
 
     #[test]
     fn test_empty_versions() {
         let versions = Versions::from(Vec::<String>::new());
         assert_eq!(
-            versions.find_latest_versions(&[VersionReq::STAR], false),
+            versions.find_latest_versions(&[Requirement::star()], &Selection::default()),
             vec![None]
         );
     }
@@ -130,8 +863,8 @@ mod tests {
     fn match_single_version() {
         let versions = Versions::from("1.0.0");
         assert_eq!(
-            versions.find_latest_versions(&[VersionReq::STAR], false),
-            vec![Some(Version::new(1, 0, 0))]
+            versions.find_latest_versions(&[Requirement::star()], &Selection::default()),
+            vec![published("1.0.0")]
         );
     }
 
@@ -139,8 +872,8 @@ mod tests {
     fn select_latest() {
         let versions = Versions::from(["1.0.0", "1.3.37"].as_ref());
         assert_eq!(
-            versions.find_latest_versions(&[VersionReq::STAR], false),
-            vec![Some(Version::new(1, 3, 37))]
+            versions.find_latest_versions(&[Requirement::star()], &Selection::default()),
+            vec![published("1.3.37")]
         );
     }
 
@@ -148,8 +881,8 @@ mod tests {
     fn lenient_version_parsing() {
         let versions = Versions::from(["1.0.0", "1.337"].as_ref());
         assert_eq!(
-            versions.find_latest_versions(&[VersionReq::STAR], false),
-            vec![Some(Version::new(1, 337, 0))]
+            versions.find_latest_versions(&[Requirement::star()], &Selection::default()),
+            vec![published("1.337")]
         );
     }
 
@@ -157,14 +890,8 @@ mod tests {
     fn group_on_reqs() {
         let versions = Versions::from(["1.0.0", "1.2.3", "2.0.0", "2.1337.42"].as_ref());
         assert_eq!(
-            versions.find_latest_versions(
-                &[
-                    VersionReq::parse("1.x").unwrap(),
-                    VersionReq::parse("2.x").unwrap()
-                ],
-                false
-            ),
-            vec![Some(Version::new(1, 2, 3)), Some(Version::new(2, 1337, 42))]
+            versions.find_latest_versions(&[req("1.x"), req("2.x")], &Selection::default()),
+            vec![published("1.2.3"), published("2.1337.42")]
         );
     }
 
@@ -173,18 +900,10 @@ mod tests {
         let versions = Versions::from(["1.0.0", "2.0.0"].as_ref());
         assert_eq!(
             versions.find_latest_versions(
-                &[
-                    VersionReq::parse("1.x").unwrap(),
-                    VersionReq::parse("42.x").unwrap(),
-                    VersionReq::parse("2.x").unwrap()
-                ],
-                false
+                &[req("1.x"), req("42.x"), req("2.x")],
+                &Selection::default()
             ),
-            vec![
-                Some(Version::new(1, 0, 0)),
-                None,
-                Some(Version::new(2, 0, 0))
-            ]
+            vec![published("1.0.0"), None, published("2.0.0")]
         );
     }
 
@@ -192,14 +911,8 @@ mod tests {
     fn skip_overshadowed_reqs() {
         let versions = Versions::from(["1.0.42", "1.2.3"].as_ref());
         assert_eq!(
-            versions.find_latest_versions(
-                &[
-                    VersionReq::parse("^1").unwrap(),
-                    VersionReq::parse("1.2.3").unwrap(),
-                ],
-                false
-            ),
-            vec![Some(Version::new(1, 2, 3)), None,]
+            versions.find_latest_versions(&[req("^1"), req("1.2.3"),], &Selection::default()),
+            vec![published("1.2.3"), None,]
         );
     }
 
@@ -207,8 +920,8 @@ mod tests {
     fn skip_prerelease() {
         let versions = Versions::from(["1.0.0", "1.1.0-alpha01"].as_ref());
         assert_eq!(
-            versions.find_latest_versions(&[VersionReq::parse("^1").unwrap(),], false),
-            vec![Some(Version::new(1, 0, 0))]
+            versions.find_latest_versions(&[req("^1"),], &Selection::default()),
+            vec![published("1.0.0")]
         );
     }
 
@@ -216,8 +929,353 @@ mod tests {
     fn include_prerelease() {
         let versions = Versions::from(["1.0.0", "1.1.0-alpha01"].as_ref());
         assert_eq!(
-            versions.find_latest_versions(&[VersionReq::parse("^1").unwrap(),], true),
-            vec![Some(Version::parse("1.1.0-alpha01").unwrap())]
+            versions.find_latest_versions(&[req("^1"),], &PRE_RELEASES),
+            vec![published("1.1.0-alpha01")]
+        );
+    }
+
+    #[test_case(false, Snapshots::AsPreRelease => published("1.3.0"); "without pre releases")]
+    #[test_case(true, Snapshots::AsPreRelease => published("1.5.0-SNAPSHOT"); "with pre releases")]
+    #[test_case(false, Snapshots::Include => published("1.5.0-SNAPSHOT"); "include snapshots")]
+    #[test_case(true, Snapshots::Exclude => published("1.4.0-alpha01"); "exclude snapshots")]
+    #[test_case(false, Snapshots::Exclude => published("1.3.0"); "exclude snapshots without pre releases")]
+    fn test_snapshots(allow_pre_release: bool, snapshots: Snapshots) -> Option<PublishedVersion> {
+        let versions = Versions::from(["1.3.0", "1.4.0-alpha01", "1.5.0-SNAPSHOT"].as_ref());
+        versions
+            .find_latest_versions(
+                &[Requirement::star()],
+                &Selection {
+                    include_pre_releases: allow_pre_release,
+                    snapshots,
+                    ..Selection::default()
+                },
+            )
+            .remove(0)
+    }
+
+    #[test_case(VersionOrder::Semver, false => Some(String::from("2.3.4.RELEASE")); "semver")]
+    #[test_case(VersionOrder::Semver, true => Some(String::from("2.3.5.M1")); "semver with pre releases")]
+    #[test_case(VersionOrder::Maven, false => Some(String::from("2.3.4.SR1")); "maven")]
+    #[test_case(VersionOrder::Maven, true => Some(String::from("2.3.5.M1")); "maven with pre releases")]
+    fn test_maven_order(order: VersionOrder, include_pre_releases: bool) -> Option<String> {
+        let versions = Versions::from(["2.3.4.SR1", "2.3.4.RELEASE", "2.3.5.M1"].as_ref());
+        let selection = Selection {
+            include_pre_releases,
+            order,
+            ..Selection::default()
+        };
+        versions
+            .find_latest_versions(&[Requirement::star()], &selection)
+            .remove(0)
+            .map(|version| version.to_string())
+    }
+
+    #[test]
+    fn test_maven_order_of_qualifiers() {
+        let versions =
+            Versions::from(["1.0.0.CR1", "1.0.0.Final", "1.0.0.Alpha2", "31.1-jre"].as_ref());
+        let selection = Selection {
+            order: VersionOrder::Maven,
+            ..Selection::default()
+        };
+        assert_eq!(
+            versions.find_latest_versions(&[req("1"), Requirement::star()], &selection),
+            vec![published("1.0.0.Final"), published("31.1-jre")]
+        );
+    }
+
+    #[test_case(&["alpha"] => Some(String::from("1.1.0-RC1")); "alpha")]
+    #[test_case(&["RC", "Alpha"] => Some(String::from("1.1.0-beta2")); "ignores case")]
+    #[test_case(&["alpha", "beta", "rc"] => Some(String::from("1.0.9.Final")); "all pre releases")]
+    #[test_case(&["final"] => Some(String::from("1.1.0-RC1")); "release qualifier")]
+    #[test_case(&["a"] => Some(String::from("1.1.0-RC1")); "only complete words")]
+    fn test_exclude_qualifiers(qualifiers: &[&str]) -> Option<String> {
+        let versions = Versions::from(
+            [
+                "1.0.0",
+                "1.1.0-alpha01",
+                "1.1.0-beta2",
+                "1.1.0-RC1",
+                "1.0.9.Final",
+            ]
+            .as_ref(),
+        );
+        let selection = Selection {
+            include_pre_releases: true,
+            exclude_qualifiers: qualifiers.iter().map(|q| String::from(*q)).collect(),
+            ..Selection::default()
+        };
+        versions
+            .find_latest_versions(&[Requirement::star()], &selection)
+            .remove(0)
+            .map(|version| version.to_string())
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case(&[r"^2\.17\.0$"] => Some(String::from("2.16.0")); "withdrawn release")]
+    #[test_case(&[r"^2\.1[67]\."] => Some(String::from("2.15.1")); "character class")]
+    #[test_case(&[r"^2\.1$"] => Some(String::from("2.17.0")); "anchored pattern")]
+    #[test_case(&[r"2\.16", r"2\.17"] => Some(String::from("2.15.1")); "multiple patterns")]
+    fn test_ignore_versions(patterns: &[&str]) -> Option<String> {
+        let versions = Versions::from(["2.15.1", "2.16.0", "2.17.0"].as_ref());
+        let selection = Selection {
+            ignore_versions: patterns.iter().map(|p| Regex::new(p).unwrap()).collect(),
+            ..Selection::default()
+        };
+        versions
+            .find_latest_versions(&[Requirement::star()], &selection)
+            .remove(0)
+            .map(|version| version.to_string())
+    }
+
+    #[test_case(VersionOrder::Semver, "1.0" => vec![Some(String::from("1.2.0")), Some(String::from("2.0.0"))]; "semver")]
+    #[test_case(VersionOrder::Semver, "1.2.0" => vec![Some(String::from("1.2.0")), Some(String::from("2.0.0"))]; "inclusive")]
+    #[test_case(VersionOrder::Semver, "1.5" => vec![None, Some(String::from("2.0.0"))]; "nothing left")]
+    #[test_case(VersionOrder::Maven, "1.2.0.Final" => vec![Some(String::from("1.2.0")), Some(String::from("2.0.0"))]; "maven")]
+    #[test_case(VersionOrder::Maven, "1.2.0.SP1" => vec![None, Some(String::from("2.0.0"))]; "maven service pack")]
+    fn test_min_version(order: VersionOrder, min_version: &str) -> Vec<Option<String>> {
+        let versions = Versions::from(["0.1.0", "0.9.3", "1.1.0", "1.2.0", "2.0.0"].as_ref());
+        let selection = Selection {
+            order,
+            min_version: Some(String::from(min_version)),
+            ..Selection::default()
+        };
+        let requirements = [req("<2"), Requirement::star()];
+        versions
+            .find_latest_versions(&requirements, &selection)
+            .into_iter()
+            .map(|version| version.map(|v| v.to_string()))
+            .collect()
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case(Some(15), None => Some(String::from("1.3.0")); "released after")]
+    #[test_case(None, Some(25) => Some(String::from("1.2.0")); "released before")]
+    #[test_case(Some(15), Some(21) => Some(String::from("1.1.0")); "window")]
+    #[test_case(Some(35), None => None; "nothing left")]
+    #[test_case(None, None => Some(String::from("1.4.0")); "no window")]
+    fn test_publication_window(after: Option<u64>, before: Option<u64>) -> Option<String> {
+        let day = |days| UNIX_EPOCH + Duration::from_secs(days * 24 * 60 * 60);
+        let dates = [("1.0.0", 10), ("1.1.0", 20), ("1.2.0", 22), ("1.3.0", 30)]
+            .iter()
+            .map(|&(version, days)| (String::from(version), day(days)))
+            .collect();
+        let versions = Versions::from(["1.0.0", "1.1.0", "1.2.0", "1.3.0", "1.4.0"].as_ref())
+            .with_publication_dates(dates);
+        let selection = Selection {
+            released_after: after.map(day),
+            released_before: before.map(day),
+            ..Selection::default()
+        };
+        versions
+            .find_latest_versions(&[Requirement::star()], &selection)
+            .remove(0)
+            .map(|version| version.to_string())
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_newest_ignores_publication_window() {
+        let dates = vec![(String::from("1.0.0"), UNIX_EPOCH)]
+            .into_iter()
+            .collect();
+        let versions =
+            Versions::from(vec!["1.0.0", "1.1.0", "2.0.0-rc1"]).with_publication_dates(dates);
+        let selection = Selection {
+            released_before: Some(UNIX_EPOCH),
+            ..Selection::default()
+        };
+        assert_eq!(versions.newest_selected(&selection), published("1.1.0"));
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_publication_dates_survive_union() {
+        let date = UNIX_EPOCH + Duration::from_secs(86400);
+        let dates = vec![(String::from("1.0.0"), date)].into_iter().collect();
+        let versions = Versions::from(vec!["1.0.0"])
+            .with_publication_dates(dates)
+            .union(Versions::from(vec!["1.1.0"]));
+        assert_eq!(versions.published("1.0.0"), Some(date));
+        assert_eq!(versions.published("1.1.0"), None);
+    }
+
+    #[test]
+    fn test_include_snapshots_skips_pre_releases() {
+        const INCLUDE_SNAPSHOTS: Selection = Selection {
+            include_pre_releases: false,
+            snapshots: Snapshots::Include,
+            order: VersionOrder::Semver,
+            exclude_qualifiers: Vec::new(),
+            ignore_versions: Vec::new(),
+            min_version: None,
+            released_after: None,
+            released_before: None,
+        };
+        let versions = Versions::from(["1.3.0", "1.4.0-alpha01", "1.4.0-SNAPSHOT"].as_ref());
+        assert_eq!(
+            versions.find_latest_versions(&[Requirement::star()], &INCLUDE_SNAPSHOTS),
+            vec![published("1.4.0-SNAPSHOT")]
         );
+        let versions = Versions::from(["1.3.0", "1.4.0-alpha01"].as_ref());
+        assert_eq!(
+            versions.find_latest_versions(&[Requirement::star()], &INCLUDE_SNAPSHOTS),
+            vec![published("1.3.0")]
+        );
+    }
+
+    #[test_case(&["1.3.3.7", "1.3.3"] => Some(String::from("1.3.3.7")); "four numbers")]
+    #[test_case(&["2.4.0.Final", "2.3.1.Final"] => Some(String::from("2.4.0.Final")); "release qualifier")]
+    #[test_case(&["1.337", "1.2"] => Some(String::from("1.337")); "two numbers")]
+    #[test_case(&["1.3.0", "1.4-SNAPSHOT"] => Some(String::from("1.4-SNAPSHOT")); "snapshot")]
+    fn test_published_version(versions: &[&str]) -> Option<String> {
+        Versions::from(versions)
+            .find_latest_versions(&[Requirement::star()], &PRE_RELEASES)
+            .remove(0)
+            .map(|version| version.to_string())
+    }
+
+    #[test_case(&["1.4.2"] => published("1.4.1"); "excluded release")]
+    #[test_case(&["1.4.2", "1.4.1"] => published("1.3.0"); "multiple exclusions")]
+    #[test_case(&["1.3.0.Final"] => published("1.4.2"); "other version")]
+    #[test_case(&["1.4.2", "1.4.1", "1.3.0"] => None; "everything excluded")]
+    fn test_excluded_versions(excluded: &[&str]) -> Option<PublishedVersion> {
+        let versions = Versions::from(["1.3.0", "1.4.1", "1.4.2", "2.0.0"].as_ref());
+        let requirement = Requirement {
+            excluded: excluded.iter().filter_map(|v| published(v)).collect(),
+            ..req("^1")
+        };
+        versions
+            .find_latest_versions(&[requirement], &Selection::default())
+            .remove(0)
+    }
+
+    #[test_case(&["~1.1", "~1.3"] => published("1.3.1"); "newest alternative")]
+    #[test_case(&["~1.3", "~1.1"] => published("1.3.1"); "order of alternatives")]
+    #[test_case(&["~1.1", "~1.5"] => published("1.1.4"); "unmatched alternative")]
+    #[test_case(&["~1.5", "~1.6"] => None; "no alternative matches")]
+    fn test_alternatives(alternatives: &[&str]) -> Option<PublishedVersion> {
+        let versions = Versions::from(["1.1.4", "1.2.0", "1.3.1", "1.4.0"].as_ref());
+        let requirement = Requirement {
+            alternatives: alternatives
+                .iter()
+                .map(|r| VersionReq::parse(r).unwrap())
+                .collect(),
+            excluded: Vec::new(),
+        };
+        versions
+            .find_latest_versions(&[requirement], &Selection::default())
+            .remove(0)
+    }
+
+    #[test]
+    fn test_excluded_pre_release() {
+        let versions = Versions::from(["1.3.0", "1.4.0-RC1", "1.4.0-RC2"].as_ref());
+        let requirement = Requirement {
+            excluded: vec![published("1.4.0-RC2").unwrap()],
+            ..req("^1.4")
+        };
+        assert_eq!(
+            versions.find_latest_versions(&[requirement], &PRE_RELEASES),
+            vec![published("1.4.0-RC1")]
+        );
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_unparseable() {
+        let versions =
+            Versions::from(["1.0.0", "r09", "2023.1", "release-2023-01", "abc"].as_ref());
+        assert_eq!(
+            versions.unparseable(&Selection::default()),
+            vec!["r09", "release-2023-01", "abc"]
+        );
+        let selection = Selection {
+            ignore_versions: vec![Regex::new("^r").unwrap()],
+            ..Selection::default()
+        };
+        assert_eq!(versions.unparseable(&selection), vec!["abc"]);
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case("1.2.3", "2.0.0", VersionOrder::Semver => Some(Bump::Major); "major")]
+    #[test_case("1.2.3", "1.3.0", VersionOrder::Semver => Some(Bump::Minor); "minor")]
+    #[test_case("1.2.3", "1.2.4", VersionOrder::Semver => Some(Bump::Patch); "patch")]
+    #[test_case("1.2.3-rc1", "1.2.3", VersionOrder::Semver => Some(Bump::PreRelease); "pre release")]
+    #[test_case("1.2.3", "1.2.3", VersionOrder::Semver => None; "same version")]
+    #[test_case("1.3.0", "1.2.3", VersionOrder::Semver => None; "older version")]
+    #[test_case("2.3.4.RELEASE", "2.3.4.SR1", VersionOrder::Maven => Some(Bump::PreRelease); "maven qualifier")]
+    #[test_case("2.3.4.SR1", "2.3.4.RELEASE", VersionOrder::Maven => None; "older maven qualifier")]
+    fn test_bump(current: &str, newer: &str, order: VersionOrder) -> Option<Bump> {
+        published(current)
+            .unwrap()
+            .bump_to(&published(newer).unwrap(), order)
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case(&[], false => vec!["2.0.0", "1.3.0", "1.2.0", "1.0.0"]; "all releases")]
+    #[test_case(&[], true => vec!["2.0.0", "1.3.0", "1.2.0", "1.2.0-rc1", "1.0.0"]; "with pre releases")]
+    #[test_case(&["~1.2", ">=2"], false => vec!["2.0.0", "1.2.0"]; "requirements")]
+    #[test_case(&["<1"], false => Vec::<String>::new(); "nothing matches")]
+    fn test_matching(requirements: &[&str], include_pre_releases: bool) -> Vec<String> {
+        let versions = Versions::from(["1.3.0", "1.2.0", "1.0.0", "1.2.0-rc1", "2.0.0"].as_ref());
+        let selection = Selection {
+            include_pre_releases,
+            ..Selection::default()
+        };
+        let requirements = requirements.iter().map(|r| req(r)).collect::<Vec<_>>();
+        versions
+            .matching(&requirements, &selection)
+            .into_iter()
+            .map(|version| version.to_string())
+            .collect()
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case(false, VersionOrder::Semver => vec!["1.1.0", "1.2.0"]; "releases")]
+    #[test_case(true, VersionOrder::Semver => vec!["1.1.0", "1.2.0-rc1", "1.2.0.SP1", "1.2.0"]; "with pre releases")]
+    #[test_case(false, VersionOrder::Maven => vec!["1.1.0", "1.2.0", "1.2.0.SP1"]; "maven")]
+    fn test_between(include_pre_releases: bool, order: VersionOrder) -> Vec<String> {
+        let versions = Versions::from(
+            [
+                "1.3.0",
+                "1.2.0",
+                "1.0.0",
+                "1.1.0",
+                "1.2.0-rc1",
+                "1.2.0.SP1",
+                "0.9.0",
+                "2.0.0",
+            ]
+            .as_ref(),
+        );
+        let selection = Selection {
+            include_pre_releases,
+            order,
+            ..Selection::default()
+        };
+        let current = published("1.0.0").unwrap();
+        let latest = published("1.3.0").unwrap();
+        versions
+            .between(&current, &latest, &selection)
+            .into_iter()
+            .map(|version| version.to_string())
+            .collect()
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test_case("1.0.0", VersionOrder::Semver => false; "semver release")]
+    #[test_case("1.0.0-rc1", VersionOrder::Semver => true; "semver pre release")]
+    #[test_case("1.0.0.Final", VersionOrder::Maven => false; "maven release")]
+    #[test_case("1.0.0.CR1", VersionOrder::Maven => true; "maven pre release")]
+    fn test_published_version_is_pre_release(version: &str, order: VersionOrder) -> bool {
+        published(version).unwrap().is_pre_release(order)
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_published_version_is_snapshot() {
+        assert!(published("1.4-SNAPSHOT").unwrap().is_snapshot());
+        assert!(!published("1.4.0-alpha01").unwrap().is_snapshot());
     }
 }
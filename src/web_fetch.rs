@@ -0,0 +1,99 @@
+use super::{Fetch, FetchRequest, FetchResponse};
+use async_trait::async_trait;
+use js_sys::{Array, Function, Promise, Reflect, Uint8Array};
+use std::convert::TryFrom;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortSignal, Headers, Request, RequestInit, Response};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Sends the requests with the global `fetch` of the JavaScript host, e.g. a browser window, a web worker, or Node.js.
+///
+/// The host follows redirects, and the browser sends its own `User-Agent` and adds the CORS checks,
+/// so that a repository must allow the origin of the page to be read from it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WebFetch;
+
+impl WebFetch {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait(?Send)]
+impl Fetch for WebFetch {
+    async fn fetch(&self, request: FetchRequest) -> Result<FetchResponse, BoxError> {
+        let headers = Headers::new().map_err(js_error)?;
+        for (name, value) in &request.headers {
+            headers.append(name, value).map_err(js_error)?;
+        }
+        let init = RequestInit::new();
+        init.set_method(request.method);
+        init.set_headers(&headers);
+        if let Some(timeout) = request.timeout {
+            let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+            init.set_signal(Some(&AbortSignal::timeout_with_u32(millis)));
+        }
+        let js_request =
+            Request::new_with_str_and_init(request.url.as_str(), &init).map_err(js_error)?;
+
+        // windows and workers have different types, but both have a global fetch
+        let fetch = Reflect::get(&js_sys::global(), &JsValue::from_str("fetch"))
+            .map_err(js_error)?
+            .dyn_into::<Function>()
+            .map_err(|_| BoxError::from("the host has no fetch function"))?;
+        let promise = fetch
+            .call1(&JsValue::UNDEFINED, &js_request)
+            .map_err(js_error)?;
+        let response = JsFuture::from(Promise::from(promise))
+            .await
+            .map_err(js_error)?
+            .dyn_into::<Response>()
+            .map_err(js_error)?;
+
+        let headers = read_headers(&response.headers())?;
+        // the body is not read if its announced length is over the limit already
+        let too_large = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, length)| length.trim().parse::<u64>().ok())
+            .is_some_and(|length| length > request.max_response_size);
+        let body = if request.method == "HEAD" || too_large {
+            Vec::new()
+        } else {
+            let buffer = JsFuture::from(response.array_buffer().map_err(js_error)?)
+                .await
+                .map_err(js_error)?;
+            Uint8Array::new(&buffer).to_vec()
+        };
+        Ok(FetchResponse {
+            status: response.status(),
+            headers,
+            body,
+        })
+    }
+}
+
+/// The names and values of the headers, which are iterated as `[name, value]` arrays.
+fn read_headers(headers: &Headers) -> Result<Vec<(String, String)>, BoxError> {
+    let entries = js_sys::try_iter(headers)
+        .map_err(js_error)?
+        .ok_or_else(|| BoxError::from("the headers cannot be iterated"))?;
+    let mut read = Vec::new();
+    for entry in entries {
+        let entry = Array::from(&entry.map_err(js_error)?);
+        let name = entry.get(0).as_string().unwrap_or_default();
+        let value = entry.get(1).as_string().unwrap_or_default();
+        read.push((name, value));
+    }
+    Ok(read)
+}
+
+/// The message of a JavaScript exception, which cannot be sent to other threads itself.
+fn js_error(value: JsValue) -> BoxError {
+    match value.dyn_ref::<js_sys::Error>() {
+        Some(error) => BoxError::from(String::from(error.message())),
+        None => BoxError::from(value.as_string().unwrap_or_else(|| format!("{:?}", value))),
+    }
+}
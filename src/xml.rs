@@ -0,0 +1,158 @@
+use xmlparser::{ElementEnd as EE, Error, Token, Tokenizer};
+
+/// A minimal element tree, used for documents that are not streamed like the metadata.
+///
+/// Attributes and namespaces are ignored, only local names are kept.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) text: String,
+    pub(crate) children: Vec<Element>,
+}
+
+impl Element {
+    /// Parses the input and returns the root element.
+    ///
+    /// An input without any element returns an empty element.
+    pub(crate) fn parse(input: &str) -> Result<Self, Error> {
+        let mut stack: Vec<Element> = Vec::new();
+        let mut root = None;
+
+        for token in Tokenizer::from(input) {
+            match token? {
+                Token::ElementStart { local, .. } => stack.push(Element {
+                    name: String::from(local.as_str()),
+                    ..Element::default()
+                }),
+                Token::ElementEnd {
+                    end: EE::Close(_, _) | EE::Empty,
+                    ..
+                } => {
+                    if let Some(element) = stack.pop() {
+                        match stack.last_mut() {
+                            Some(parent) => parent.children.push(element),
+                            None => root = root.or(Some(element)),
+                        }
+                    }
+                }
+                Token::Text { text } => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&unescape(text.as_str()));
+                    }
+                }
+                Token::Cdata { text, .. } => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(text.as_str());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(root.unwrap_or_default())
+    }
+
+    pub(crate) fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    pub(crate) fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Follows the path of child elements, e.g. `["versioning", "versions"]`.
+    pub(crate) fn find(&self, path: &[&str]) -> Option<&Element> {
+        path.iter()
+            .try_fold(self, |element, name| element.child(name))
+    }
+
+    /// The trimmed text of this element, or `None` if it is empty.
+    pub(crate) fn text(&self) -> Option<&str> {
+        Some(self.text.trim()).filter(|t| !t.is_empty())
+    }
+
+    pub(crate) fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).and_then(Element::text)
+    }
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let replacement = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            }?;
+            Some((ch, end))
+        });
+        match replacement {
+            Some((ch, end)) => {
+                result.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_parse_tree() {
+        let root = Element::parse(
+            r#"<?xml version="1.0"?>
+            <settings xmlns="http://maven.apache.org/SETTINGS/1.0.0">
+              <servers>
+                <server><id>one</id></server>
+                <server><id>two</id><empty/></server>
+              </servers>
+            </settings>"#,
+        )
+        .unwrap();
+        assert_eq!(root.name, "settings");
+        let ids = root
+            .find(&["servers"])
+            .unwrap()
+            .children("server")
+            .filter_map(|s| s.child_text("id"))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(Element::parse("").unwrap(), Element::default());
+    }
+
+    #[test_case("<a>foo</a>", Some("foo"); "plain")]
+    #[test_case("<a>  foo  </a>", Some("foo"); "trimmed")]
+    #[test_case("<a><![CDATA[<foo>]]></a>", Some("<foo>"); "cdata")]
+    #[test_case("<a>a&amp;b&lt;c&#62;&#x41;</a>", Some("a&b<c>A"); "entities")]
+    #[test_case("<a>a&b</a>", Some("a&b"); "bare ampersand")]
+    #[test_case("<a>   </a>", None; "whitespace")]
+    fn test_text(input: &str, expected: Option<&str>) {
+        let element = Element::parse(input).unwrap_or_default();
+        assert_eq!(element.text(), expected);
+    }
+}